@@ -126,6 +126,8 @@ pub fn generate_device_features(
     let mut vk_struct_name_idents = Vec::with_capacity(total_features.len());
     // match arms for function feature registration
     let mut register_feature_match_arms = Vec::with_capacity(total_features.len());
+    // match arms for function feature support query
+    let mut supported_feature_match_arms = Vec::with_capacity(total_features.len());
 
     for (feature_name, feature_detail) in total_features {
         let ash_ty_name = format_ident!(
@@ -209,6 +211,11 @@ pub fn generate_device_features(
                     feature.register(&mut feature2.features);
                 }
             ));
+            supported_feature_match_arms.push(quote!(
+                FeatureType::DeviceFeatures(feature) => {
+                    physical_device.get_physical_device_features().contains(feature)
+                }
+            ));
         } else {
             sub_feature_enum_definition = quote!(
                 #sub_feature_enum_definition
@@ -239,6 +246,11 @@ pub fn generate_device_features(
                     feature.register(unsafe { std::mem::transmute(u) });
                 }
             ));
+            supported_feature_match_arms.push(quote!(
+                FeatureType::#feature_name_ident_logical(feature) => {
+                    is_feature2_supported(physical_device, feature)
+                }
+            ));
         }
         sub_feature_enum_definitions.push(sub_feature_enum_definition);
         vk_struct_name_idents.push(ash_ty_name);
@@ -278,7 +290,19 @@ pub fn generate_device_features(
             pub device: std::sync::Arc<crate::device::Device>,
             pub(crate) _p: std::marker::PhantomData<usize>,
         }
-        pub(crate) fn register_features(features: &rustc_hash::FxHashSet<FeatureType>) -> ash::vk::PhysicalDeviceFeatures2 {
+        fn is_feature2_supported<T>(physical_device: &crate::physical_device::PhysicalDevice, feature: &T) -> bool
+        where
+            T: SubPhysicalFeature + Eq + std::hash::Hash,
+            T::VkStruct: VkDeviceFeature<SubFeatureEnumTy = T>,
+        {
+            physical_device.get_physical_device_features2::<T>().contains(feature)
+        }
+        pub(crate) fn is_feature_supported(physical_device: &crate::physical_device::PhysicalDevice, feature: &FeatureType) -> bool {
+            match feature {
+                #(#supported_feature_match_arms)*
+            }
+        }
+        pub(crate) fn register_features(features: &rustc_hash::FxHashSet<FeatureType>) -> (ash::vk::PhysicalDeviceFeatures2, Vec<VkFeatureUnion>) {
             struct VkStructHeader {
                 pub _s_type: ash::vk::StructureType,
                 pub p_next: *mut std::ffi::c_void,
@@ -291,14 +315,15 @@ pub fn generate_device_features(
                 }
             };
 
-            map.into_iter().for_each(|(_, mut feature_union)| {
+            let mut feature_unions: Vec<VkFeatureUnion> = map.into_values().collect();
+            feature_unions.iter_mut().for_each(|feature_union| {
                 unsafe {
-                    let header = &mut feature_union as *mut _ as *mut VkStructHeader;
+                    let header = feature_union as *mut _ as *mut VkStructHeader;
                     (*header).p_next = feature2.p_next;
                     feature2.p_next = header as *mut _;
                 }
             });
-            feature2
+            (feature2, feature_unions)
         }
     };
     res
@@ -0,0 +1,376 @@
+use crate::buffer::Buffer;
+use crate::command::command_buffer::RenderPassScope::OUTSIDE;
+use crate::command::command_buffer::State::RECORDING;
+use crate::command::command_buffer::{CommandBuffer, Level};
+use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceAccelerationStructureFeaturesKHR::AccelerationStructure as AccelerationStructureFeature;
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::KhrAccelerationStructure;
+use ash::vk::Handle;
+use std::sync::Arc;
+
+pub struct AccelerationStructure {
+    pub buffer: Arc<Buffer>,
+    pub(crate) vk_acceleration_structure: ash::vk::AccelerationStructureKHR,
+    pub ty: ash::vk::AccelerationStructureTypeKHR,
+}
+
+impl AccelerationStructure {
+    pub fn builder(
+        _extension: DeviceExtension<{ KhrAccelerationStructure }>,
+        _feature: Feature<{ AccelerationStructureFeature.into() }>,
+        buffer: Arc<Buffer>,
+    ) -> AccelerationStructureBuilder {
+        AccelerationStructureBuilder {
+            buffer,
+            offset: 0,
+            size: 0,
+            ty: ash::vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL,
+        }
+    }
+    // sizes of the acceleration structure and the scratch buffers needed to build `geometries`
+    pub fn get_build_sizes(
+        _extension: &DeviceExtension<{ KhrAccelerationStructure }>,
+        feature: &Feature<{ AccelerationStructureFeature.into() }>,
+        ty: ash::vk::AccelerationStructureTypeKHR,
+        flags: ash::vk::BuildAccelerationStructureFlagsKHR,
+        geometries: &[AccelerationStructureGeometry],
+        max_primitive_counts: &[u32],
+    ) -> ash::vk::AccelerationStructureBuildSizesInfoKHR {
+        if geometries.len() != max_primitive_counts.len() {
+            panic!("geometries and max_primitive_counts must have the same length");
+        }
+        check_geometries(ty, geometries);
+        let vk_geometries = geometries
+            .iter()
+            .map(AccelerationStructureGeometry::to_ash)
+            .collect::<Vec<_>>();
+        let build_info = ash::vk::AccelerationStructureBuildGeometryInfoKHR::builder()
+            .ty(ty)
+            .flags(flags)
+            .mode(ash::vk::BuildAccelerationStructureModeKHR::BUILD)
+            .geometries(vk_geometries.as_slice());
+        unsafe {
+            // Host Synchronization: none
+            feature
+                .device
+                .acceleration_structure_loader()
+                .get_acceleration_structure_build_sizes(
+                    ash::vk::AccelerationStructureBuildTypeKHR::DEVICE,
+                    &build_info,
+                    max_primitive_counts,
+                )
+        }
+    }
+    pub fn get_device_address(&self) -> ash::vk::DeviceAddress {
+        let info = ash::vk::AccelerationStructureDeviceAddressInfoKHR::builder()
+            .acceleration_structure(self.vk_acceleration_structure);
+        unsafe {
+            // Host Synchronization: none
+            self.buffer
+                .device
+                .acceleration_structure_loader()
+                .get_acceleration_structure_device_address(&info)
+        }
+    }
+}
+
+impl Drop for AccelerationStructure {
+    fn drop(&mut self) {
+        // TODO VUID-vkDestroyAccelerationStructureKHR-accelerationStructure-02442
+        // Host Synchronization: accelerationStructure
+        unsafe {
+            self.buffer
+                .device
+                .acceleration_structure_loader()
+                .destroy_acceleration_structure(self.vk_acceleration_structure, None);
+        }
+    }
+}
+
+pub struct AccelerationStructureBuilder {
+    buffer: Arc<Buffer>,
+    offset: ash::vk::DeviceSize,
+    size: ash::vk::DeviceSize,
+    ty: ash::vk::AccelerationStructureTypeKHR,
+}
+
+impl AccelerationStructureBuilder {
+    pub fn offset(mut self, offset: ash::vk::DeviceSize) -> Self {
+        self.offset = offset;
+        self
+    }
+    pub fn size(mut self, size: ash::vk::DeviceSize) -> Self {
+        self.size = size;
+        self
+    }
+    pub fn ty(mut self, ty: ash::vk::AccelerationStructureTypeKHR) -> Self {
+        self.ty = ty;
+        self
+    }
+    pub fn build(self) -> Result<Arc<AccelerationStructure>, ash::vk::Result> {
+        // DONE VUID-vkCreateAccelerationStructureKHR-accelerationStructure-03611
+        // MUST VUID-VkAccelerationStructureCreateInfoKHR-offset-03616
+        if self.offset + self.size > self.buffer.size {
+            panic!("VUID-VkAccelerationStructureCreateInfoKHR-offset-03616");
+        }
+        // MUST VUID-VkAccelerationStructureCreateInfoKHR-buffer-03614
+        if !self
+            .buffer
+            .usage
+            .contains(ash::vk::BufferUsageFlags::ACCELERATION_STRUCTURE_STORAGE_KHR)
+        {
+            panic!("VUID-VkAccelerationStructureCreateInfoKHR-buffer-03614");
+        }
+        // MUST VUID-VkAccelerationStructureCreateInfoKHR-offset-03734
+        if self.offset % 256 != 0 {
+            panic!("VUID-VkAccelerationStructureCreateInfoKHR-offset-03734");
+        }
+        let create_info = ash::vk::AccelerationStructureCreateInfoKHR::builder()
+            .buffer(self.buffer.ash_vk_buffer)
+            .offset(self.offset)
+            .size(self.size)
+            .ty(self.ty);
+        // Host Synchronization: none
+        let vk_acceleration_structure = unsafe {
+            self.buffer
+                .device
+                .acceleration_structure_loader()
+                .create_acceleration_structure(&create_info, None)?
+        };
+        Ok(Arc::new(AccelerationStructure {
+            buffer: self.buffer,
+            vk_acceleration_structure,
+            ty: self.ty,
+        }))
+    }
+}
+
+pub enum AccelerationStructureGeometryData {
+    // index_data of None means the triangles are not indexed
+    Triangles {
+        vertex_format: ash::vk::Format,
+        vertex_data: (Arc<Buffer>, ash::vk::DeviceSize),
+        vertex_stride: ash::vk::DeviceSize,
+        max_vertex: u32,
+        index_data: Option<(Arc<Buffer>, ash::vk::DeviceSize, ash::vk::IndexType)>,
+        transform_data: Option<(Arc<Buffer>, ash::vk::DeviceSize)>,
+    },
+    // tightly packed ash::vk::AccelerationStructureInstanceKHR
+    Instances {
+        data: (Arc<Buffer>, ash::vk::DeviceSize),
+    },
+}
+
+pub struct AccelerationStructureGeometry {
+    pub data: AccelerationStructureGeometryData,
+    pub flags: ash::vk::GeometryFlagsKHR,
+}
+
+impl AccelerationStructureGeometry {
+    fn to_ash(&self) -> ash::vk::AccelerationStructureGeometryKHR {
+        let (geometry_type, geometry) = match &self.data {
+            AccelerationStructureGeometryData::Triangles {
+                vertex_format,
+                vertex_data,
+                vertex_stride,
+                max_vertex,
+                index_data,
+                transform_data,
+            } => {
+                let mut triangles =
+                    ash::vk::AccelerationStructureGeometryTrianglesDataKHR::builder()
+                        .vertex_format(*vertex_format)
+                        .vertex_data(device_address(&vertex_data.0, vertex_data.1))
+                        .vertex_stride(*vertex_stride)
+                        .max_vertex(*max_vertex)
+                        .index_type(ash::vk::IndexType::NONE_KHR);
+                if let Some((buffer, offset, index_type)) = index_data {
+                    triangles = triangles
+                        .index_data(device_address(buffer, *offset))
+                        .index_type(*index_type);
+                }
+                if let Some((buffer, offset)) = transform_data {
+                    triangles = triangles.transform_data(device_address(buffer, *offset));
+                }
+                (
+                    ash::vk::GeometryTypeKHR::TRIANGLES,
+                    ash::vk::AccelerationStructureGeometryDataKHR {
+                        triangles: triangles.build(),
+                    },
+                )
+            }
+            AccelerationStructureGeometryData::Instances { data } => (
+                ash::vk::GeometryTypeKHR::INSTANCES,
+                ash::vk::AccelerationStructureGeometryDataKHR {
+                    instances: ash::vk::AccelerationStructureGeometryInstancesDataKHR::builder()
+                        .array_of_pointers(false)
+                        .data(device_address(&data.0, data.1))
+                        .build(),
+                },
+            ),
+        };
+        ash::vk::AccelerationStructureGeometryKHR::builder()
+            .geometry_type(geometry_type)
+            .geometry(geometry)
+            .flags(self.flags)
+            .build()
+    }
+    fn buffers(&self) -> Vec<&Arc<Buffer>> {
+        match &self.data {
+            AccelerationStructureGeometryData::Triangles {
+                vertex_data,
+                index_data,
+                transform_data,
+                ..
+            } => {
+                let mut buffers = vec![&vertex_data.0];
+                if let Some((buffer, _, _)) = index_data {
+                    buffers.push(buffer);
+                }
+                if let Some((buffer, _)) = transform_data {
+                    buffers.push(buffer);
+                }
+                buffers
+            }
+            AccelerationStructureGeometryData::Instances { data } => vec![&data.0],
+        }
+    }
+}
+
+fn device_address(
+    buffer: &Buffer,
+    offset: ash::vk::DeviceSize,
+) -> ash::vk::DeviceOrHostAddressConstKHR {
+    ash::vk::DeviceOrHostAddressConstKHR {
+        device_address: buffer.device_address() + offset,
+    }
+}
+
+fn check_geometries(
+    ty: ash::vk::AccelerationStructureTypeKHR,
+    geometries: &[AccelerationStructureGeometry],
+) {
+    if ty == ash::vk::AccelerationStructureTypeKHR::TOP_LEVEL {
+        // MUST VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03789
+        if geometries.iter().any(|geometry| {
+            !matches!(
+                geometry.data,
+                AccelerationStructureGeometryData::Instances { .. }
+            )
+        }) {
+            panic!("VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03789");
+        }
+        // MUST VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03790
+        if geometries.len() != 1 {
+            panic!("VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03790");
+        }
+    } else {
+        // MUST VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03791
+        if geometries.iter().any(|geometry| {
+            matches!(
+                geometry.data,
+                AccelerationStructureGeometryData::Instances { .. }
+            )
+        }) {
+            panic!("VUID-VkAccelerationStructureBuildGeometryInfoKHR-type-03791");
+        }
+    }
+}
+
+pub struct AccelerationStructureBuildGeometryInfo {
+    pub flags: ash::vk::BuildAccelerationStructureFlagsKHR,
+    // Some(src) updates src into dst, otherwise dst is built from scratch
+    pub src: Option<Arc<AccelerationStructure>>,
+    pub dst: Arc<AccelerationStructure>,
+    pub geometries: Vec<AccelerationStructureGeometry>,
+    pub scratch_data: (Arc<Buffer>, ash::vk::DeviceSize),
+}
+
+impl<const LEVEL: Level> CommandBuffer<LEVEL, { RECORDING }, { OUTSIDE }> {
+    // DONE VUID-vkCmdBuildAccelerationStructuresKHR-commandBuffer-recording
+    // DONE VUID-vkCmdBuildAccelerationStructuresKHR-renderpass
+    pub fn cmd_build_acceleration_structures(
+        &mut self,
+        infos: &[AccelerationStructureBuildGeometryInfo],
+        build_range_infos: &[&[ash::vk::AccelerationStructureBuildRangeInfoKHR]],
+    ) {
+        if infos.len() != build_range_infos.len() {
+            panic!("infos and build_range_infos must have the same length");
+        }
+        let mut vk_geometries = Vec::with_capacity(infos.len());
+        for (info, build_range_info) in infos.iter().zip(build_range_infos) {
+            check_geometries(info.dst.ty, info.geometries.as_slice());
+            if info.geometries.len() != build_range_info.len() {
+                panic!("each info must have one build range info per geometry");
+            }
+            // MUST VUID-vkCmdBuildAccelerationStructuresKHR-pInfos-03674
+            if !info
+                .scratch_data
+                .0
+                .usage
+                .contains(ash::vk::BufferUsageFlags::STORAGE_BUFFER)
+            {
+                panic!("VUID-vkCmdBuildAccelerationStructuresKHR-pInfos-03674");
+            }
+            vk_geometries.push(
+                info.geometries
+                    .iter()
+                    .map(AccelerationStructureGeometry::to_ash)
+                    .collect::<Vec<_>>(),
+            );
+        }
+        let vk_infos = infos
+            .iter()
+            .zip(&vk_geometries)
+            .map(|(info, vk_geometries)| {
+                let mut vk_info = ash::vk::AccelerationStructureBuildGeometryInfoKHR::builder()
+                    .ty(info.dst.ty)
+                    .flags(info.flags)
+                    .mode(ash::vk::BuildAccelerationStructureModeKHR::BUILD)
+                    .dst_acceleration_structure(info.dst.vk_acceleration_structure)
+                    .geometries(vk_geometries.as_slice())
+                    .scratch_data(ash::vk::DeviceOrHostAddressKHR {
+                        device_address: info.scratch_data.0.device_address() + info.scratch_data.1,
+                    });
+                if let Some(src) = &info.src {
+                    vk_info = vk_info
+                        .mode(ash::vk::BuildAccelerationStructureModeKHR::UPDATE)
+                        .src_acceleration_structure(src.vk_acceleration_structure);
+                }
+                vk_info.build()
+            })
+            .collect::<Vec<_>>();
+        for info in infos {
+            for geometry in &info.geometries {
+                for buffer in geometry.buffers() {
+                    self.holding_resources
+                        .read_buffers
+                        .insert(buffer.ash_vk_buffer.as_raw(), buffer.clone());
+                }
+            }
+            let scratch_buffer = &info.scratch_data.0;
+            self.holding_resources.write_buffers.insert(
+                scratch_buffer.ash_vk_buffer.as_raw(),
+                scratch_buffer.clone(),
+            );
+            for acceleration_structure in info.src.iter().chain(std::iter::once(&info.dst)) {
+                self.holding_resources.acceleration_structures.insert(
+                    acceleration_structure.vk_acceleration_structure.as_raw(),
+                    acceleration_structure.clone(),
+                );
+            }
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device
+                .acceleration_structure_loader()
+                .cmd_build_acceleration_structures(
+                    self.vk_command_buffer,
+                    vk_infos.as_slice(),
+                    build_range_infos,
+                );
+        }
+    }
+}
@@ -4,7 +4,11 @@ use crate::buffer::Buffer;
 use crate::command::command_buffer::State::RECORDING;
 use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
 use crate::image::image_subresource_range::ImageSubresourceRange;
-use crate::image::Image;
+use crate::device_features::{
+    PhysicalDeviceSeparateDepthStencilLayoutsFeatures, PhysicalDeviceSynchronization2Features,
+    PhysicalDeviceVulkan12Features, PhysicalDeviceVulkan13Features,
+};
+use crate::image::{format_aspect_mask, Image};
 use crate::image::State::Bound;
 use crate::physical_device::queue_falmily_properties::QueueFamilyProperties;
 use crate::pipeline::pipeline_stage_flags::PipelineStageFlags;
@@ -48,6 +52,8 @@ impl MemoryBarrierBuilder {
 pub struct ImageMemoryBarrier {
     pub(crate) ash_vk_image_memory_barrier: ash::vk::ImageMemoryBarrier,
     pub image: Arc<Image<{ Bound }>>,
+    // set the image's layout to the new layout when recorded
+    track_layout: bool,
     // src_queue_family: Option<QueueFamilyProperties>,
     // dst_queue_family: Option<QueueFamilyProperties>,
 }
@@ -72,6 +78,25 @@ impl ImageMemoryBarrier {
             dst_queue_family: Default::default(),
             image,
             subresource_range: Default::default(),
+            track_layout: false,
+        }
+    }
+    // old layout is taken from the image's last known layout, which is updated when the barrier
+    // is recorded by `cmd_pipeline_barrier`, as long as the barrier still covers the whole image
+    pub fn transition(
+        image: Arc<Image<{ Bound }>>,
+        new_layout: ash::vk::ImageLayout,
+    ) -> ImageMemoryBarrierBuilder {
+        ImageMemoryBarrierBuilder {
+            src_access_mask: Default::default(),
+            dst_access_mask: Default::default(),
+            old_layout: image.layout(),
+            new_layout,
+            src_queue_family: Default::default(),
+            dst_queue_family: Default::default(),
+            subresource_range: image.full_subresource_range(),
+            image,
+            track_layout: true,
         }
     }
 }
@@ -85,6 +110,7 @@ pub struct ImageMemoryBarrierBuilder {
     dst_queue_family: Option<QueueFamilyProperties>,
     image: Arc<Image<{ Bound }>>,
     subresource_range: ImageSubresourceRange,
+    track_layout: bool,
 }
 
 impl ImageMemoryBarrierBuilder {
@@ -116,7 +142,24 @@ impl ImageMemoryBarrierBuilder {
         self.subresource_range = subresource_range;
         self
     }
-    pub fn build(self) -> Pin<Arc<ImageMemoryBarrier>> {
+    pub fn build(mut self) -> Pin<Arc<ImageMemoryBarrier>> {
+        let depth_stencil = ash::vk::ImageAspectFlags::DEPTH | ash::vk::ImageAspectFlags::STENCIL;
+        let format_aspect_mask = format_aspect_mask(self.image.image_create_info.format);
+        if format_aspect_mask.intersects(depth_stencil)
+            && self.subresource_range.aspect_mask.intersects(depth_stencil)
+        {
+            let enabled_features = &self.image.device.enabled_features;
+            let separate_depth_stencil_layouts = enabled_features.contains(
+                &PhysicalDeviceSeparateDepthStencilLayoutsFeatures::SeparateDepthStencilLayouts
+                    .into(),
+            ) || enabled_features
+                .contains(&PhysicalDeviceVulkan12Features::SeparateDepthStencilLayouts.into());
+            // DONE VUID-VkImageMemoryBarrier-image-03320
+            // only combined depth/stencil formats gain the other aspect
+            if !separate_depth_stencil_layouts {
+                self.subresource_range.aspect_mask |= format_aspect_mask;
+            }
+        }
         let mut ash_vk_image_memory_barrier = ash::vk::ImageMemoryBarrier::builder()
             .src_access_mask(self.src_access_mask)
             .dst_access_mask(self.dst_access_mask)
@@ -132,9 +175,20 @@ impl ImageMemoryBarrierBuilder {
             ash_vk_image_memory_barrier =
                 ash_vk_image_memory_barrier.dst_queue_family_index(dst_queue_family.index);
         }
+        // a barrier narrowed by `subresource_range` leaves the other subresources in their old
+        // layout, so the image has no single layout to track anymore
+        let range = &self.subresource_range;
+        let covers_image = range.aspect_mask.contains(format_aspect_mask)
+            && range.base_mip_level == 0
+            && (range.level_count == ash::vk::REMAINING_MIP_LEVELS
+                || range.level_count == self.image.image_create_info.mip_levels)
+            && range.base_array_layer == 0
+            && (range.layer_count == ash::vk::REMAINING_ARRAY_LAYERS
+                || range.layer_count == self.image.image_create_info.array_layers);
         // TODO handle p_next by using pin
         Arc::pin(ImageMemoryBarrier {
             ash_vk_image_memory_barrier: ash_vk_image_memory_barrier.build(),
+            track_layout: self.track_layout && covers_image,
             image: self.image,
             // src_queue_family: self.src_queue_family,
             // dst_queue_family: self.dst_queue_family,
@@ -222,6 +276,8 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
                                         )> = Cell::new((Vec::new(), Vec::new(), Vec::new()));
     }
     // DONE VUID-vkCmdPipelineBarrier-commandBuffer-recording
+    // the stage flags of `src_stage_mask` and `dst_stage_mask` are OR'd into one mask each, they
+    // are slices because each flag carries its own feature
     pub fn cmd_pipeline_barrier(
         &mut self,
         src_stage_mask: &[PipelineStageFlags],
@@ -231,6 +287,20 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
         buffer_memory_barriers: &[Pin<Arc<BufferMemoryBarrier>>],
         image_memory_barriers: &[Pin<Arc<ImageMemoryBarrier>>],
     ) {
+        let synchronization2 = self.device.enabled_features.contains(
+            &PhysicalDeviceSynchronization2Features::Synchronization2.into(),
+        ) || self
+            .device
+            .enabled_features
+            .contains(&PhysicalDeviceVulkan13Features::Synchronization2.into());
+        // MUST VUID-vkCmdPipelineBarrier-srcStageMask-03937
+        if src_stage_mask.is_empty() && !synchronization2 {
+            panic!("VUID-vkCmdPipelineBarrier-srcStageMask-03937");
+        }
+        // MUST VUID-vkCmdPipelineBarrier-dstStageMask-03937
+        if dst_stage_mask.is_empty() && !synchronization2 {
+            panic!("VUID-vkCmdPipelineBarrier-dstStageMask-03937");
+        }
         Self::BARRIERS_CACHES.with(|local| {
             let mut vk_src_stage_mask = ash::vk::PipelineStageFlags::default();
             src_stage_mask
@@ -253,6 +323,11 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
                 buffer_cache.push(barrier.ash_vk_buffer_memory_barrier);
             }
             for barrier in image_memory_barriers {
+                if barrier.track_layout {
+                    barrier
+                        .image
+                        .set_layout(barrier.ash_vk_image_memory_barrier.new_layout);
+                }
                 let image = barrier.image.clone();
                 self.holding_resources
                     .write_images
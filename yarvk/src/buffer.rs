@@ -7,11 +7,17 @@ use crate::device_features::Feature;
 use crate::device_features::PhysicalDeviceFeatures::{
     SparseBinding, SparseResidencyAliased, SparseResidencyBuffer,
 };
+use crate::device_features::PhysicalDeviceExtendedDynamicStateFeaturesEXT::ExtendedDynamicState;
+use crate::device_features::PhysicalDeviceIndexTypeUint8FeaturesEXT::IndexTypeUint8;
 use crate::device_features::PhysicalDeviceVulkan11Features::ProtectedMemory;
-use crate::device_features::PhysicalDeviceVulkan12Features::BufferDeviceAddressCaptureReplay;
-use crate::device_memory::DeviceMemory;
+use crate::device_features::PhysicalDeviceVulkan12Features::{
+    BufferDeviceAddress, BufferDeviceAddressCaptureReplay,
+};
+use crate::device_memory::{DedicatedRequirements, DeviceMemory};
+use crate::error::{Result, ResultContext};
 use crate::physical_device::SharingMode;
 
+use smallvec::SmallVec;
 use std::sync::Arc;
 use ash::vk::Handle;
 
@@ -48,9 +54,37 @@ impl BufferCreateFlags {
     }
 }
 
+// DONE VUID-vkCmdBindIndexBuffer-indexType-02507
+pub enum IndexType {
+    UINT16,
+    UINT32,
+    // DONE VUID-vkCmdBindIndexBuffer-indexType-02765
+    UINT8(Feature<{ IndexTypeUint8.into() }>),
+}
+
+impl IndexType {
+    pub(crate) fn to_ash(&self) -> ash::vk::IndexType {
+        match self {
+            IndexType::UINT16 => ash::vk::IndexType::UINT16,
+            IndexType::UINT32 => ash::vk::IndexType::UINT32,
+            IndexType::UINT8(_) => ash::vk::IndexType::UINT8_EXT,
+        }
+    }
+    fn size(&self) -> ash::vk::DeviceSize {
+        match self {
+            IndexType::UINT16 => 2,
+            IndexType::UINT32 => 4,
+            IndexType::UINT8(_) => 1,
+        }
+    }
+}
+
 pub struct Buffer<const STATE: State = Bound> {
     pub device: Arc<Device>,
     pub(crate) ash_vk_buffer: ash::vk::Buffer,
+    pub(crate) flags: ash::vk::BufferCreateFlags,
+    pub(crate) size: ash::vk::DeviceSize,
+    pub(crate) usage: ash::vk::BufferUsageFlags,
 }
 
 impl<const STATE: State> Drop for Buffer<STATE> {
@@ -72,22 +106,143 @@ impl Buffer<{ Unbound }> {
                 .get_buffer_memory_requirements(self.ash_vk_buffer)
         }
     }
+    // the memory requirements along with whether a dedicated allocation is preferred or required
+    pub fn get_buffer_memory_requirements2(
+        &self,
+    ) -> (ash::vk::MemoryRequirements, DedicatedRequirements) {
+        let mut dedicated_requirements = ash::vk::MemoryDedicatedRequirements::default();
+        let mut requirements = ash::vk::MemoryRequirements2::builder()
+            .push_next(&mut dedicated_requirements)
+            .build();
+        let info = ash::vk::BufferMemoryRequirementsInfo2::builder()
+            .buffer(self.ash_vk_buffer)
+            .build();
+        unsafe {
+            // Host Synchronization: none
+            self.device
+                .ash_device
+                .get_buffer_memory_requirements2(&info, &mut requirements);
+        }
+        (
+            requirements.memory_requirements,
+            DedicatedRequirements::from_ash(&dedicated_requirements),
+        )
+    }
 
     pub fn bind_memory(
         self,
         memory: &DeviceMemory,
         memory_offset: ash::vk::DeviceSize,
-    ) -> Result<Arc<Buffer<{ Bound }>>, ash::vk::Result> {
+    ) -> Result<Arc<Buffer<{ Bound }>>> {
+        // MUST VUID-vkBindBufferMemory-buffer-01030
+        if self.is_sparse() {
+            panic!("VUID-vkBindBufferMemory-buffer-01030");
+        }
+        // MUST VUID-vkBindBufferMemory-memory-01508
+        memory.check_dedicated(
+            self.ash_vk_buffer.as_raw(),
+            memory_offset,
+            "VUID-vkBindBufferMemory-memory-01508",
+        );
         // TODO why device_memory do not need to be synchronized?
         unsafe {
-            self.device.ash_device.bind_buffer_memory(
-                self.ash_vk_buffer,
-                memory.vk_device_memory,
-                memory_offset,
-            )?;
+            self.device
+                .ash_device
+                .bind_buffer_memory(self.ash_vk_buffer, memory.vk_device_memory, memory_offset)
+                .context(
+                    "vkBindBufferMemory",
+                    ash::vk::ObjectType::BUFFER,
+                    self.ash_vk_buffer.as_raw(),
+                )?;
         }
         Ok(Arc::new(unsafe { std::mem::transmute(self) }))
     }
+
+    // sparse buffers are bound through Queue::bind_sparse instead
+    pub fn into_sparse(self) -> Arc<Buffer<{ Bound }>> {
+        if !self.is_sparse() {
+            panic!("buffer is not created with SPARSE_BINDING");
+        }
+        Arc::new(unsafe { std::mem::transmute(self) })
+    }
+
+    pub(crate) fn is_sparse(&self) -> bool {
+        self.flags
+            .contains(ash::vk::BufferCreateFlags::SPARSE_BINDING)
+    }
+}
+
+impl Buffer {
+    pub fn size(&self) -> ash::vk::DeviceSize {
+        self.size
+    }
+    pub fn usage(&self) -> ash::vk::BufferUsageFlags {
+        self.usage
+    }
+    pub fn get_device_address(
+        &self,
+        _feature: Feature<{ BufferDeviceAddress.into() }>,
+    ) -> ash::vk::DeviceAddress {
+        self.device_address()
+    }
+    pub(crate) fn device_address(&self) -> ash::vk::DeviceAddress {
+        // MUST VUID-VkBufferDeviceAddressInfo-buffer-02601
+        if !self
+            .usage
+            .contains(ash::vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS)
+        {
+            panic!("VUID-VkBufferDeviceAddressInfo-buffer-02601");
+        }
+        // DONE VUID-VkBufferDeviceAddressInfo-buffer-02600
+        let info = ash::vk::BufferDeviceAddressInfo::builder().buffer(self.ash_vk_buffer);
+        unsafe {
+            // Host Synchronization: none
+            self.device.ash_device.get_buffer_device_address(&info)
+        }
+    }
+}
+
+impl Device {
+    pub fn bind_buffers(
+        &self,
+        bind_infos: Vec<(Buffer<{ Unbound }>, &DeviceMemory, ash::vk::DeviceSize)>,
+    ) -> Result<Vec<Arc<Buffer<{ Bound }>>>> {
+        // DONE VUID-VkBindBufferMemoryInfo-buffer-01029
+        let vk_bind_infos = bind_infos
+            .iter()
+            .map(|(buffer, memory, memory_offset)| {
+                // MUST VUID-VkBindBufferMemoryInfo-buffer-01030
+                if buffer.is_sparse() {
+                    panic!("VUID-VkBindBufferMemoryInfo-buffer-01030");
+                }
+                // MUST VUID-VkBindBufferMemoryInfo-memory-01508
+                memory.check_dedicated(
+                    buffer.ash_vk_buffer.as_raw(),
+                    *memory_offset,
+                    "VUID-VkBindBufferMemoryInfo-memory-01508",
+                );
+                ash::vk::BindBufferMemoryInfo::builder()
+                    .buffer(buffer.ash_vk_buffer)
+                    .memory(memory.vk_device_memory)
+                    .memory_offset(*memory_offset)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        unsafe {
+            // Host Synchronization: none
+            self.ash_device
+                .bind_buffer_memory2(vk_bind_infos.as_slice())
+                .context(
+                    "vkBindBufferMemory2",
+                    ash::vk::ObjectType::DEVICE,
+                    self.ash_device.handle().as_raw(),
+                )?;
+        }
+        Ok(bind_infos
+            .into_iter()
+            .map(|(buffer, _, _)| Arc::new(unsafe { std::mem::transmute(buffer) }))
+            .collect())
+    }
 }
 
 impl Buffer<{ Unbound }> {
@@ -131,7 +286,7 @@ impl BufferBuilder {
         self
     }
 
-    pub fn build(mut self) -> Result<Buffer<{ Unbound }>, ash::vk::Result> {
+    pub fn build(mut self) -> Result<Buffer<{ Unbound }>> {
         // SILENCE VUID-VkBufferCreateInfo-flags-00918
         if self
             .flags
@@ -168,10 +323,18 @@ impl BufferBuilder {
             }
         }
         // Host Synchronization: none
-        let ash_vk_buffer = unsafe { self.device.ash_device.create_buffer(&create_info, None)? };
+        let ash_vk_buffer = unsafe {
+            self.device
+                .ash_device
+                .create_buffer(&create_info, None)
+                .context("vkCreateBuffer", ash::vk::ObjectType::BUFFER, 0)?
+        };
         Ok(Buffer {
             device: self.device,
             ash_vk_buffer,
+            flags: self.flags,
+            size: self.size,
+            usage: self.usage,
         })
     }
 }
@@ -230,7 +393,7 @@ impl BufferViewBuilder {
         self.range = range;
         self
     }
-    pub fn build(self) -> Result<Arc<BufferView>, ash::vk::Result> {
+    pub fn build(self) -> Result<Arc<BufferView>> {
         let create_info = ash::vk::BufferViewCreateInfo::builder()
             .flags(self.flags)
             .format(self.format)
@@ -243,7 +406,12 @@ impl BufferViewBuilder {
                 .buffer
                 .device
                 .ash_device
-                .create_buffer_view(&create_info, None)?;
+                .create_buffer_view(&create_info, None)
+                .context(
+                    "vkCreateBufferView",
+                    ash::vk::ObjectType::BUFFER,
+                    self.buffer.ash_vk_buffer.as_raw(),
+                )?;
             Ok(Arc::new(BufferView {
                 buffer: self.buffer,
                 ash_vk_buffer_view,
@@ -260,7 +428,7 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
         buffers: &[Arc<Buffer>],
         offsets: &[ash::vk::DeviceSize],
     ) {
-        let mut ash_vk_buffers = Vec::with_capacity(buffers.len());
+        let mut ash_vk_buffers = SmallVec::<[ash::vk::Buffer; 4]>::with_capacity(buffers.len());
         for buffer in buffers {
             ash_vk_buffers.push(buffer.ash_vk_buffer);
             // TODO insert by raw automatically
@@ -280,12 +448,140 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
         }
     }
 
+    // `sizes` bounds each binding instead of binding to the end of the buffer, `strides` are used
+    // by pipelines with DynamicState::VertexInputBindingStride
+    // DONE VUID-vkCmdBindVertexBuffers2-commandBuffer-recording
+    pub fn cmd_bind_vertex_buffers2(
+        &mut self,
+        first_binding: u32,
+        buffers: &[Arc<Buffer>],
+        offsets: &[ash::vk::DeviceSize],
+        sizes: Option<&[ash::vk::DeviceSize]>,
+        strides: Option<&[ash::vk::DeviceSize]>,
+        _feature: &Feature<{ ExtendedDynamicState.into() }>,
+    ) {
+        // all arrays are bindingCount long
+        if offsets.len() != buffers.len()
+            || sizes.map_or(false, |sizes| sizes.len() != buffers.len())
+            || strides.map_or(false, |strides| strides.len() != buffers.len())
+        {
+            panic!("offsets, sizes and strides must have the same length as buffers");
+        }
+        let limits = &self.device.physical_device.properties.limits;
+        // MUST VUID-vkCmdBindVertexBuffers2-firstBinding-03355
+        if first_binding >= limits.max_vertex_input_bindings {
+            panic!("VUID-vkCmdBindVertexBuffers2-firstBinding-03355");
+        }
+        // MUST VUID-vkCmdBindVertexBuffers2-firstBinding-03356
+        if first_binding + buffers.len() as u32 > limits.max_vertex_input_bindings {
+            panic!("VUID-vkCmdBindVertexBuffers2-firstBinding-03356");
+        }
+        let mut ash_vk_buffers = SmallVec::<[ash::vk::Buffer; 4]>::with_capacity(buffers.len());
+        for (index, buffer) in buffers.iter().enumerate() {
+            // MUST VUID-vkCmdBindVertexBuffers2-pOffsets-03357
+            if offsets[index] >= buffer.size {
+                panic!("VUID-vkCmdBindVertexBuffers2-pOffsets-03357");
+            }
+            // MUST VUID-vkCmdBindVertexBuffers2-pSizes-03358
+            if let Some(sizes) = sizes {
+                if sizes[index] != ash::vk::WHOLE_SIZE
+                    && offsets[index] + sizes[index] > buffer.size
+                {
+                    panic!("VUID-vkCmdBindVertexBuffers2-pSizes-03358");
+                }
+            }
+            // MUST VUID-vkCmdBindVertexBuffers2-pBuffers-03359
+            if !buffer
+                .usage
+                .contains(ash::vk::BufferUsageFlags::VERTEX_BUFFER)
+            {
+                panic!("VUID-vkCmdBindVertexBuffers2-pBuffers-03359");
+            }
+            // DONE VUID-vkCmdBindVertexBuffers2-pBuffers-03360
+            ash_vk_buffers.push(buffer.ash_vk_buffer);
+            self.holding_resources
+                .read_buffers
+                .insert(buffer.ash_vk_buffer.as_raw(), buffer.clone());
+        }
+        // MUST VUID-vkCmdBindVertexBuffers2-pStrides-03362
+        if strides.map_or(false, |strides| {
+            strides
+                .iter()
+                .any(|stride| *stride > limits.max_vertex_input_binding_stride as u64)
+        }) {
+            panic!("VUID-vkCmdBindVertexBuffers2-pStrides-03362");
+        }
+        // TODO VUID-vkCmdBindVertexBuffers2-pStrides-06209
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            // the core command is only loaded for 1.3 devices
+            if self.device.physical_device.instance.api_version >= ash::vk::API_VERSION_1_3 {
+                self.device.ash_device.cmd_bind_vertex_buffers2(
+                    self.vk_command_buffer,
+                    first_binding,
+                    ash_vk_buffers.as_slice(),
+                    offsets,
+                    sizes,
+                    strides,
+                );
+            } else {
+                self.device.extended_dynamic_state_loader().cmd_bind_vertex_buffers2(
+                    self.vk_command_buffer,
+                    first_binding,
+                    ash_vk_buffers.as_slice(),
+                    offsets,
+                    sizes,
+                    strides,
+                );
+            }
+        }
+    }
+
+    // bind the index range [offset, offset + size) of `buffer`, the bounds are validated on the
+    // host and the range is recorded through vkCmdBindIndexBuffer until ash exposes
+    // vkCmdBindIndexBuffer2KHR (VK_KHR_maintenance5)
+    pub fn cmd_bind_index_buffer2(
+        &mut self,
+        buffer: Arc<Buffer>,
+        offset: ash::vk::DeviceSize,
+        size: ash::vk::DeviceSize,
+        index_type: IndexType,
+    ) {
+        // DONE VUID-vkCmdBindIndexBuffer2KHR-indexType-08786
+        // DONE VUID-vkCmdBindIndexBuffer2KHR-indexType-08787
+        let index_size = index_type.size();
+        // MUST VUID-vkCmdBindIndexBuffer2KHR-offset-08782
+        if offset >= buffer.size {
+            panic!("VUID-vkCmdBindIndexBuffer2KHR-offset-08782");
+        }
+        // MUST VUID-vkCmdBindIndexBuffer2KHR-offset-08783
+        if offset % index_size != 0 {
+            panic!("VUID-vkCmdBindIndexBuffer2KHR-offset-08783");
+        }
+        // MUST VUID-vkCmdBindIndexBuffer2KHR-buffer-08784
+        if !buffer.usage.contains(ash::vk::BufferUsageFlags::INDEX_BUFFER) {
+            panic!("VUID-vkCmdBindIndexBuffer2KHR-buffer-08784");
+        }
+        if size != ash::vk::WHOLE_SIZE {
+            // MUST VUID-vkCmdBindIndexBuffer2KHR-size-08767
+            if size % index_size != 0 {
+                panic!("VUID-vkCmdBindIndexBuffer2KHR-size-08767");
+            }
+            // MUST VUID-vkCmdBindIndexBuffer2KHR-size-08768
+            if offset.checked_add(size).map_or(true, |end| end > buffer.size) {
+                panic!("VUID-vkCmdBindIndexBuffer2KHR-size-08768");
+            }
+        }
+        self.cmd_bind_index_buffer(buffer, offset, index_type);
+    }
+
     // DONE VUID-vkCmdBindIndexBuffer-commandBuffer-recording
     pub fn cmd_bind_index_buffer(
         &mut self,
         buffer: Arc<Buffer>,
         offset: ash::vk::DeviceSize,
-        index_type: ash::vk::IndexType,
+        index_type: IndexType,
     ) {
         // TODO insert by raw automatically
         self.holding_resources
@@ -294,12 +590,11 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
         unsafe {
             // Host Synchronization: commandBuffer, VkCommandPool
             let _pool = self.command_pool.vk_command_pool.write();
-            // TODO index_type feature safety
             self.device.ash_device.cmd_bind_index_buffer(
                 self.vk_command_buffer,
                 buffer.ash_vk_buffer,
                 offset,
-                index_type,
+                index_type.to_ash(),
             );
         }
     }
@@ -312,6 +607,8 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
         vertex_offset: i32,
         first_instance: u32,
     ) {
+        #[cfg(debug_assertions)]
+        self.check_bound_descriptor_sets(ash::vk::PipelineBindPoint::GRAPHICS);
         unsafe {
             // Host Synchronization: commandBuffer, VkCommandPool
             let _pool = self.command_pool.vk_command_pool.write();
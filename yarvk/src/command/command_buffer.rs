@@ -1,13 +1,21 @@
 
 
+use crate::acceleration_structure::AccelerationStructure;
 use crate::buffer::Buffer;
 use crate::command::command_buffer::Level::{PRIMARY, SECONDARY};
-use crate::command::command_buffer::RenderPassScope::OUTSIDE;
+use crate::command::command_buffer::RenderPassScope::{INSIDE, OUTSIDE};
 use crate::command::command_buffer::State::{EXECUTABLE, INITIAL, INVALID, PENDING, RECORDING};
 use crate::command::command_pool::CommandPool;
 use crate::device::Device;
+use crate::device_memory::DeviceMemory;
 use crate::frame_buffer::Framebuffer;
+use crate::image::image_view::ImageView;
 use crate::image::Image;
+use crate::query_pool::QueryPool;
+#[cfg(debug_assertions)]
+use crate::descriptor_pool::DescriptorSetLayout;
+#[cfg(debug_assertions)]
+use crate::pipeline::PipelineLayout;
 
 
 use crate::render_pass::subpass::SubpassIndex;
@@ -15,9 +23,11 @@ use crate::render_pass::RenderPass;
 
 use lazy_static::lazy_static;
 use rustc_hash::FxHashMap;
+use smallvec::SmallVec;
 
 use std::marker::PhantomPinned;
 use std::pin::Pin;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 lazy_static! {
     pub static ref DEFAULT_INHERITANCE_INFO: Pin<Arc<CommandBufferInheritanceInfo>> =
@@ -85,6 +95,27 @@ pub struct CommandBufferInheritanceInfoBuilder {
 }
 
 impl CommandBufferInheritanceInfoBuilder {
+    pub fn render_pass(mut self, render_pass: Arc<RenderPass>, subpass: SubpassIndex) -> Self {
+        self.render_pass = Some(render_pass);
+        self.subpass = Some(subpass);
+        self
+    }
+    pub fn framebuffer(mut self, framebuffer: Arc<Framebuffer>) -> Self {
+        self.framebuffer = Some(framebuffer);
+        self
+    }
+    pub fn occlusion_query_enable(mut self, query_flags: ash::vk::QueryControlFlags) -> Self {
+        self.occlusion_query_enable = true;
+        self.query_flags = query_flags;
+        self
+    }
+    pub fn pipeline_statistics(
+        mut self,
+        pipeline_statistics: ash::vk::QueryPipelineStatisticFlags,
+    ) -> Self {
+        self.pipeline_statistics = pipeline_statistics;
+        self
+    }
     pub fn build(self) -> Pin<Arc<CommandBufferInheritanceInfo>> {
         // TODO VUID-VkCommandBufferInheritanceInfo-occlusionQueryEnable-00056
         // TODO VUID-VkCommandBufferInheritanceInfo-queryFlags-00057
@@ -120,6 +151,27 @@ pub(crate) struct HoldingResources {
     pub write_buffers: FxHashMap<u64, Arc<Buffer>>,
     pub read_images: FxHashMap<u64, Arc<Image>>,
     pub read_buffers: FxHashMap<u64, Arc<Buffer>>,
+    // attachments of imageless framebuffers
+    pub image_views: FxHashMap<u64, Arc<ImageView>>,
+    pub query_pools: FxHashMap<u64, Arc<QueryPool>>,
+    pub acceleration_structures: FxHashMap<u64, Arc<AccelerationStructure>>,
+    #[cfg(debug_assertions)]
+    pub bound_set_layouts:
+        FxHashMap<ash::vk::PipelineBindPoint, FxHashMap<u32, Arc<DescriptorSetLayout>>>,
+    #[cfg(debug_assertions)]
+    pub bound_pipeline_layouts: FxHashMap<ash::vk::PipelineBindPoint, Arc<PipelineLayout>>,
+    // secondary command buffers are only kept alive here, so their scope is erased
+    pub secondary_command_buffers: Vec<CommandBuffer<{ SECONDARY }, { EXECUTABLE }, { OUTSIDE }>>,
+    // staging buffers of `cmd_upload_image`, kept by `clear` so later recordings reuse them, the
+    // first `staging_buffers_in_use` of them are used by the current recording
+    pub staging_buffers: Vec<StagingBuffer>,
+    pub staging_buffers_in_use: usize,
+}
+
+// a host visible buffer and its memory, the buffer is dropped before the memory
+pub(crate) struct StagingBuffer {
+    pub buffer: Arc<Buffer>,
+    pub memory: DeviceMemory,
 }
 
 impl HoldingResources {
@@ -128,6 +180,18 @@ impl HoldingResources {
         self.read_images.clear();
         self.write_buffers.clear();
         self.read_buffers.clear();
+        self.image_views.clear();
+        self.query_pools.clear();
+        self.acceleration_structures.clear();
+        self.secondary_command_buffers.clear();
+        // a command buffer is recorded again only after its previous recording is executed, so
+        // the staging buffers are free to reuse
+        self.staging_buffers_in_use = 0;
+        #[cfg(debug_assertions)]
+        {
+            self.bound_set_layouts.clear();
+            self.bound_pipeline_layouts.clear();
+        }
     }
 }
 
@@ -138,6 +202,8 @@ pub struct CommandBuffer<const LEVEL: Level, const STATE: State, const SCOPE: Re
     inheritance_info: Pin<Arc<CommandBufferInheritanceInfo>>,
     pub(crate) holding_resources: HoldingResources,
     pub(crate) one_time_submit: bool,
+    // generation of the command pool when the command buffer began recording
+    pub(crate) generation: u64,
 }
 
 impl<const LEVEL: Level, const STATE: State, const SCOPE: RenderPassScope> Drop
@@ -156,20 +222,38 @@ impl<const LEVEL: Level, const STATE: State, const SCOPE: RenderPassScope> Drop
                 .ash_device
                 .free_command_buffers(*vk_pool, &[self.vk_command_buffer]);
         }
+        if STATE == RECORDING {
+            self.command_pool
+                .recording_count
+                .fetch_sub(1, Ordering::Release);
+        }
     }
 }
 
 macro_rules! reset_impls {
     ($($stage: expr),*) => {$(
         impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { $stage }, SCOPE> {
-            pub fn reset(mut self) -> Result<CommandBuffer<LEVEL, { INITIAL }, SCOPE>, ash::vk::Result> {
+            // `release_resources` returns the memory of the command buffer to its pool, keep it for
+            // command buffers re-recorded with similar commands every frame
+            pub fn reset(mut self, release_resources: bool) -> Result<CommandBuffer<LEVEL, { INITIAL }, SCOPE>, ash::vk::Result> {
                 self.holding_resources.clear();
+                if release_resources {
+                    self.holding_resources.staging_buffers.clear();
+                }
+                let flags = if release_resources {
+                    ash::vk::CommandBufferResetFlags::RELEASE_RESOURCES
+                } else {
+                    ash::vk::CommandBufferResetFlags::empty()
+                };
                 // Host Synchronization: commandBuffer, VkCommandPool
                 // DONE VUID-vkResetCommandBuffer-commandBuffer-00046
                 // DONE VUID-vkResetCommandBuffer-commandBuffer-00045
                 let pool = self.command_pool.vk_command_pool.write();
                 unsafe {
-                    self.device.ash_device.reset_command_buffer(self.vk_command_buffer, ash::vk::CommandBufferResetFlags::RELEASE_RESOURCES)?
+                    self.device.ash_device.reset_command_buffer(self.vk_command_buffer, flags)?
+                }
+                if $stage == RECORDING {
+                    self.command_pool.recording_count.fetch_sub(1, Ordering::Release);
                 }
                 drop(pool);
                 Ok(unsafe { std::mem::transmute(self) })
@@ -180,8 +264,22 @@ macro_rules! reset_impls {
 
 reset_impls!(INITIAL, RECORDING, EXECUTABLE, INVALID);
 
+impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { EXECUTABLE }, SCOPE> {
+    // the command pool is reset after the command buffer began recording, which puts it back to
+    // the initial state, resetting the pool of an executed secondary command buffer invalidates
+    // the command buffer as well
+    pub(crate) fn is_reset_by_pool(&self) -> bool {
+        self.generation != self.command_pool.generation.load(Ordering::Acquire)
+            || self
+                .holding_resources
+                .secondary_command_buffers
+                .iter()
+                .any(CommandBuffer::is_reset_by_pool)
+    }
+}
+
 impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RECORDING }, SCOPE> {
-    fn end(self) -> Result<CommandBuffer<LEVEL, { EXECUTABLE }, SCOPE>, ash::vk::Result> {
+    pub fn end(self) -> Result<CommandBuffer<LEVEL, { EXECUTABLE }, SCOPE>, ash::vk::Result> {
         // Host Synchronization:commandBuffer, VkCommandPool
         let pool = self.command_pool.vk_command_pool.write();
         unsafe {
@@ -189,13 +287,16 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
                 .ash_device
                 .end_command_buffer(self.vk_command_buffer)?;
         }
+        self.command_pool
+            .recording_count
+            .fetch_sub(1, Ordering::Release);
         drop(pool);
         Ok(unsafe { std::mem::transmute(self) })
     }
 }
 
 impl<const STATE: State, const SCOPE: RenderPassScope> CommandBuffer<{ PRIMARY }, STATE, SCOPE> {
-    fn begin(
+    fn begin_inner(
         mut self,
         flags: ash::vk::CommandBufferUsageFlags,
     ) -> Result<CommandBuffer<{ PRIMARY }, { RECORDING }, SCOPE>, ash::vk::Result> {
@@ -215,6 +316,10 @@ impl<const STATE: State, const SCOPE: RenderPassScope> CommandBuffer<{ PRIMARY }
                 .ash_device
                 .begin_command_buffer(self.vk_command_buffer, &begin_info)?;
         }
+        self.command_pool
+            .recording_count
+            .fetch_add(1, Ordering::Release);
+        self.generation = self.command_pool.generation.load(Ordering::Acquire);
         drop(pool);
         Ok(unsafe { std::mem::transmute(self) })
     }
@@ -226,10 +331,15 @@ macro_rules! primary_record_impls {
             pub fn record<F>(self, flags: ash::vk::CommandBufferUsageFlags, f: F)
                              -> Result<CommandBuffer<{ PRIMARY }, { EXECUTABLE }, SCOPE>, ash::vk::Result>
                 where F: FnOnce(&mut CommandBuffer<{ PRIMARY }, { RECORDING }, SCOPE>) {
-                let mut recording_buffer = self.begin(flags)?;
+                let mut recording_buffer = self.begin_inner(flags)?;
                 f(&mut recording_buffer);
                 recording_buffer.end()
             }
+            // Begin recording without a closure, call `end` on the returned buffer when done
+            pub fn begin(self, flags: ash::vk::CommandBufferUsageFlags)
+                             -> Result<CommandBuffer<{ PRIMARY }, { RECORDING }, SCOPE>, ash::vk::Result> {
+                self.begin_inner(flags)
+            }
         }
     )*};
 }
@@ -237,7 +347,7 @@ macro_rules! primary_record_impls {
 primary_record_impls!(INITIAL, EXECUTABLE, INVALID);
 
 impl<const STATE: State, const SCOPE: RenderPassScope> CommandBuffer<{ SECONDARY }, STATE, SCOPE> {
-    fn begin(
+    fn begin_inner(
         mut self,
         flags: ash::vk::CommandBufferUsageFlags,
         inheritance_info: Pin<Arc<CommandBufferInheritanceInfo>>,
@@ -260,6 +370,10 @@ impl<const STATE: State, const SCOPE: RenderPassScope> CommandBuffer<{ SECONDARY
                 .ash_device
                 .begin_command_buffer(self.vk_command_buffer, &begin_info)?;
         }
+        self.command_pool
+            .recording_count
+            .fetch_add(1, Ordering::Release);
+        self.generation = self.command_pool.generation.load(Ordering::Acquire);
         drop(pool);
         Ok(unsafe { std::mem::transmute(self) })
     }
@@ -271,17 +385,107 @@ macro_rules! secondary_record_impls {
             pub fn record<F>(self, flags: ash::vk::CommandBufferUsageFlags, inheritance_info: Pin<Arc<CommandBufferInheritanceInfo>>, f: F)
                              -> Result<CommandBuffer<{ SECONDARY }, { EXECUTABLE }, SCOPE>, ash::vk::Result>
                 where F: FnOnce(&mut CommandBuffer<{ SECONDARY }, { RECORDING }, SCOPE>) {
-                let mut recording_buffer = self.begin(flags, inheritance_info)?;
+                let mut recording_buffer = self.begin_inner(flags, inheritance_info)?;
+                f(&mut recording_buffer);
+                recording_buffer.end()
+            }
+            // Record a secondary command buffer executed entirely inside the render pass of
+            // `inheritance_info`, RENDER_PASS_CONTINUE is added to `flags`
+            pub fn record_render_pass_continue<F>(self, flags: ash::vk::CommandBufferUsageFlags, inheritance_info: Pin<Arc<CommandBufferInheritanceInfo>>, f: F)
+                             -> Result<CommandBuffer<{ SECONDARY }, { EXECUTABLE }, { INSIDE }>, ash::vk::Result>
+                where F: FnOnce(&mut CommandBuffer<{ SECONDARY }, { RECORDING }, { INSIDE }>) {
+                // MUST VUID-VkCommandBufferBeginInfo-flags-00053
+                if inheritance_info.render_pass.is_none() {
+                    panic!("VUID-VkCommandBufferBeginInfo-flags-00053");
+                }
+                // DONE VUID-VkCommandBufferBeginInfo-flags-00054
+                let recording_buffer = self.begin_inner(
+                    flags | ash::vk::CommandBufferUsageFlags::RENDER_PASS_CONTINUE,
+                    inheritance_info,
+                )?;
+                let mut recording_buffer: CommandBuffer<{ SECONDARY }, { RECORDING }, { INSIDE }> =
+                    unsafe { std::mem::transmute(recording_buffer) };
                 f(&mut recording_buffer);
                 recording_buffer.end()
             }
+            // Begin recording without a closure, call `end` on the returned buffer when done
+            pub fn begin(self, flags: ash::vk::CommandBufferUsageFlags, inheritance_info: Pin<Arc<CommandBufferInheritanceInfo>>)
+                             -> Result<CommandBuffer<{ SECONDARY }, { RECORDING }, SCOPE>, ash::vk::Result> {
+                self.begin_inner(flags, inheritance_info)
+            }
         }
     )*};
 }
 
 secondary_record_impls!(INITIAL, EXECUTABLE, INVALID);
 
+impl<const SCOPE: RenderPassScope> CommandBuffer<{ PRIMARY }, { RECORDING }, SCOPE> {
+    // DONE VUID-vkCmdExecuteCommands-commandBuffer-recording
+    // DONE VUID-vkCmdExecuteCommands-pCommandBuffers-00088
+    // DONE VUID-vkCmdExecuteCommands-pCommandBuffers-00094
+    // DONE VUID-vkCmdExecuteCommands-pCommandBuffers-00100
+    // TODO VUID-vkCmdExecuteCommands-pCommandBuffers-00096
+    // TODO VUID-vkCmdExecuteCommands-pCommandBuffers-00097
+    // TODO VUID-vkCmdExecuteCommands-pCommandBuffers-00099
+    // TODO VUID-vkCmdExecuteCommands-contents-06018
+    pub fn cmd_execute_commands(
+        &mut self,
+        command_buffers: Vec<CommandBuffer<{ SECONDARY }, { EXECUTABLE }, SCOPE>>,
+    ) {
+        // MUST VUID-vkCmdExecuteCommands-commandBufferCount-arraylength
+        if command_buffers.is_empty() {
+            panic!("VUID-vkCmdExecuteCommands-commandBufferCount-arraylength");
+        }
+        let vk_command_buffers: SmallVec<[ash::vk::CommandBuffer; 8]> = command_buffers
+            .iter()
+            .map(|command_buffer| command_buffer.vk_command_buffer)
+            .collect();
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            // MUST VUID-vkCmdExecuteCommands-pCommandBuffers-00089
+            if command_buffers.iter().any(CommandBuffer::is_reset_by_pool) {
+                panic!("VUID-vkCmdExecuteCommands-pCommandBuffers-00089");
+            }
+            self.device
+                .ash_device
+                .cmd_execute_commands(self.vk_command_buffer, vk_command_buffers.as_slice());
+        }
+        for command_buffer in command_buffers {
+            self.holding_resources
+                .secondary_command_buffers
+                .push(unsafe { std::mem::transmute(command_buffer) });
+        }
+    }
+}
+
+impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RECORDING }, SCOPE> {
+    // DONE VUID-vkCmdSetDeviceMask-commandBuffer-recording
+    // TODO VUID-vkCmdSetDeviceMask-deviceMask-00110
+    // TODO VUID-vkCmdSetDeviceMask-deviceMask-00111
+    pub fn cmd_set_device_mask(&mut self, device_mask: u32) {
+        // MUST VUID-vkCmdSetDeviceMask-deviceMask-00108
+        if device_mask >> self.device.physical_device_count != 0 {
+            panic!("VUID-vkCmdSetDeviceMask-deviceMask-00108");
+        }
+        // MUST VUID-vkCmdSetDeviceMask-deviceMask-00109
+        if device_mask == 0 {
+            panic!("VUID-vkCmdSetDeviceMask-deviceMask-00109");
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device
+                .ash_device
+                .cmd_set_device_mask(self.vk_command_buffer, device_mask);
+        }
+    }
+    // TODO cmd_dispatch_base, needs compute pipelines created with
+    // ash::vk::PipelineCreateFlags::DISPATCH_BASE
+}
+
 impl CommandPool {
+    // e.g. `allocate_command_buffers::<{ SECONDARY }>(n)` for secondary command buffers
     pub fn allocate_command_buffers<const LEVEL: Level>(
         self: Arc<Self>,
         command_buffer_count: u32,
@@ -298,7 +502,6 @@ impl CommandPool {
                 .ash_device
                 .allocate_command_buffers(&create_info)?
         };
-
         let buffers = vk_buffers
             .into_iter()
             .map(|vk_command_buffer| CommandBuffer {
@@ -307,7 +510,8 @@ impl CommandPool {
                 vk_command_buffer,
                 inheritance_info: DEFAULT_INHERITANCE_INFO.clone(),
                 holding_resources: Default::default(),
-                one_time_submit: false
+                one_time_submit: false,
+                generation: 0,
             })
             .collect();
         Ok(buffers)
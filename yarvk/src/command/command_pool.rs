@@ -3,6 +3,7 @@ use crate::device_features::Feature;
 use crate::device_features::PhysicalDeviceVulkan11Features::ProtectedMemory;
 use crate::physical_device::queue_falmily_properties::QueueFamilyProperties;
 use parking_lot::RwLock;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
 
 pub enum CommandPoolCreateFlags {
@@ -27,6 +28,14 @@ impl CommandPoolCreateFlags {
 pub struct CommandPool {
     pub device: Arc<Device>,
     pub(crate) vk_command_pool: RwLock<ash::vk::CommandPool>,
+    // bumped by `reset`, command buffers begun in an older generation are back in the initial
+    // state no matter what their typestate says
+    pub(crate) generation: AtomicU64,
+    // command buffers of this pool in the recording state
+    pub(crate) recording_count: AtomicU32,
+    // command buffers of this pool submitted and not waited yet
+    pub(crate) pending_count: AtomicU32,
+    pub(crate) protected: bool,
 }
 
 impl CommandPool {
@@ -39,6 +48,47 @@ impl CommandPool {
     }
 }
 
+impl CommandPool {
+    // return unused memory of the pool to the system, command buffers are not affected
+    pub fn trim(&self) {
+        unsafe {
+            // Host Synchronization: commandPool
+            let vk_command_pool = self.vk_command_pool.write();
+            self.device
+                .ash_device
+                .trim_command_pool(*vk_command_pool, ash::vk::CommandPoolTrimFlags::empty());
+        }
+    }
+
+    // Reset all command buffers allocated from the pool at once. Executable command buffers of
+    // the pool are back in the initial state, they can be recorded or reset again, but panic if
+    // submitted or executed without being recorded again.
+    pub fn reset(&self, release_resources: bool) -> Result<(), ash::vk::Result> {
+        let flags = if release_resources {
+            ash::vk::CommandPoolResetFlags::RELEASE_RESOURCES
+        } else {
+            ash::vk::CommandPoolResetFlags::empty()
+        };
+        // Host Synchronization: commandPool
+        let vk_command_pool = self.vk_command_pool.write();
+        // MUST VUID-vkResetCommandPool-commandPool-00040
+        if self.pending_count.load(Ordering::Acquire) != 0 {
+            panic!("VUID-vkResetCommandPool-commandPool-00040");
+        }
+        // the typestate of a recording command buffer can not follow the reset
+        if self.recording_count.load(Ordering::Acquire) != 0 {
+            panic!("command buffers allocated from the command pool are being recorded");
+        }
+        unsafe {
+            self.device
+                .ash_device
+                .reset_command_pool(*vk_command_pool, flags)?;
+        }
+        self.generation.fetch_add(1, Ordering::Release);
+        Ok(())
+    }
+}
+
 impl Drop for CommandPool {
     fn drop(&mut self) {
         unsafe {
@@ -82,6 +132,12 @@ impl CommandPoolBuilder {
         Ok(Arc::new(CommandPool {
             device: self.device,
             vk_command_pool,
+            generation: AtomicU64::new(0),
+            recording_count: AtomicU32::new(0),
+            pending_count: AtomicU32::new(0),
+            protected: self
+                .flags
+                .contains(ash::vk::CommandPoolCreateFlags::PROTECTED),
         }))
     }
 }
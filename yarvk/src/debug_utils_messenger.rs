@@ -117,6 +117,10 @@ impl DebugUtilsMessengerCreateInfoEXTBuilder {
         &mut self,
         severity: ash::vk::DebugUtilsMessageSeverityFlagsEXT,
     ) -> &mut Self {
+        // MUST VUID-VkDebugUtilsMessengerCreateInfoEXT-messageSeverity-requiredbitmask
+        if severity.is_empty() {
+            panic!("VUID-VkDebugUtilsMessengerCreateInfoEXT-messageSeverity-requiredbitmask");
+        }
         self.inner.message_severity = severity;
         self
     }
@@ -124,10 +128,16 @@ impl DebugUtilsMessengerCreateInfoEXTBuilder {
         self.set_severity(severity);
         self
     }
+    // the callback is only called for messages of these types, e.g. leave out PERFORMANCE to
+    // suppress performance warnings
     pub fn set_message_type(
         &mut self,
         message_type: ash::vk::DebugUtilsMessageTypeFlagsEXT,
     ) -> &mut Self {
+        // MUST VUID-VkDebugUtilsMessengerCreateInfoEXT-messageType-requiredbitmask
+        if message_type.is_empty() {
+            panic!("VUID-VkDebugUtilsMessengerCreateInfoEXT-messageType-requiredbitmask");
+        }
         self.inner.message_type = message_type;
         self
     }
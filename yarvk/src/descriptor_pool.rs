@@ -1,16 +1,30 @@
+use crate::acceleration_structure::AccelerationStructure;
 use crate::buffer::{Buffer, BufferView};
 use crate::command::command_buffer::State::RECORDING;
 use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
 use crate::device::Device;
 use crate::device_features::Feature;
 use crate::device_features::PhysicalDeviceMutableDescriptorTypeFeaturesVALVE::MutableDescriptorType;
+use crate::device_features::PhysicalDeviceVulkan12Features::{
+    DescriptorBindingPartiallyBound, DescriptorBindingSampledImageUpdateAfterBind,
+    DescriptorBindingStorageBufferUpdateAfterBind, DescriptorBindingStorageImageUpdateAfterBind,
+    DescriptorBindingStorageTexelBufferUpdateAfterBind,
+    DescriptorBindingUniformBufferUpdateAfterBind,
+    DescriptorBindingUniformTexelBufferUpdateAfterBind, DescriptorBindingUpdateUnusedWhilePending,
+    DescriptorBindingVariableDescriptorCount,
+};
+use crate::device_features::PhysicalDeviceVulkan13Features::{
+    DescriptorBindingInlineUniformBlockUpdateAfterBind, InlineUniformBlock,
+};
 use crate::image::image_view::ImageView;
+use crate::image::is_depth_stencil_format;
 use crate::pipeline::shader_stage::ShaderStageFlags;
 use crate::pipeline::PipelineLayout;
 use crate::sampler::Sampler;
 
 use parking_lot::RwLock;
 use rustc_hash::FxHashMap;
+use smallvec::SmallVec;
 use std::sync::Arc;
 
 pub enum DescriptorPoolCreateFlags {
@@ -48,6 +62,7 @@ impl DescriptorPool {
             flags: Default::default(),
             max_sets: 1,
             descriptor_pool_sizes: Default::default(),
+            max_inline_uniform_block_bindings: 0,
         }
     }
 }
@@ -70,6 +85,7 @@ pub struct DescriptorPoolBuilder {
     flags: ash::vk::DescriptorPoolCreateFlags,
     max_sets: u32,
     descriptor_pool_sizes: Vec<ash::vk::DescriptorPoolSize>,
+    max_inline_uniform_block_bindings: u32,
 }
 
 impl DescriptorPoolBuilder {
@@ -85,16 +101,38 @@ impl DescriptorPoolBuilder {
         mut self,
         descriptor_pool_size: ash::vk::DescriptorPoolSize,
     ) -> Self {
+        // MUST VUID-VkDescriptorPoolSize-type-02218
+        if descriptor_pool_size.ty == ash::vk::DescriptorType::INLINE_UNIFORM_BLOCK
+            && descriptor_pool_size.descriptor_count % 4 != 0
+        {
+            panic!("VUID-VkDescriptorPoolSize-type-02218");
+        }
         self.descriptor_pool_sizes.push(descriptor_pool_size);
         self
     }
+    // the number of inline uniform block bindings of all sets allocated from the pool, the
+    // bytes of the blocks are added with an INLINE_UNIFORM_BLOCK descriptor pool size
+    pub fn max_inline_uniform_block_bindings(
+        mut self,
+        max_inline_uniform_block_bindings: u32,
+        _feature: Feature<{ InlineUniformBlock.into() }>,
+    ) -> Self {
+        self.max_inline_uniform_block_bindings = max_inline_uniform_block_bindings;
+        self
+    }
 
     pub fn build(self) -> Result<Arc<DescriptorPool>, ash::vk::Result> {
-        let info = ash::vk::DescriptorPoolCreateInfo::builder()
+        let mut inline_uniform_block_info =
+            ash::vk::DescriptorPoolInlineUniformBlockCreateInfo::builder()
+                .max_inline_uniform_block_bindings(self.max_inline_uniform_block_bindings);
+        let mut info = ash::vk::DescriptorPoolCreateInfo::builder()
             .flags(self.flags)
             .max_sets(self.max_sets)
-            .pool_sizes(self.descriptor_pool_sizes.as_slice())
-            .build();
+            .pool_sizes(self.descriptor_pool_sizes.as_slice());
+        if self.max_inline_uniform_block_bindings != 0 {
+            info = info.push_next(&mut inline_uniform_block_info);
+        }
+        let info = info.build();
         unsafe {
             // Host Synchronization: none
             let ash_vk_descriptor_pool =
@@ -115,6 +153,7 @@ pub struct DescriptorSetLayoutBinding {
     stage_flags: ash::vk::ShaderStageFlags,
     p_immutable_samplers: Vec<Arc<Sampler>>,
     ash_vk_samplers: Vec<ash::vk::Sampler>,
+    binding_flags: ash::vk::DescriptorBindingFlags,
 }
 
 impl DescriptorSetLayoutBinding {
@@ -123,6 +162,14 @@ impl DescriptorSetLayoutBinding {
             inner: Default::default(),
         }
     }
+    fn identical(&self, other: &DescriptorSetLayoutBinding) -> bool {
+        self.binding == other.binding
+            && self.descriptor_type == other.descriptor_type
+            && self.descriptor_count == other.descriptor_count
+            && self.stage_flags == other.stage_flags
+            && self.ash_vk_samplers == other.ash_vk_samplers
+            && self.binding_flags == other.binding_flags
+    }
     fn ash_builder(&self) -> ash::vk::DescriptorSetLayoutBindingBuilder {
         let builder = ash::vk::DescriptorSetLayoutBinding::builder()
             .binding(self.binding)
@@ -161,6 +208,12 @@ impl DescriptorSetLayoutBindingBuilder {
         self.inner.stage_flags |= stage_flag.to_ash();
         self
     }
+    // the stages come from reflected shader code, which needed the stage features already
+    #[cfg(feature = "spirv-reflect")]
+    pub(crate) fn ash_stage_flags(mut self, stage_flags: ash::vk::ShaderStageFlags) -> Self {
+        self.inner.stage_flags |= stage_flags;
+        self
+    }
     pub fn immutable_sampler(mut self, immutable_samplers: &[Arc<Sampler>]) -> Self {
         self.inner.descriptor_count = immutable_samplers.len() as _;
         self.inner
@@ -168,14 +221,170 @@ impl DescriptorSetLayoutBindingBuilder {
             .extend_from_slice(immutable_samplers);
         self
     }
+    // an inline uniform block binding holds `byte_size` bytes of uniform data in the set itself,
+    // write it with `WriteDescriptorSetBuilder::<DESCRIPTOR_INFO_TYPE_INLINE_UNIFORM_BLOCK>`
+    pub fn inline_uniform_block(
+        mut self,
+        byte_size: u32,
+        feature: Feature<{ InlineUniformBlock.into() }>,
+    ) -> Self {
+        // MUST VUID-VkDescriptorSetLayoutBinding-descriptorType-02209
+        if byte_size % 4 != 0 {
+            panic!("VUID-VkDescriptorSetLayoutBinding-descriptorType-02209");
+        }
+        // MUST VUID-VkDescriptorSetLayoutBinding-descriptorType-08004
+        if byte_size
+            > feature
+                .device
+                .physical_device
+                .get_physical_device_properties2::<ash::vk::PhysicalDeviceInlineUniformBlockProperties>()
+                .max_inline_uniform_block_size
+        {
+            panic!("VUID-VkDescriptorSetLayoutBinding-descriptorType-08004");
+        }
+        self.inner.descriptor_type = ash::vk::DescriptorType::INLINE_UNIFORM_BLOCK;
+        self.inner.descriptor_count = byte_size;
+        self
+    }
+    // descriptors can be left invalid if they are not dynamically used
+    // DONE VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingPartiallyBound-03013
+    pub fn partially_bound(
+        mut self,
+        _feature: Feature<{ DescriptorBindingPartiallyBound.into() }>,
+    ) -> Self {
+        self.inner.binding_flags |= ash::vk::DescriptorBindingFlags::PARTIALLY_BOUND;
+        self
+    }
+    // descriptors can be updated after the set is bound, the layout must be created with
+    // UPDATE_AFTER_BIND_POOL and the feature for the descriptor type must be enabled
+    pub fn update_after_bind(mut self) -> Self {
+        self.inner.binding_flags |= ash::vk::DescriptorBindingFlags::UPDATE_AFTER_BIND;
+        self
+    }
+    // descriptors not dynamically used can be updated while the set is in use
+    // DONE VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUpdateUnusedWhilePending-03012
+    pub fn update_unused_while_pending(
+        mut self,
+        _feature: Feature<{ DescriptorBindingUpdateUnusedWhilePending.into() }>,
+    ) -> Self {
+        self.inner.binding_flags |= ash::vk::DescriptorBindingFlags::UPDATE_UNUSED_WHILE_PENDING;
+        self
+    }
+    // the descriptor count is the upper bound, the actual count is given when allocating the set,
+    // only the binding with the largest binding number can be variable-sized
+    // DONE VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingVariableDescriptorCount-03014
+    pub fn variable_descriptor_count(
+        mut self,
+        _feature: Feature<{ DescriptorBindingVariableDescriptorCount.into() }>,
+    ) -> Self {
+        self.inner.binding_flags |= ash::vk::DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT;
+        self
+    }
     pub fn build(self) -> DescriptorSetLayoutBinding {
         self.inner
     }
 }
 
+fn check_binding_flags<'a>(
+    device: &Device,
+    flags: ash::vk::DescriptorSetLayoutCreateFlags,
+    bindings: impl Iterator<Item = &'a DescriptorSetLayoutBinding> + Clone,
+) {
+    if bindings
+        .clone()
+        .all(|binding| binding.binding_flags.is_empty())
+    {
+        return;
+    }
+    // SILENCE VK_EXT_descriptor_indexing is promoted to 1.2, use the core struct without an
+    // extension token.
+    if device.physical_device.instance.api_version < ash::vk::API_VERSION_1_2 {
+        panic!("descriptor binding flags require api version 1.2 or later");
+    }
+    let max_binding = bindings.clone().map(|binding| binding.binding).max();
+    for binding in bindings {
+        if binding
+            .binding_flags
+            .contains(ash::vk::DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT)
+        {
+            // MUST VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-pBindingFlags-03004
+            if Some(binding.binding) != max_binding {
+                panic!("VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-pBindingFlags-03004");
+            }
+            // MUST VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-pBindingFlags-03015
+            if binding.descriptor_type == ash::vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC
+                || binding.descriptor_type == ash::vk::DescriptorType::STORAGE_BUFFER_DYNAMIC
+            {
+                panic!("VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-pBindingFlags-03015");
+            }
+        }
+        if !binding
+            .binding_flags
+            .contains(ash::vk::DescriptorBindingFlags::UPDATE_AFTER_BIND)
+        {
+            continue;
+        }
+        // MUST VUID-VkDescriptorSetLayoutCreateInfo-flags-03000
+        if !flags.contains(ash::vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL) {
+            panic!("VUID-VkDescriptorSetLayoutCreateInfo-flags-03000");
+        }
+        let (feature, vuid) = match binding.descriptor_type {
+            // MUST VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUniformBufferUpdateAfterBind-03005
+            ash::vk::DescriptorType::UNIFORM_BUFFER => (
+                DescriptorBindingUniformBufferUpdateAfterBind.into(),
+                "VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUniformBufferUpdateAfterBind-03005",
+            ),
+            // MUST VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingSampledImageUpdateAfterBind-03006
+            ash::vk::DescriptorType::SAMPLER
+            | ash::vk::DescriptorType::COMBINED_IMAGE_SAMPLER
+            | ash::vk::DescriptorType::SAMPLED_IMAGE => (
+                DescriptorBindingSampledImageUpdateAfterBind.into(),
+                "VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingSampledImageUpdateAfterBind-03006",
+            ),
+            // MUST VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageImageUpdateAfterBind-03007
+            ash::vk::DescriptorType::STORAGE_IMAGE => (
+                DescriptorBindingStorageImageUpdateAfterBind.into(),
+                "VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageImageUpdateAfterBind-03007",
+            ),
+            // MUST VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageBufferUpdateAfterBind-03008
+            ash::vk::DescriptorType::STORAGE_BUFFER => (
+                DescriptorBindingStorageBufferUpdateAfterBind.into(),
+                "VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageBufferUpdateAfterBind-03008",
+            ),
+            // MUST VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUniformTexelBufferUpdateAfterBind-03009
+            ash::vk::DescriptorType::UNIFORM_TEXEL_BUFFER => (
+                DescriptorBindingUniformTexelBufferUpdateAfterBind.into(),
+                "VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUniformTexelBufferUpdateAfterBind-03009",
+            ),
+            // MUST VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageTexelBufferUpdateAfterBind-03010
+            ash::vk::DescriptorType::STORAGE_TEXEL_BUFFER => (
+                DescriptorBindingStorageTexelBufferUpdateAfterBind.into(),
+                "VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageTexelBufferUpdateAfterBind-03010",
+            ),
+            // MUST VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingInlineUniformBlockUpdateAfterBind-02211
+            ash::vk::DescriptorType::INLINE_UNIFORM_BLOCK => (
+                DescriptorBindingInlineUniformBlockUpdateAfterBind.into(),
+                "VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingInlineUniformBlockUpdateAfterBind-02211",
+            ),
+            // MUST VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-None-03011
+            _ => panic!("VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-None-03011"),
+        };
+        if !device.enabled_features.contains(&feature) {
+            panic!("{}", vuid);
+        }
+    }
+}
+
+pub struct DescriptorSetLayoutSupport {
+    pub supported: bool,
+    // the maximum count of a variable-sized binding, 0 if the layout has none
+    pub max_variable_descriptor_count: u32,
+}
+
 pub struct DescriptorSetLayout {
     pub device: Arc<Device>,
     pub(crate) ash_vk_descriptor_set_layout: ash::vk::DescriptorSetLayout,
+    flags: ash::vk::DescriptorSetLayoutCreateFlags,
     bindings: FxHashMap<u32, DescriptorSetLayoutBinding>,
 }
 
@@ -187,6 +396,67 @@ impl DescriptorSetLayout {
             bindings: Default::default(),
         }
     }
+    // query if a layout can be created before creating it, e.g. with many descriptors
+    pub fn get_support(
+        device: &Device,
+        flags: ash::vk::DescriptorSetLayoutCreateFlags,
+        bindings: &[DescriptorSetLayoutBinding],
+    ) -> DescriptorSetLayoutSupport {
+        let ash_bindings = bindings
+            .iter()
+            .map(|binding| binding.ash_builder().build())
+            .collect::<Vec<_>>();
+        check_binding_flags(device, flags, bindings.iter());
+        // DONE VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-bindingCount-03002
+        let binding_flags = bindings
+            .iter()
+            .map(|binding| binding.binding_flags)
+            .collect::<Vec<_>>();
+        let mut binding_flags_create_info =
+            ash::vk::DescriptorSetLayoutBindingFlagsCreateInfo::builder()
+                .binding_flags(binding_flags.as_slice())
+                .build();
+        let mut create_info = ash::vk::DescriptorSetLayoutCreateInfo::builder()
+            .flags(flags)
+            .bindings(ash_bindings.as_slice());
+        if binding_flags.iter().any(|flags| !flags.is_empty()) {
+            create_info = create_info.push_next(&mut binding_flags_create_info);
+        }
+        let create_info = create_info.build();
+        let mut variable_count_support =
+            ash::vk::DescriptorSetVariableDescriptorCountLayoutSupport::default();
+        let mut support = ash::vk::DescriptorSetLayoutSupport::builder();
+        // SILENCE VK_EXT_descriptor_indexing is promoted to 1.2, use the core struct without an
+        // extension token, report 0 variable descriptors before 1.2.
+        if device.physical_device.instance.api_version >= ash::vk::API_VERSION_1_2 {
+            support = support.push_next(&mut variable_count_support);
+        }
+        let mut support = support.build();
+        unsafe {
+            // Host Synchronization: none
+            device
+                .ash_device
+                .get_descriptor_set_layout_support(&create_info, &mut support);
+        }
+        DescriptorSetLayoutSupport {
+            supported: support.supported == ash::vk::TRUE,
+            max_variable_descriptor_count: variable_count_support.max_variable_descriptor_count,
+        }
+    }
+    // two layouts are compatible if they are the same object or identically defined
+    pub fn is_compatible(&self, other: &DescriptorSetLayout) -> bool {
+        if self.ash_vk_descriptor_set_layout == other.ash_vk_descriptor_set_layout {
+            return true;
+        }
+        self.flags == other.flags
+            && self.bindings.len() == other.bindings.len()
+            && self.bindings.iter().all(|(index, binding)| {
+                other
+                    .bindings
+                    .get(index)
+                    .map_or(false, |other| binding.identical(other))
+            })
+    }
 }
 
 pub struct DescriptorSetLayoutBuilder {
@@ -209,15 +479,29 @@ impl DescriptorSetLayoutBuilder {
         self
     }
     pub fn build(self) -> Result<Arc<DescriptorSetLayout>, ash::vk::Result> {
+        check_binding_flags(&self.device, self.flags, self.bindings.values());
         let bindings = self
             .bindings
             .iter()
             .map(|(_, binding)| binding.ash_builder().build())
             .collect::<Vec<_>>();
-        let create_info = ash::vk::DescriptorSetLayoutCreateInfo::builder()
+        // DONE VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-bindingCount-03002
+        let binding_flags = self
+            .bindings
+            .values()
+            .map(|binding| binding.binding_flags)
+            .collect::<Vec<_>>();
+        let mut binding_flags_create_info =
+            ash::vk::DescriptorSetLayoutBindingFlagsCreateInfo::builder()
+                .binding_flags(binding_flags.as_slice())
+                .build();
+        let mut create_info = ash::vk::DescriptorSetLayoutCreateInfo::builder()
             .flags(self.flags)
-            .bindings(bindings.as_slice())
-            .build();
+            .bindings(bindings.as_slice());
+        if binding_flags.iter().any(|flags| !flags.is_empty()) {
+            create_info = create_info.push_next(&mut binding_flags_create_info);
+        }
+        let create_info = create_info.build();
         unsafe {
             // Host Synchronization: none
             let ash_vk_descriptor_set_layout = self
@@ -227,6 +511,7 @@ impl DescriptorSetLayoutBuilder {
             Ok(Arc::new(DescriptorSetLayout {
                 device: self.device,
                 ash_vk_descriptor_set_layout,
+                flags: self.flags,
                 bindings: self.bindings,
             }))
         }
@@ -349,6 +634,8 @@ impl DescriptorBufferInfo {
 pub const DESCRIPTOR_INFO_TYPE_IMAGE: usize = 0;
 pub const DESCRIPTOR_INFO_TYPE_BUFFER: usize = 1;
 pub const DESCRIPTOR_INFO_TYPE_TEXEL_BUFFER_VIEW: usize = 2;
+pub const DESCRIPTOR_INFO_TYPE_ACCELERATION_STRUCTURE: usize = 3;
+pub const DESCRIPTOR_INFO_TYPE_INLINE_UNIFORM_BLOCK: usize = 4;
 
 pub struct WriteDescriptorSet {
     pub(crate) dst_set: Arc<DescriptorSet>,
@@ -357,9 +644,12 @@ pub struct WriteDescriptorSet {
     p_image_info: Vec<DescriptorImageInfo>,
     p_buffer_info: Vec<DescriptorBufferInfo>,
     p_texel_buffer_view: Vec<Arc<BufferView>>,
+    p_acceleration_structures: Vec<Arc<AccelerationStructure>>,
     ash_vk_image_infos: Vec<ash::vk::DescriptorImageInfo>,
     ash_vk_buffer_infos: Vec<ash::vk::DescriptorBufferInfo>,
     ash_vk_buffer_views: Vec<ash::vk::BufferView>,
+    ash_vk_acceleration_structures: Vec<ash::vk::AccelerationStructureKHR>,
+    inline_uniform_block_data: Vec<u8>,
     which_info: usize,
 }
 
@@ -375,9 +665,12 @@ impl WriteDescriptorSet {
                 p_image_info: vec![],
                 p_buffer_info: vec![],
                 p_texel_buffer_view: vec![],
+                p_acceleration_structures: vec![],
                 ash_vk_image_infos: vec![],
                 ash_vk_buffer_infos: vec![],
                 ash_vk_buffer_views: vec![],
+                ash_vk_acceleration_structures: vec![],
+                inline_uniform_block_data: vec![],
                 dst_set,
             },
         }
@@ -401,6 +694,10 @@ impl WriteDescriptorSet {
             DESCRIPTOR_INFO_TYPE_TEXEL_BUFFER_VIEW => {
                 builder.texel_buffer_view(self.ash_vk_buffer_views.as_slice())
             }
+            // the acceleration structures are chained by ash_acceleration_structure_write
+            DESCRIPTOR_INFO_TYPE_ACCELERATION_STRUCTURE => builder,
+            // the data is chained by ash_inline_uniform_block_write
+            DESCRIPTOR_INFO_TYPE_INLINE_UNIFORM_BLOCK => builder,
             _ => {
                 panic!("unsupported descriptor info")
             }
@@ -408,6 +705,33 @@ impl WriteDescriptorSet {
     }
 }
 
+impl WriteDescriptorSet {
+    pub(crate) fn ash_acceleration_structure_write(
+        &self,
+    ) -> Option<ash::vk::WriteDescriptorSetAccelerationStructureKHR> {
+        if self.which_info != DESCRIPTOR_INFO_TYPE_ACCELERATION_STRUCTURE {
+            return None;
+        }
+        Some(
+            ash::vk::WriteDescriptorSetAccelerationStructureKHR::builder()
+                .acceleration_structures(self.ash_vk_acceleration_structures.as_slice())
+                .build(),
+        )
+    }
+    pub(crate) fn ash_inline_uniform_block_write(
+        &self,
+    ) -> Option<ash::vk::WriteDescriptorSetInlineUniformBlock> {
+        if self.which_info != DESCRIPTOR_INFO_TYPE_INLINE_UNIFORM_BLOCK {
+            return None;
+        }
+        Some(
+            ash::vk::WriteDescriptorSetInlineUniformBlock::builder()
+                .data(self.inline_uniform_block_data.as_slice())
+                .build(),
+        )
+    }
+}
+
 pub struct WriteDescriptorSetBuilder<const INFO_TYPE: usize> {
     inner: WriteDescriptorSet,
 }
@@ -429,6 +753,15 @@ impl<const INFO_TYPE: usize> WriteDescriptorSetBuilder<INFO_TYPE> {
 
 impl WriteDescriptorSetBuilder<DESCRIPTOR_INFO_TYPE_IMAGE> {
     pub fn add_image_info(mut self, image_info: DescriptorImageInfo) -> Self {
+        // MUST VUID-VkDescriptorImageInfo-imageView-01976
+        if is_depth_stencil_format(image_info.image_view.image.image_create_info.format)
+            && image_info
+                .image_view
+                .aspect_mask
+                .contains(ash::vk::ImageAspectFlags::DEPTH | ash::vk::ImageAspectFlags::STENCIL)
+        {
+            panic!("VUID-VkDescriptorImageInfo-imageView-01976");
+        }
         self.inner.p_image_info.push(image_info);
         self
     }
@@ -478,6 +811,48 @@ impl WriteDescriptorSetBuilder<DESCRIPTOR_INFO_TYPE_TEXEL_BUFFER_VIEW> {
     }
 }
 
+impl WriteDescriptorSetBuilder<DESCRIPTOR_INFO_TYPE_ACCELERATION_STRUCTURE> {
+    pub fn add_acceleration_structure(
+        mut self,
+        acceleration_structure: Arc<AccelerationStructure>,
+    ) -> Self {
+        self.inner
+            .p_acceleration_structures
+            .push(acceleration_structure);
+        self
+    }
+    pub fn build(mut self) -> WriteDescriptorSet {
+        self.inner.which_info = DESCRIPTOR_INFO_TYPE_ACCELERATION_STRUCTURE;
+        self.inner.ash_vk_acceleration_structures = self
+            .inner
+            .p_acceleration_structures
+            .iter()
+            .map(|a| a.vk_acceleration_structure)
+            .collect::<Vec<_>>();
+        self.inner
+    }
+}
+
+impl WriteDescriptorSetBuilder<DESCRIPTOR_INFO_TYPE_INLINE_UNIFORM_BLOCK> {
+    // `dst_array_element` is the byte offset into the block
+    pub fn data(mut self, data: &[u8]) -> Self {
+        self.inner.inline_uniform_block_data = data.to_vec();
+        self
+    }
+    pub fn build(mut self) -> WriteDescriptorSet {
+        // MUST VUID-VkWriteDescriptorSet-descriptorType-02219
+        if self.inner.dst_array_element % 4 != 0 {
+            panic!("VUID-VkWriteDescriptorSet-descriptorType-02219");
+        }
+        // MUST VUID-VkWriteDescriptorSet-descriptorType-02220
+        if self.inner.inline_uniform_block_data.len() % 4 != 0 {
+            panic!("VUID-VkWriteDescriptorSet-descriptorType-02220");
+        }
+        self.inner.which_info = DESCRIPTOR_INFO_TYPE_INLINE_UNIFORM_BLOCK;
+        self.inner
+    }
+}
+
 pub struct CopyDescriptorSet {
     pub(crate) src_set: Arc<DescriptorSet>,
     src_binding: u32,
@@ -546,9 +921,21 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
         descriptor_sets: &[Arc<DescriptorSet>],
         dynamic_offsets: &[u32],
     ) {
-        // TODO performance (vec new)
-        let mut locks = Vec::new();
-        let mut vk_descriptor_sets = Vec::new();
+        let mut locks = SmallVec::<[_; 4]>::with_capacity(descriptor_sets.len());
+        let mut vk_descriptor_sets =
+            SmallVec::<[ash::vk::DescriptorSet; 4]>::with_capacity(descriptor_sets.len());
+        #[cfg(debug_assertions)]
+        {
+            let bound_set_layouts = self
+                .holding_resources
+                .bound_set_layouts
+                .entry(pipeline_bind_point)
+                .or_default();
+            for (index, set) in descriptor_sets.iter().enumerate() {
+                bound_set_layouts
+                    .insert(first_set + index as u32, set.descriptor_set_layout.clone());
+            }
+        }
         for set in descriptor_sets.iter() {
             let lock = set.ash_vk_descriptor_set.read();
             let vk_ds = *lock;
@@ -570,3 +957,39 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
         }
     }
 }
+
+impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RECORDING }, SCOPE> {
+    // Every set bound so far must be compatible with the set layout of the same index in the
+    // layout of the bound pipeline, sets not bound are not checked since they may be unused.
+    #[cfg(debug_assertions)]
+    pub(crate) fn check_bound_descriptor_sets(
+        &self,
+        pipeline_bind_point: ash::vk::PipelineBindPoint,
+    ) {
+        let pipeline_layout = match self
+            .holding_resources
+            .bound_pipeline_layouts
+            .get(&pipeline_bind_point)
+        {
+            Some(pipeline_layout) => pipeline_layout,
+            None => return,
+        };
+        if let Some(bound_set_layouts) = self
+            .holding_resources
+            .bound_set_layouts
+            .get(&pipeline_bind_point)
+        {
+            for (index, set_layout) in bound_set_layouts {
+                if let Some(pipeline_set_layout) = pipeline_layout.set_layouts.get(*index as usize)
+                {
+                    if !set_layout.is_compatible(pipeline_set_layout) {
+                        panic!(
+                            "descriptor set bound at index {} is not compatible with the layout of the bound pipeline",
+                            index
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
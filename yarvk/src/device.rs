@@ -1,15 +1,20 @@
 use crate::descriptor_pool::DescriptorSet;
 use crate::descriptor_pool::{CopyDescriptorSet, WriteDescriptorSet};
+use crate::device_features::PhysicalDeviceVulkan11Features::ProtectedMemory;
 use crate::device_features::{register_features, Feature, FeatureType};
 use crate::extensions::{DeviceExtension, DeviceExtensionType, PhysicalDeviceExtensionType};
+use crate::fence::FenceReactor;
 use crate::physical_device::queue_falmily_properties::QueueFamilyProperties;
+use crate::instance::PhysicalDeviceGroup;
 use crate::physical_device::PhysicalDevice;
 use crate::queue::Queue;
+use crate::transform_feedback::TransformFeedbackProperties;
 use ash::vk::Handle;
 
-use parking_lot::RwLockWriteGuard;
+use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::cell::Cell;
+use std::ffi::{CStr, CString};
 
 use std::sync::atomic::AtomicU32;
 use std::sync::Arc;
@@ -17,6 +22,8 @@ use std::sync::Arc;
 pub struct DeviceQueueCreateInfoBuilder {
     queue_family: QueueFamilyProperties,
     priorities: Vec<f32>,
+    global_priority: Option<ash::vk::DeviceQueueGlobalPriorityCreateInfoKHR>,
+    flags: ash::vk::DeviceQueueCreateFlags,
 }
 
 impl DeviceQueueCreateInfoBuilder {
@@ -25,10 +32,43 @@ impl DeviceQueueCreateInfoBuilder {
         self
     }
 
+    // device creation fails with ERROR_NOT_PERMITTED_KHR if the caller is not allowed to use
+    // the requested priority
+    pub fn global_priority(
+        mut self,
+        _extension: DeviceExtension<{ PhysicalDeviceExtensionType::KhrGlobalPriority }>,
+        global_priority: ash::vk::QueueGlobalPriorityKHR,
+    ) -> Self {
+        self.global_priority = Some(
+            ash::vk::DeviceQueueGlobalPriorityCreateInfoKHR::builder()
+                .global_priority(global_priority)
+                .build(),
+        );
+        self
+    }
+
+    // create protected-capable queues, which can submit protected command buffers
+    // DONE VUID-VkDeviceQueueCreateInfo-flags-02861
+    pub fn protected(mut self, _feature: Feature<{ ProtectedMemory.into() }>) -> Self {
+        // MUST VUID-VkDeviceQueueCreateInfo-flags-06449
+        if !self
+            .queue_family
+            .property
+            .queue_flags
+            .contains(ash::vk::QueueFlags::PROTECTED)
+        {
+            panic!("VUID-VkDeviceQueueCreateInfo-flags-06449");
+        }
+        self.flags |= ash::vk::DeviceQueueCreateFlags::PROTECTED;
+        self
+    }
+
     pub fn build(self) -> DeviceQueueCreateInfo {
         DeviceQueueCreateInfo {
             queue_family: self.queue_family,
             priorities: self.priorities,
+            global_priority: self.global_priority,
+            flags: self.flags,
         }
     }
 }
@@ -36,6 +76,8 @@ impl DeviceQueueCreateInfoBuilder {
 pub struct DeviceQueueCreateInfo {
     queue_family: QueueFamilyProperties,
     priorities: Vec<f32>,
+    global_priority: Option<ash::vk::DeviceQueueGlobalPriorityCreateInfoKHR>,
+    flags: ash::vk::DeviceQueueCreateFlags,
 }
 
 impl DeviceQueueCreateInfo {
@@ -44,12 +86,19 @@ impl DeviceQueueCreateInfo {
         DeviceQueueCreateInfoBuilder {
             queue_family,
             priorities: Default::default(),
+            global_priority: None,
+            flags: Default::default(),
         }
     }
-    fn ash_builder(&self) -> ash::vk::DeviceQueueCreateInfoBuilder {
-        ash::vk::DeviceQueueCreateInfo::builder()
+    fn ash_builder(&mut self) -> ash::vk::DeviceQueueCreateInfoBuilder {
+        let builder = ash::vk::DeviceQueueCreateInfo::builder()
+            .flags(self.flags)
             .queue_family_index(self.queue_family.index)
-            .queue_priorities(self.priorities.as_slice())
+            .queue_priorities(self.priorities.as_slice());
+        match &mut self.global_priority {
+            Some(global_priority) => builder.push_next(global_priority),
+            None => builder,
+        }
     }
 }
 
@@ -58,6 +107,8 @@ pub struct DeviceBuilder {
     device_queue_create_infos: Vec<DeviceQueueCreateInfo>,
     enabled_features: FxHashSet<FeatureType>,
     enabled_extensions: FxHashSet<PhysicalDeviceExtensionType>,
+    raw_extensions: FxHashSet<CString>,
+    device_group: Vec<ash::vk::PhysicalDevice>,
 }
 
 impl DeviceBuilder {
@@ -71,6 +122,18 @@ impl DeviceBuilder {
         self
     }
 
+    pub fn enable_feature<T: Into<FeatureType>>(
+        mut self,
+        feature: T,
+    ) -> Result<Self, ash::vk::Result> {
+        let feature = feature.into();
+        if !self.physical_device.supports_feature(feature) {
+            return Err(ash::vk::Result::ERROR_FEATURE_NOT_PRESENT);
+        }
+        self.enabled_features.insert(feature);
+        Ok(self)
+    }
+
     fn add_extension_inner(&mut self, extension: &DeviceExtensionType) {
         let physical_ext_ty = extension.into();
         self.enabled_extensions.insert(physical_ext_ty);
@@ -88,6 +151,34 @@ impl DeviceBuilder {
         self
     }
 
+    // Enable an extension by name, no `DeviceExtension` token can be retrieved for it and its
+    // dependencies are not enabled automatically.
+    pub fn add_extension_raw(mut self, extension: &CStr) -> Self {
+        self.raw_extensions.insert(extension.to_owned());
+        self
+    }
+
+    // Create the device across all physical devices of `group`, which must contain the physical
+    // device of the builder
+    pub fn device_group(mut self, group: &PhysicalDeviceGroup) -> Self {
+        // MUST VUID-VkDeviceGroupDeviceCreateInfo-physicalDeviceCount-00377
+        if !group
+            .physical_devices
+            .iter()
+            .any(|pdevice| pdevice.vk_physical_device == self.physical_device.vk_physical_device)
+        {
+            panic!("VUID-VkDeviceGroupDeviceCreateInfo-physicalDeviceCount-00377");
+        }
+        // DONE VUID-VkDeviceGroupDeviceCreateInfo-pPhysicalDevices-00375
+        // DONE VUID-VkDeviceGroupDeviceCreateInfo-pPhysicalDevices-00376
+        self.device_group = group
+            .physical_devices
+            .iter()
+            .map(|pdevice| pdevice.vk_physical_device)
+            .collect();
+        self
+    }
+
     pub fn build(
         mut self,
     ) -> Result<(Arc<Device>, FxHashMap<QueueFamilyProperties, Vec<Queue>>), ash::vk::Result> {
@@ -107,23 +198,83 @@ impl DeviceBuilder {
             panic!("VUID-VkDeviceCreateInfo-queueCreateInfoCount-arraylength");
         }
 
+        // storage features like StorageBuffer16BitAccess can be enabled either by the promoted
+        // struct or by the VulkanXXFeatures struct, but not both.
+        let has_feature = |f: fn(&FeatureType) -> bool| self.enabled_features.iter().any(f);
+        // MUST VUID-VkDeviceCreateInfo-pNext-02829
+        if has_feature(|f| matches!(f, FeatureType::DeviceVulkan11Features(_)))
+            && has_feature(|f| {
+                matches!(
+                    f,
+                    FeatureType::Device16BitStorageFeatures(_)
+                        | FeatureType::DeviceMultiviewFeatures(_)
+                        | FeatureType::DeviceVariablePointersFeatures(_)
+                        | FeatureType::DeviceProtectedMemoryFeatures(_)
+                        | FeatureType::DeviceSamplerYcbcrConversionFeatures(_)
+                )
+            })
+        {
+            panic!("VUID-VkDeviceCreateInfo-pNext-02829");
+        }
+        // MUST VUID-VkDeviceCreateInfo-pNext-02830
+        if has_feature(|f| matches!(f, FeatureType::DeviceVulkan12Features(_)))
+            && has_feature(|f| {
+                matches!(
+                    f,
+                    FeatureType::Device8BitStorageFeatures(_)
+                        | FeatureType::DeviceShaderAtomicInt64Features(_)
+                        | FeatureType::DeviceShaderFloat16Int8Features(_)
+                        | FeatureType::DeviceDescriptorIndexingFeatures(_)
+                        | FeatureType::DeviceScalarBlockLayoutFeatures(_)
+                        | FeatureType::DeviceImagelessFramebufferFeatures(_)
+                        | FeatureType::DeviceUniformBufferStandardLayoutFeatures(_)
+                        | FeatureType::DeviceShaderSubgroupExtendedTypesFeatures(_)
+                        | FeatureType::DeviceSeparateDepthStencilLayoutsFeatures(_)
+                        | FeatureType::DeviceHostQueryResetFeatures(_)
+                        | FeatureType::DeviceTimelineSemaphoreFeatures(_)
+                        | FeatureType::DeviceBufferDeviceAddressFeatures(_)
+                        | FeatureType::DeviceVulkanMemoryModelFeatures(_)
+                )
+            })
+        {
+            panic!("VUID-VkDeviceCreateInfo-pNext-02830");
+        }
+
         let mut device_create_info = ash::vk::DeviceCreateInfo::builder();
+        // the chained feature structs must outlive vkCreateDevice
+        let (feature2, _feature_structs) = register_features(&self.enabled_features);
         unsafe {
-            let feature2 = register_features(&self.enabled_features);
             // SILENCE VUID-VkDeviceCreateInfo-pNext-00373
             device_create_info.p_next = std::mem::transmute(&feature2);
         }
+        let mut device_group_info = ash::vk::DeviceGroupDeviceCreateInfo::builder()
+            .physical_devices(self.device_group.as_slice())
+            .build();
+        if !self.device_group.is_empty() {
+            device_group_info.p_next = device_create_info.p_next;
+            device_create_info.p_next = &device_group_info as *const _ as _;
+        }
 
         let queue_create_infos: Vec<ash::vk::DeviceQueueCreateInfo> = self
             .device_queue_create_infos
-            .iter()
+            .iter_mut()
             .map(|q| q.ash_builder().build())
             .collect();
-        let extensions: Vec<*const i8> = self
+        let mut extensions: Vec<*const i8> = self
             .enabled_extensions
             .iter()
             .map(|extension| extension.to_cstr().as_ptr())
             .collect();
+        // skip raw extensions which are also added as typed extensions
+        for raw_extension in &self.raw_extensions {
+            if !self
+                .enabled_extensions
+                .iter()
+                .any(|extension| extension.to_cstr() == raw_extension.as_c_str())
+            {
+                extensions.push(raw_extension.as_ptr());
+            }
+        }
         let device_create_info = device_create_info
             .queue_create_infos(unsafe { std::mem::transmute(queue_create_infos.as_slice()) })
             .enabled_extension_names(extensions.as_slice());
@@ -138,34 +289,27 @@ impl DeviceBuilder {
             )?
         };
 
+        let extension_fns =
+            DeviceExtensionFns::load(&self.physical_device, &vk_device, &self.enabled_extensions);
         let device = Arc::new(Device {
             physical_device: self.physical_device,
             ash_device: vk_device,
             enabled_extensions: self.enabled_extensions,
             enabled_features: self.enabled_features,
             allocations: AtomicU32::default(),
+            queues_lock: RwLock::default(),
+            image_layouts: RwLock::default(),
+            fence_reactor: Mutex::default(),
+            destroy_on_drop: true,
+            physical_device_count: self.device_group.len().max(1) as u32,
+            extension_fns,
         });
 
-        let mut queues_map = FxHashMap::default();
+        // a family can have both protected-capable and unprotected queues, see `Queue::protected`
+        let mut queues_map: FxHashMap<QueueFamilyProperties, Vec<Queue>> = FxHashMap::default();
         self.device_queue_create_infos.into_iter().for_each(|q| {
-            let queue_family = q.queue_family;
-            let queues = q
-                .priorities
-                .iter()
-                .enumerate()
-                .map(|(index, _)| unsafe {
-                    // Host Synchronization: none
-                    let queue = device
-                        .ash_device
-                        .get_device_queue(queue_family.index, index as u32);
-                    Queue {
-                        device: device.clone(),
-                        vk_queue: queue,
-                        // queue_family_property: queue_family.property,
-                    }
-                })
-                .collect();
-            queues_map.insert(queue_family, queues);
+            let queues = device.get_queues(&q.queue_family, q.priorities.len() as u32, q.flags);
+            queues_map.entry(q.queue_family).or_default().extend(queues);
         });
 
         Ok((device, queues_map))
@@ -178,6 +322,105 @@ pub struct Device {
     pub(crate) enabled_extensions: FxHashSet<PhysicalDeviceExtensionType>,
     pub(crate) enabled_features: FxHashSet<FeatureType>,
     pub(crate) allocations: AtomicU32,
+    // queue operations hold it shared, vkDeviceWaitIdle holds it exclusively
+    pub(crate) queues_lock: RwLock<()>,
+    // last known layouts of the device's images, kept here so images hash and compare by their
+    // handle only, see `Image::layout`
+    pub(crate) image_layouts: RwLock<FxHashMap<ash::vk::Image, ash::vk::ImageLayout>>,
+    // waits on the fences of `FenceFuture`s, started by the first one that has to wait
+    pub(crate) fence_reactor: Mutex<Option<Arc<FenceReactor>>>,
+    destroy_on_drop: bool,
+    // number of physical devices in the device group, 1 if not created from a group
+    pub physical_device_count: u32,
+    extension_fns: DeviceExtensionFns,
+}
+
+// device level extension commands loaded once at device creation, `None` if the extension is not
+// enabled
+pub(crate) struct DeviceExtensionFns {
+    line_rasterization: Option<ash::vk::ExtLineRasterizationFn>,
+    transform_feedback: Option<ash::vk::ExtTransformFeedbackFn>,
+    transform_feedback_properties: Option<TransformFeedbackProperties>,
+    sample_locations: Option<ash::vk::ExtSampleLocationsFn>,
+    ray_tracing_pipeline: Option<ash::extensions::khr::RayTracingPipeline>,
+    acceleration_structure: Option<ash::extensions::khr::AccelerationStructure>,
+    external_fence_fd: Option<ash::extensions::khr::ExternalFenceFd>,
+    external_semaphore_fd: Option<ash::extensions::khr::ExternalSemaphoreFd>,
+    present_wait: Option<ash::extensions::khr::PresentWait>,
+    full_screen_exclusive: Option<ash::extensions::ext::FullScreenExclusive>,
+    shared_presentable_image: Option<ash::vk::KhrSharedPresentableImageFn>,
+    extended_dynamic_state3: Option<ash::extensions::ext::ExtendedDynamicState3>,
+    mesh_shader: Option<ash::extensions::ext::MeshShader>,
+    fragment_shading_rate: Option<ash::vk::KhrFragmentShadingRateFn>,
+    // VK_EXT_extended_dynamic_state is promoted to 1.3 and has no extension token, loaded for
+    // devices before 1.3 only
+    extended_dynamic_state: Option<ash::extensions::ext::ExtendedDynamicState>,
+    // VK_KHR_create_renderpass2 is promoted to 1.2 and has no extension token, loaded for devices
+    // before 1.2 only
+    create_render_pass2: Option<ash::extensions::khr::CreateRenderPass2>,
+}
+
+impl DeviceExtensionFns {
+    fn load(
+        physical_device: &PhysicalDevice,
+        ash_device: &ash::Device,
+        enabled_extensions: &FxHashSet<PhysicalDeviceExtensionType>,
+    ) -> Self {
+        let ash_instance = &physical_device.instance.ash_instance;
+        let mut load = |name: &CStr| unsafe {
+            std::mem::transmute(
+                ash_instance.get_device_proc_addr(ash_device.handle(), name.as_ptr()),
+            )
+        };
+        let enabled = |extension| enabled_extensions.contains(&extension);
+        DeviceExtensionFns {
+            line_rasterization: enabled(PhysicalDeviceExtensionType::ExtLineRasterization)
+                .then(|| ash::vk::ExtLineRasterizationFn::load(&mut load)),
+            transform_feedback: enabled(PhysicalDeviceExtensionType::ExtTransformFeedback)
+                .then(|| ash::vk::ExtTransformFeedbackFn::load(&mut load)),
+            transform_feedback_properties: enabled(
+                PhysicalDeviceExtensionType::ExtTransformFeedback,
+            )
+            .then(|| TransformFeedbackProperties::new(physical_device)),
+            sample_locations: enabled(PhysicalDeviceExtensionType::ExtSampleLocations)
+                .then(|| ash::vk::ExtSampleLocationsFn::load(&mut load)),
+            ray_tracing_pipeline: enabled(PhysicalDeviceExtensionType::KhrRayTracingPipeline)
+                .then(|| ash::extensions::khr::RayTracingPipeline::new(ash_instance, ash_device)),
+            acceleration_structure: enabled(PhysicalDeviceExtensionType::KhrAccelerationStructure)
+                .then(|| {
+                    ash::extensions::khr::AccelerationStructure::new(ash_instance, ash_device)
+                }),
+            external_fence_fd: enabled(PhysicalDeviceExtensionType::KhrExternalFenceFd)
+                .then(|| ash::extensions::khr::ExternalFenceFd::new(ash_instance, ash_device)),
+            external_semaphore_fd: enabled(PhysicalDeviceExtensionType::KhrExternalSemaphoreFd)
+                .then(|| {
+                    ash::extensions::khr::ExternalSemaphoreFd::new(ash_instance, ash_device)
+                }),
+            present_wait: enabled(PhysicalDeviceExtensionType::KhrPresentWait)
+                .then(|| ash::extensions::khr::PresentWait::new(ash_instance, ash_device)),
+            full_screen_exclusive: enabled(PhysicalDeviceExtensionType::ExtFullScreenExclusive)
+                .then(|| {
+                    ash::extensions::ext::FullScreenExclusive::new(ash_instance, ash_device)
+                }),
+            shared_presentable_image: enabled(
+                PhysicalDeviceExtensionType::KhrSharedPresentableImage,
+            )
+            .then(|| ash::vk::KhrSharedPresentableImageFn::load(&mut load)),
+            extended_dynamic_state3: enabled(PhysicalDeviceExtensionType::ExtExtendedDynamicState3)
+                .then(|| {
+                    ash::extensions::ext::ExtendedDynamicState3::new(ash_instance, ash_device)
+                }),
+            mesh_shader: enabled(PhysicalDeviceExtensionType::ExtMeshShader)
+                .then(|| ash::extensions::ext::MeshShader::new(ash_instance, ash_device)),
+            fragment_shading_rate: enabled(PhysicalDeviceExtensionType::KhrFragmentShadingRate)
+                .then(|| ash::vk::KhrFragmentShadingRateFn::load(&mut load)),
+            extended_dynamic_state: (physical_device.instance.api_version
+                < ash::vk::API_VERSION_1_3)
+                .then(|| ash::extensions::ext::ExtendedDynamicState::new(ash_instance, ash_device)),
+            create_render_pass2: (physical_device.instance.api_version < ash::vk::API_VERSION_1_2)
+                .then(|| ash::extensions::khr::CreateRenderPass2::new(ash_instance, ash_device)),
+        }
+    }
 }
 
 impl PartialEq for Device {
@@ -195,8 +438,185 @@ impl Device {
             device_queue_create_infos: vec![],
             enabled_features: Default::default(),
             enabled_extensions: Default::default(),
+            raw_extensions: Default::default(),
+            device_group: vec![],
         }
     }
+    /// Wrap a device created outside of yarvk.
+    ///
+    /// # Safety
+    ///
+    /// `ash_device` must be created from `physical_device` with `enabled_extensions` and
+    /// `enabled_features` enabled, `queues` must be the queue families and queue counts the
+    /// device is created with. If `destroy_on_drop` is false, the caller must destroy the device
+    /// after the returned [`Device`] and all its children are dropped.
+    pub unsafe fn from_raw(
+        physical_device: Arc<PhysicalDevice>,
+        ash_device: ash::Device,
+        enabled_extensions: FxHashSet<PhysicalDeviceExtensionType>,
+        enabled_features: FxHashSet<FeatureType>,
+        queues: Vec<(QueueFamilyProperties, u32)>,
+        destroy_on_drop: bool,
+    ) -> (Arc<Device>, FxHashMap<QueueFamilyProperties, Vec<Queue>>) {
+        let extension_fns =
+            DeviceExtensionFns::load(&physical_device, &ash_device, &enabled_extensions);
+        let device = Arc::new(Device {
+            physical_device,
+            ash_device,
+            enabled_extensions,
+            enabled_features,
+            allocations: AtomicU32::default(),
+            queues_lock: RwLock::default(),
+            image_layouts: RwLock::default(),
+            fence_reactor: Mutex::default(),
+            destroy_on_drop,
+            physical_device_count: 1,
+            extension_fns,
+        });
+        let queues_map = queues
+            .into_iter()
+            .map(|(queue_family, count)| {
+                let queues = device.get_queues(
+                    &queue_family,
+                    count,
+                    ash::vk::DeviceQueueCreateFlags::empty(),
+                );
+                (queue_family, queues)
+            })
+            .collect();
+        (device, queues_map)
+    }
+    pub(crate) fn line_rasterization_fn(&self) -> &ash::vk::ExtLineRasterizationFn {
+        self.extension_fns
+            .line_rasterization
+            .as_ref()
+            .expect("VK_EXT_line_rasterization is not enabled")
+    }
+    pub(crate) fn transform_feedback_fn(&self) -> &ash::vk::ExtTransformFeedbackFn {
+        self.extension_fns
+            .transform_feedback
+            .as_ref()
+            .expect("VK_EXT_transform_feedback is not enabled")
+    }
+    pub(crate) fn transform_feedback_properties(&self) -> &TransformFeedbackProperties {
+        self.extension_fns
+            .transform_feedback_properties
+            .as_ref()
+            .expect("VK_EXT_transform_feedback is not enabled")
+    }
+    pub(crate) fn sample_locations_fn(&self) -> &ash::vk::ExtSampleLocationsFn {
+        self.extension_fns
+            .sample_locations
+            .as_ref()
+            .expect("VK_EXT_sample_locations is not enabled")
+    }
+    pub(crate) fn ray_tracing_pipeline_loader(&self) -> &ash::extensions::khr::RayTracingPipeline {
+        self.extension_fns
+            .ray_tracing_pipeline
+            .as_ref()
+            .expect("VK_KHR_ray_tracing_pipeline is not enabled")
+    }
+    pub(crate) fn acceleration_structure_loader(
+        &self,
+    ) -> &ash::extensions::khr::AccelerationStructure {
+        self.extension_fns
+            .acceleration_structure
+            .as_ref()
+            .expect("VK_KHR_acceleration_structure is not enabled")
+    }
+    pub(crate) fn external_fence_fd_loader(&self) -> &ash::extensions::khr::ExternalFenceFd {
+        self.extension_fns
+            .external_fence_fd
+            .as_ref()
+            .expect("VK_KHR_external_fence_fd is not enabled")
+    }
+    pub(crate) fn external_semaphore_fd_loader(
+        &self,
+    ) -> &ash::extensions::khr::ExternalSemaphoreFd {
+        self.extension_fns
+            .external_semaphore_fd
+            .as_ref()
+            .expect("VK_KHR_external_semaphore_fd is not enabled")
+    }
+    pub(crate) fn present_wait_loader(&self) -> &ash::extensions::khr::PresentWait {
+        self.extension_fns
+            .present_wait
+            .as_ref()
+            .expect("VK_KHR_present_wait is not enabled")
+    }
+    pub(crate) fn full_screen_exclusive_loader(
+        &self,
+    ) -> &ash::extensions::ext::FullScreenExclusive {
+        self.extension_fns
+            .full_screen_exclusive
+            .as_ref()
+            .expect("VK_EXT_full_screen_exclusive is not enabled")
+    }
+    pub(crate) fn shared_presentable_image_fn(&self) -> &ash::vk::KhrSharedPresentableImageFn {
+        self.extension_fns
+            .shared_presentable_image
+            .as_ref()
+            .expect("VK_KHR_shared_presentable_image is not enabled")
+    }
+    pub(crate) fn extended_dynamic_state3_loader(
+        &self,
+    ) -> &ash::extensions::ext::ExtendedDynamicState3 {
+        self.extension_fns
+            .extended_dynamic_state3
+            .as_ref()
+            .expect("VK_EXT_extended_dynamic_state3 is not enabled")
+    }
+    pub(crate) fn mesh_shader_loader(&self) -> &ash::extensions::ext::MeshShader {
+        self.extension_fns
+            .mesh_shader
+            .as_ref()
+            .expect("VK_EXT_mesh_shader is not enabled")
+    }
+    pub(crate) fn fragment_shading_rate_fn(&self) -> &ash::vk::KhrFragmentShadingRateFn {
+        self.extension_fns
+            .fragment_shading_rate
+            .as_ref()
+            .expect("VK_KHR_fragment_shading_rate is not enabled")
+    }
+    pub(crate) fn extended_dynamic_state_loader(
+        &self,
+    ) -> &ash::extensions::ext::ExtendedDynamicState {
+        self.extension_fns
+            .extended_dynamic_state
+            .as_ref()
+            .expect("VK_EXT_extended_dynamic_state is only loaded for devices before 1.3")
+    }
+    pub(crate) fn create_render_pass2_loader(&self) -> &ash::extensions::khr::CreateRenderPass2 {
+        self.extension_fns
+            .create_render_pass2
+            .as_ref()
+            .expect("VK_KHR_create_renderpass2 is only loaded for devices before 1.2")
+    }
+    fn get_queues(
+        self: &Arc<Self>,
+        queue_family: &QueueFamilyProperties,
+        count: u32,
+        flags: ash::vk::DeviceQueueCreateFlags,
+    ) -> Vec<Queue> {
+        (0..count)
+            .map(|index| unsafe {
+                // DONE VUID-vkGetDeviceQueue-flags-01841
+                let queue_info = ash::vk::DeviceQueueInfo2::builder()
+                    .flags(flags)
+                    .queue_family_index(queue_family.index)
+                    .queue_index(index)
+                    .build();
+                // Host Synchronization: none
+                let queue = self.ash_device.get_device_queue2(&queue_info);
+                Queue {
+                    device: self.clone(),
+                    vk_queue: queue,
+                    queue_family: queue_family.clone(),
+                    protected: flags.contains(ash::vk::DeviceQueueCreateFlags::PROTECTED),
+                }
+            })
+            .collect()
+    }
     pub fn get_extension<const EXT: PhysicalDeviceExtensionType>(
         self: &Arc<Self>,
     ) -> Option<DeviceExtension<EXT>> {
@@ -235,8 +655,32 @@ impl Device {
             let (mut write_set_cache, mut copy_set_cache, mut unique_descriptor_sets, mut locks) =
                 local.take();
             // Host Synchronization: pDescriptorWrites[].dstSet, DescriptorCopies[].dstSet
+            // reserved up front so the chained structures never move
+            let mut acceleration_structure_writes = Vec::with_capacity(descriptor_writes.len());
+            let mut inline_uniform_block_writes = Vec::with_capacity(descriptor_writes.len());
             for descriptor_set in descriptor_writes {
-                write_set_cache.push(descriptor_set.ash_builder().build());
+                let mut write = descriptor_set.ash_builder().build();
+                if let Some(acceleration_structure_write) =
+                    descriptor_set.ash_acceleration_structure_write()
+                {
+                    write.descriptor_count =
+                        acceleration_structure_write.acceleration_structure_count;
+                    acceleration_structure_writes.push(acceleration_structure_write);
+                    write.p_next = acceleration_structure_writes.last().unwrap()
+                        as *const ash::vk::WriteDescriptorSetAccelerationStructureKHR
+                        as *const std::ffi::c_void;
+                }
+                // DONE VUID-VkWriteDescriptorSet-descriptorType-02221
+                if let Some(inline_uniform_block_write) =
+                    descriptor_set.ash_inline_uniform_block_write()
+                {
+                    write.descriptor_count = inline_uniform_block_write.data_size;
+                    inline_uniform_block_writes.push(inline_uniform_block_write);
+                    write.p_next = inline_uniform_block_writes.last().unwrap()
+                        as *const ash::vk::WriteDescriptorSetInlineUniformBlock
+                        as *const std::ffi::c_void;
+                }
+                write_set_cache.push(write);
                 let lock = descriptor_set.dst_set.ash_vk_descriptor_set.read();
                 unique_descriptor_sets.insert(lock.as_raw(), descriptor_set.dst_set.clone());
             }
@@ -264,10 +708,13 @@ impl Device {
             ));
         })
     }
-    pub fn wait_idle(&self) {
-        panic!("due to performance reason, device_wait_idle is not implemented. \
-        vkDeviceWaitIdle is equivalent to calling vkQueueWaitIdle for all queues owned by device. \
-        Manage queues and wait on them manually for best performance");
+    pub fn wait_idle(&self) -> Result<(), ash::vk::Result> {
+        // Host Synchronization: all VkQueue objects created from device
+        let _queues = self.queues_lock.write();
+        unsafe {
+            self.ash_device.device_wait_idle()?;
+        }
+        Ok(())
     }
 }
 
@@ -277,6 +724,12 @@ impl Drop for Device {
     // TODO VUID-vkDestroyDevice-device-00380
     // Host Synchronization: device all VkQueue objects
     fn drop(&mut self) {
+        if let Some(fence_reactor) = self.fence_reactor.get_mut().take() {
+            fence_reactor.shutdown();
+        }
+        if !self.destroy_on_drop {
+            return;
+        }
         unsafe {
             self.ash_device.destroy_device(None);
         }
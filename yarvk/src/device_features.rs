@@ -1234,6 +1234,78 @@ impl SubPhysicalFeature for PhysicalDeviceVulkan13Features {
     }
 }
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PhysicalDeviceFragmentShadingRateEnumsFeaturesNV {
+    FragmentShadingRateEnums,
+    SupersampleFragmentShadingRates,
+    NoInvocationFragmentShadingRates,
+}
+impl const From<PhysicalDeviceFragmentShadingRateEnumsFeaturesNV> for FeatureType {
+    fn from(feature: PhysicalDeviceFragmentShadingRateEnumsFeaturesNV) -> Self {
+        FeatureType::DeviceFragmentShadingRateEnumsFeaturesNV(feature)
+    }
+}
+#[derive(Clone, PartialEq, Eq)]
+pub enum DeviceFragmentShadingRateEnumsFeaturesNV {
+    FragmentShadingRateEnums,
+    SupersampleFragmentShadingRates,
+    NoInvocationFragmentShadingRates,
+}
+impl ToPhysicalFeature for DeviceFragmentShadingRateEnumsFeaturesNV {
+    type PhysicalDeviceFeatureTy = PhysicalDeviceFragmentShadingRateEnumsFeaturesNV;
+    fn to_physical(&self) -> Self::PhysicalDeviceFeatureTy {
+        match self {
+            DeviceFragmentShadingRateEnumsFeaturesNV::FragmentShadingRateEnums => {
+                PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::FragmentShadingRateEnums
+            }
+            DeviceFragmentShadingRateEnumsFeaturesNV::SupersampleFragmentShadingRates => {
+                PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::SupersampleFragmentShadingRates
+            }
+            DeviceFragmentShadingRateEnumsFeaturesNV::NoInvocationFragmentShadingRates => {
+                PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::NoInvocationFragmentShadingRates
+            }
+        }
+    }
+}
+impl From<DeviceFragmentShadingRateEnumsFeaturesNV> for DeviceFeature {
+    fn from(feature: DeviceFragmentShadingRateEnumsFeaturesNV) -> Self {
+        DeviceFeature::DeviceFragmentShadingRateEnumsFeaturesNV(feature)
+    }
+}
+impl VkDeviceFeature for ash::vk::PhysicalDeviceFragmentShadingRateEnumsFeaturesNV {
+    type SubFeatureEnumTy = PhysicalDeviceFragmentShadingRateEnumsFeaturesNV;
+    fn collect_feature(&self) -> rustc_hash::FxHashSet<Self::SubFeatureEnumTy> {
+        let mut set = rustc_hash::FxHashSet::<Self::SubFeatureEnumTy>::default();
+        if self.fragment_shading_rate_enums != 0 {
+            set.insert(PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::FragmentShadingRateEnums);
+        }
+        if self.supersample_fragment_shading_rates != 0 {
+            set.insert(
+                PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::SupersampleFragmentShadingRates,
+            );
+        }
+        if self.no_invocation_fragment_shading_rates != 0 {
+            set.insert(
+                PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::NoInvocationFragmentShadingRates,
+            );
+        }
+        set
+    }
+}
+impl SubPhysicalFeature for PhysicalDeviceFragmentShadingRateEnumsFeaturesNV {
+    type VkStruct = ash::vk::PhysicalDeviceFragmentShadingRateEnumsFeaturesNV;
+    fn register(&self, vk_struct: &mut Self::VkStruct) {
+        match self {
+            Self::FragmentShadingRateEnums => vk_struct.fragment_shading_rate_enums = 1,
+            Self::SupersampleFragmentShadingRates => {
+                vk_struct.supersample_fragment_shading_rates = 1
+            }
+            Self::NoInvocationFragmentShadingRates => {
+                vk_struct.no_invocation_fragment_shading_rates = 1
+            }
+        }
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum PhysicalDeviceExternalMemoryRDMAFeaturesNV {
     ExternalMemoryRdma,
 }
@@ -5955,6 +6027,298 @@ impl SubPhysicalFeature for PhysicalDeviceZeroInitializeWorkgroupMemoryFeatures
     }
 }
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PhysicalDeviceExtendedDynamicState3FeaturesEXT {
+    ExtendedDynamicState3TessellationDomainOrigin,
+    ExtendedDynamicState3DepthClampEnable,
+    ExtendedDynamicState3PolygonMode,
+    ExtendedDynamicState3RasterizationSamples,
+    ExtendedDynamicState3SampleMask,
+    ExtendedDynamicState3AlphaToCoverageEnable,
+    ExtendedDynamicState3AlphaToOneEnable,
+    ExtendedDynamicState3LogicOpEnable,
+    ExtendedDynamicState3ColorBlendEnable,
+    ExtendedDynamicState3ColorBlendEquation,
+    ExtendedDynamicState3ColorWriteMask,
+    ExtendedDynamicState3RasterizationStream,
+    ExtendedDynamicState3ConservativeRasterizationMode,
+    ExtendedDynamicState3ExtraPrimitiveOverestimationSize,
+    ExtendedDynamicState3DepthClipEnable,
+    ExtendedDynamicState3SampleLocationsEnable,
+    ExtendedDynamicState3ColorBlendAdvanced,
+    ExtendedDynamicState3ProvokingVertexMode,
+    ExtendedDynamicState3LineRasterizationMode,
+    ExtendedDynamicState3LineStippleEnable,
+    ExtendedDynamicState3DepthClipNegativeOneToOne,
+    ExtendedDynamicState3ViewportWScalingEnable,
+    ExtendedDynamicState3ViewportSwizzle,
+    ExtendedDynamicState3CoverageToColorEnable,
+    ExtendedDynamicState3CoverageToColorLocation,
+    ExtendedDynamicState3CoverageModulationMode,
+    ExtendedDynamicState3CoverageModulationTableEnable,
+    ExtendedDynamicState3CoverageModulationTable,
+    ExtendedDynamicState3CoverageReductionMode,
+    ExtendedDynamicState3RepresentativeFragmentTestEnable,
+    ExtendedDynamicState3ShadingRateImageEnable,
+}
+impl const From<PhysicalDeviceExtendedDynamicState3FeaturesEXT> for FeatureType {
+    fn from(feature: PhysicalDeviceExtendedDynamicState3FeaturesEXT) -> Self {
+        FeatureType::DeviceExtendedDynamicState3FeaturesEXT(feature)
+    }
+}
+#[derive(Clone, PartialEq, Eq)]
+pub enum DeviceExtendedDynamicState3FeaturesEXT {
+    ExtendedDynamicState3TessellationDomainOrigin,
+    ExtendedDynamicState3DepthClampEnable,
+    ExtendedDynamicState3PolygonMode,
+    ExtendedDynamicState3RasterizationSamples,
+    ExtendedDynamicState3SampleMask,
+    ExtendedDynamicState3AlphaToCoverageEnable,
+    ExtendedDynamicState3AlphaToOneEnable,
+    ExtendedDynamicState3LogicOpEnable,
+    ExtendedDynamicState3ColorBlendEnable,
+    ExtendedDynamicState3ColorBlendEquation,
+    ExtendedDynamicState3ColorWriteMask,
+    ExtendedDynamicState3RasterizationStream,
+    ExtendedDynamicState3ConservativeRasterizationMode,
+    ExtendedDynamicState3ExtraPrimitiveOverestimationSize,
+    ExtendedDynamicState3DepthClipEnable,
+    ExtendedDynamicState3SampleLocationsEnable,
+    ExtendedDynamicState3ColorBlendAdvanced,
+    ExtendedDynamicState3ProvokingVertexMode,
+    ExtendedDynamicState3LineRasterizationMode,
+    ExtendedDynamicState3LineStippleEnable,
+    ExtendedDynamicState3DepthClipNegativeOneToOne,
+    ExtendedDynamicState3ViewportWScalingEnable,
+    ExtendedDynamicState3ViewportSwizzle,
+    ExtendedDynamicState3CoverageToColorEnable,
+    ExtendedDynamicState3CoverageToColorLocation,
+    ExtendedDynamicState3CoverageModulationMode,
+    ExtendedDynamicState3CoverageModulationTableEnable,
+    ExtendedDynamicState3CoverageModulationTable,
+    ExtendedDynamicState3CoverageReductionMode,
+    ExtendedDynamicState3RepresentativeFragmentTestEnable,
+    ExtendedDynamicState3ShadingRateImageEnable,
+}
+impl ToPhysicalFeature for DeviceExtendedDynamicState3FeaturesEXT {
+    type PhysicalDeviceFeatureTy = PhysicalDeviceExtendedDynamicState3FeaturesEXT;
+    fn to_physical(&self) -> Self::PhysicalDeviceFeatureTy {
+        match self { DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3TessellationDomainOrigin => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3TessellationDomainOrigin , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3DepthClampEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3DepthClampEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3PolygonMode => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3PolygonMode , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3RasterizationSamples => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3RasterizationSamples , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3SampleMask => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3SampleMask , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3AlphaToCoverageEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3AlphaToCoverageEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3AlphaToOneEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3AlphaToOneEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3LogicOpEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3LogicOpEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorBlendEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorBlendEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorBlendEquation => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorBlendEquation , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorWriteMask => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorWriteMask , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3RasterizationStream => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3RasterizationStream , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ConservativeRasterizationMode => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ConservativeRasterizationMode , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ExtraPrimitiveOverestimationSize => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ExtraPrimitiveOverestimationSize , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3DepthClipEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3DepthClipEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3SampleLocationsEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3SampleLocationsEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorBlendAdvanced => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorBlendAdvanced , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ProvokingVertexMode => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ProvokingVertexMode , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3LineRasterizationMode => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3LineRasterizationMode , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3LineStippleEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3LineStippleEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3DepthClipNegativeOneToOne => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3DepthClipNegativeOneToOne , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ViewportWScalingEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ViewportWScalingEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ViewportSwizzle => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ViewportSwizzle , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageToColorEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageToColorEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageToColorLocation => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageToColorLocation , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageModulationMode => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageModulationMode , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageModulationTableEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageModulationTableEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageModulationTable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageModulationTable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageReductionMode => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageReductionMode , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3RepresentativeFragmentTestEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3RepresentativeFragmentTestEnable , DeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ShadingRateImageEnable => PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ShadingRateImageEnable , }
+    }
+}
+impl From<DeviceExtendedDynamicState3FeaturesEXT> for DeviceFeature {
+    fn from(feature: DeviceExtendedDynamicState3FeaturesEXT) -> Self {
+        DeviceFeature::DeviceExtendedDynamicState3FeaturesEXT(feature)
+    }
+}
+impl VkDeviceFeature for ash::vk::PhysicalDeviceExtendedDynamicState3FeaturesEXT {
+    type SubFeatureEnumTy = PhysicalDeviceExtendedDynamicState3FeaturesEXT;
+    fn collect_feature(&self) -> rustc_hash::FxHashSet<Self::SubFeatureEnumTy> {
+        let mut set = rustc_hash::FxHashSet::<Self::SubFeatureEnumTy>::default();
+        if self.extended_dynamic_state3_tessellation_domain_origin != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3TessellationDomainOrigin) ;
+        }
+        if self.extended_dynamic_state3_depth_clamp_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3DepthClampEnable) ;
+        }
+        if self.extended_dynamic_state3_polygon_mode != 0 {
+            set.insert(
+                PhysicalDeviceExtendedDynamicState3FeaturesEXT::ExtendedDynamicState3PolygonMode,
+            );
+        }
+        if self.extended_dynamic_state3_rasterization_samples != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3RasterizationSamples) ;
+        }
+        if self.extended_dynamic_state3_sample_mask != 0 {
+            set.insert(
+                PhysicalDeviceExtendedDynamicState3FeaturesEXT::ExtendedDynamicState3SampleMask,
+            );
+        }
+        if self.extended_dynamic_state3_alpha_to_coverage_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3AlphaToCoverageEnable) ;
+        }
+        if self.extended_dynamic_state3_alpha_to_one_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3AlphaToOneEnable) ;
+        }
+        if self.extended_dynamic_state3_logic_op_enable != 0 {
+            set.insert(
+                PhysicalDeviceExtendedDynamicState3FeaturesEXT::ExtendedDynamicState3LogicOpEnable,
+            );
+        }
+        if self.extended_dynamic_state3_color_blend_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorBlendEnable) ;
+        }
+        if self.extended_dynamic_state3_color_blend_equation != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorBlendEquation) ;
+        }
+        if self.extended_dynamic_state3_color_write_mask != 0 {
+            set.insert(
+                PhysicalDeviceExtendedDynamicState3FeaturesEXT::ExtendedDynamicState3ColorWriteMask,
+            );
+        }
+        if self.extended_dynamic_state3_rasterization_stream != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3RasterizationStream) ;
+        }
+        if self.extended_dynamic_state3_conservative_rasterization_mode != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ConservativeRasterizationMode) ;
+        }
+        if self.extended_dynamic_state3_extra_primitive_overestimation_size != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ExtraPrimitiveOverestimationSize) ;
+        }
+        if self.extended_dynamic_state3_depth_clip_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3DepthClipEnable) ;
+        }
+        if self.extended_dynamic_state3_sample_locations_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3SampleLocationsEnable) ;
+        }
+        if self.extended_dynamic_state3_color_blend_advanced != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ColorBlendAdvanced) ;
+        }
+        if self.extended_dynamic_state3_provoking_vertex_mode != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ProvokingVertexMode) ;
+        }
+        if self.extended_dynamic_state3_line_rasterization_mode != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3LineRasterizationMode) ;
+        }
+        if self.extended_dynamic_state3_line_stipple_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3LineStippleEnable) ;
+        }
+        if self.extended_dynamic_state3_depth_clip_negative_one_to_one != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3DepthClipNegativeOneToOne) ;
+        }
+        if self.extended_dynamic_state3_viewport_w_scaling_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ViewportWScalingEnable) ;
+        }
+        if self.extended_dynamic_state3_viewport_swizzle != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ViewportSwizzle) ;
+        }
+        if self.extended_dynamic_state3_coverage_to_color_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageToColorEnable) ;
+        }
+        if self.extended_dynamic_state3_coverage_to_color_location != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageToColorLocation) ;
+        }
+        if self.extended_dynamic_state3_coverage_modulation_mode != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageModulationMode) ;
+        }
+        if self.extended_dynamic_state3_coverage_modulation_table_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageModulationTableEnable) ;
+        }
+        if self.extended_dynamic_state3_coverage_modulation_table != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageModulationTable) ;
+        }
+        if self.extended_dynamic_state3_coverage_reduction_mode != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3CoverageReductionMode) ;
+        }
+        if self.extended_dynamic_state3_representative_fragment_test_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3RepresentativeFragmentTestEnable) ;
+        }
+        if self.extended_dynamic_state3_shading_rate_image_enable != 0 {
+            set . insert (PhysicalDeviceExtendedDynamicState3FeaturesEXT :: ExtendedDynamicState3ShadingRateImageEnable) ;
+        }
+        set
+    }
+}
+impl SubPhysicalFeature for PhysicalDeviceExtendedDynamicState3FeaturesEXT {
+    type VkStruct = ash::vk::PhysicalDeviceExtendedDynamicState3FeaturesEXT;
+    fn register(&self, vk_struct: &mut Self::VkStruct) {
+        match self {
+            Self::ExtendedDynamicState3TessellationDomainOrigin => {
+                vk_struct.extended_dynamic_state3_tessellation_domain_origin = 1
+            }
+            Self::ExtendedDynamicState3DepthClampEnable => {
+                vk_struct.extended_dynamic_state3_depth_clamp_enable = 1
+            }
+            Self::ExtendedDynamicState3PolygonMode => {
+                vk_struct.extended_dynamic_state3_polygon_mode = 1
+            }
+            Self::ExtendedDynamicState3RasterizationSamples => {
+                vk_struct.extended_dynamic_state3_rasterization_samples = 1
+            }
+            Self::ExtendedDynamicState3SampleMask => {
+                vk_struct.extended_dynamic_state3_sample_mask = 1
+            }
+            Self::ExtendedDynamicState3AlphaToCoverageEnable => {
+                vk_struct.extended_dynamic_state3_alpha_to_coverage_enable = 1
+            }
+            Self::ExtendedDynamicState3AlphaToOneEnable => {
+                vk_struct.extended_dynamic_state3_alpha_to_one_enable = 1
+            }
+            Self::ExtendedDynamicState3LogicOpEnable => {
+                vk_struct.extended_dynamic_state3_logic_op_enable = 1
+            }
+            Self::ExtendedDynamicState3ColorBlendEnable => {
+                vk_struct.extended_dynamic_state3_color_blend_enable = 1
+            }
+            Self::ExtendedDynamicState3ColorBlendEquation => {
+                vk_struct.extended_dynamic_state3_color_blend_equation = 1
+            }
+            Self::ExtendedDynamicState3ColorWriteMask => {
+                vk_struct.extended_dynamic_state3_color_write_mask = 1
+            }
+            Self::ExtendedDynamicState3RasterizationStream => {
+                vk_struct.extended_dynamic_state3_rasterization_stream = 1
+            }
+            Self::ExtendedDynamicState3ConservativeRasterizationMode => {
+                vk_struct.extended_dynamic_state3_conservative_rasterization_mode = 1
+            }
+            Self::ExtendedDynamicState3ExtraPrimitiveOverestimationSize => {
+                vk_struct.extended_dynamic_state3_extra_primitive_overestimation_size = 1
+            }
+            Self::ExtendedDynamicState3DepthClipEnable => {
+                vk_struct.extended_dynamic_state3_depth_clip_enable = 1
+            }
+            Self::ExtendedDynamicState3SampleLocationsEnable => {
+                vk_struct.extended_dynamic_state3_sample_locations_enable = 1
+            }
+            Self::ExtendedDynamicState3ColorBlendAdvanced => {
+                vk_struct.extended_dynamic_state3_color_blend_advanced = 1
+            }
+            Self::ExtendedDynamicState3ProvokingVertexMode => {
+                vk_struct.extended_dynamic_state3_provoking_vertex_mode = 1
+            }
+            Self::ExtendedDynamicState3LineRasterizationMode => {
+                vk_struct.extended_dynamic_state3_line_rasterization_mode = 1
+            }
+            Self::ExtendedDynamicState3LineStippleEnable => {
+                vk_struct.extended_dynamic_state3_line_stipple_enable = 1
+            }
+            Self::ExtendedDynamicState3DepthClipNegativeOneToOne => {
+                vk_struct.extended_dynamic_state3_depth_clip_negative_one_to_one = 1
+            }
+            Self::ExtendedDynamicState3ViewportWScalingEnable => {
+                vk_struct.extended_dynamic_state3_viewport_w_scaling_enable = 1
+            }
+            Self::ExtendedDynamicState3ViewportSwizzle => {
+                vk_struct.extended_dynamic_state3_viewport_swizzle = 1
+            }
+            Self::ExtendedDynamicState3CoverageToColorEnable => {
+                vk_struct.extended_dynamic_state3_coverage_to_color_enable = 1
+            }
+            Self::ExtendedDynamicState3CoverageToColorLocation => {
+                vk_struct.extended_dynamic_state3_coverage_to_color_location = 1
+            }
+            Self::ExtendedDynamicState3CoverageModulationMode => {
+                vk_struct.extended_dynamic_state3_coverage_modulation_mode = 1
+            }
+            Self::ExtendedDynamicState3CoverageModulationTableEnable => {
+                vk_struct.extended_dynamic_state3_coverage_modulation_table_enable = 1
+            }
+            Self::ExtendedDynamicState3CoverageModulationTable => {
+                vk_struct.extended_dynamic_state3_coverage_modulation_table = 1
+            }
+            Self::ExtendedDynamicState3CoverageReductionMode => {
+                vk_struct.extended_dynamic_state3_coverage_reduction_mode = 1
+            }
+            Self::ExtendedDynamicState3RepresentativeFragmentTestEnable => {
+                vk_struct.extended_dynamic_state3_representative_fragment_test_enable = 1
+            }
+            Self::ExtendedDynamicState3ShadingRateImageEnable => {
+                vk_struct.extended_dynamic_state3_shading_rate_image_enable = 1
+            }
+        }
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum PhysicalDeviceRobustness2FeaturesEXT {
     RobustBufferAccess2,
     RobustImageAccess2,
@@ -6019,52 +6383,6 @@ impl SubPhysicalFeature for PhysicalDeviceRobustness2FeaturesEXT {
     }
 }
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-pub enum PhysicalDeviceShaderTerminateInvocationFeatures {
-    ShaderTerminateInvocation,
-}
-impl const From<PhysicalDeviceShaderTerminateInvocationFeatures> for FeatureType {
-    fn from(feature: PhysicalDeviceShaderTerminateInvocationFeatures) -> Self {
-        FeatureType::DeviceShaderTerminateInvocationFeatures(feature)
-    }
-}
-#[derive(Clone, PartialEq, Eq)]
-pub enum DeviceShaderTerminateInvocationFeatures {
-    ShaderTerminateInvocation,
-}
-impl ToPhysicalFeature for DeviceShaderTerminateInvocationFeatures {
-    type PhysicalDeviceFeatureTy = PhysicalDeviceShaderTerminateInvocationFeatures;
-    fn to_physical(&self) -> Self::PhysicalDeviceFeatureTy {
-        match self {
-            DeviceShaderTerminateInvocationFeatures::ShaderTerminateInvocation => {
-                PhysicalDeviceShaderTerminateInvocationFeatures::ShaderTerminateInvocation
-            }
-        }
-    }
-}
-impl From<DeviceShaderTerminateInvocationFeatures> for DeviceFeature {
-    fn from(feature: DeviceShaderTerminateInvocationFeatures) -> Self {
-        DeviceFeature::DeviceShaderTerminateInvocationFeatures(feature)
-    }
-}
-impl VkDeviceFeature for ash::vk::PhysicalDeviceShaderTerminateInvocationFeatures {
-    type SubFeatureEnumTy = PhysicalDeviceShaderTerminateInvocationFeatures;
-    fn collect_feature(&self) -> rustc_hash::FxHashSet<Self::SubFeatureEnumTy> {
-        let mut set = rustc_hash::FxHashSet::<Self::SubFeatureEnumTy>::default();
-        if self.shader_terminate_invocation != 0 {
-            set.insert(PhysicalDeviceShaderTerminateInvocationFeatures::ShaderTerminateInvocation);
-        }
-        set
-    }
-}
-impl SubPhysicalFeature for PhysicalDeviceShaderTerminateInvocationFeatures {
-    type VkStruct = ash::vk::PhysicalDeviceShaderTerminateInvocationFeatures;
-    fn register(&self, vk_struct: &mut Self::VkStruct) {
-        match self {
-            Self::ShaderTerminateInvocation => vk_struct.shader_terminate_invocation = 1,
-        }
-    }
-}
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum PhysicalDeviceGlobalPriorityQueryFeaturesKHR {
     GlobalPriorityQuery,
 }
@@ -6913,6 +7231,90 @@ impl SubPhysicalFeature for PhysicalDeviceMeshShaderFeaturesNV {
     }
 }
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PhysicalDeviceMeshShaderFeaturesEXT {
+    TaskShader,
+    MeshShader,
+    MultiviewMeshShader,
+    PrimitiveFragmentShadingRateMeshShader,
+    MeshShaderQueries,
+}
+impl const From<PhysicalDeviceMeshShaderFeaturesEXT> for FeatureType {
+    fn from(feature: PhysicalDeviceMeshShaderFeaturesEXT) -> Self {
+        FeatureType::DeviceMeshShaderFeaturesEXT(feature)
+    }
+}
+#[derive(Clone, PartialEq, Eq)]
+pub enum DeviceMeshShaderFeaturesEXT {
+    TaskShader,
+    MeshShader,
+    MultiviewMeshShader,
+    PrimitiveFragmentShadingRateMeshShader,
+    MeshShaderQueries,
+}
+impl ToPhysicalFeature for DeviceMeshShaderFeaturesEXT {
+    type PhysicalDeviceFeatureTy = PhysicalDeviceMeshShaderFeaturesEXT;
+    fn to_physical(&self) -> Self::PhysicalDeviceFeatureTy {
+        match self {
+            DeviceMeshShaderFeaturesEXT::TaskShader => {
+                PhysicalDeviceMeshShaderFeaturesEXT::TaskShader
+            }
+            DeviceMeshShaderFeaturesEXT::MeshShader => {
+                PhysicalDeviceMeshShaderFeaturesEXT::MeshShader
+            }
+            DeviceMeshShaderFeaturesEXT::MultiviewMeshShader => {
+                PhysicalDeviceMeshShaderFeaturesEXT::MultiviewMeshShader
+            }
+            DeviceMeshShaderFeaturesEXT::PrimitiveFragmentShadingRateMeshShader => {
+                PhysicalDeviceMeshShaderFeaturesEXT::PrimitiveFragmentShadingRateMeshShader
+            }
+            DeviceMeshShaderFeaturesEXT::MeshShaderQueries => {
+                PhysicalDeviceMeshShaderFeaturesEXT::MeshShaderQueries
+            }
+        }
+    }
+}
+impl From<DeviceMeshShaderFeaturesEXT> for DeviceFeature {
+    fn from(feature: DeviceMeshShaderFeaturesEXT) -> Self {
+        DeviceFeature::DeviceMeshShaderFeaturesEXT(feature)
+    }
+}
+impl VkDeviceFeature for ash::vk::PhysicalDeviceMeshShaderFeaturesEXT {
+    type SubFeatureEnumTy = PhysicalDeviceMeshShaderFeaturesEXT;
+    fn collect_feature(&self) -> rustc_hash::FxHashSet<Self::SubFeatureEnumTy> {
+        let mut set = rustc_hash::FxHashSet::<Self::SubFeatureEnumTy>::default();
+        if self.task_shader != 0 {
+            set.insert(PhysicalDeviceMeshShaderFeaturesEXT::TaskShader);
+        }
+        if self.mesh_shader != 0 {
+            set.insert(PhysicalDeviceMeshShaderFeaturesEXT::MeshShader);
+        }
+        if self.multiview_mesh_shader != 0 {
+            set.insert(PhysicalDeviceMeshShaderFeaturesEXT::MultiviewMeshShader);
+        }
+        if self.primitive_fragment_shading_rate_mesh_shader != 0 {
+            set.insert(PhysicalDeviceMeshShaderFeaturesEXT::PrimitiveFragmentShadingRateMeshShader);
+        }
+        if self.mesh_shader_queries != 0 {
+            set.insert(PhysicalDeviceMeshShaderFeaturesEXT::MeshShaderQueries);
+        }
+        set
+    }
+}
+impl SubPhysicalFeature for PhysicalDeviceMeshShaderFeaturesEXT {
+    type VkStruct = ash::vk::PhysicalDeviceMeshShaderFeaturesEXT;
+    fn register(&self, vk_struct: &mut Self::VkStruct) {
+        match self {
+            Self::TaskShader => vk_struct.task_shader = 1,
+            Self::MeshShader => vk_struct.mesh_shader = 1,
+            Self::MultiviewMeshShader => vk_struct.multiview_mesh_shader = 1,
+            Self::PrimitiveFragmentShadingRateMeshShader => {
+                vk_struct.primitive_fragment_shading_rate_mesh_shader = 1
+            }
+            Self::MeshShaderQueries => vk_struct.mesh_shader_queries = 1,
+        }
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum PhysicalDeviceShaderClockFeaturesKHR {
     ShaderSubgroupClock,
     ShaderDeviceClock,
@@ -7086,74 +7488,48 @@ impl SubPhysicalFeature for PhysicalDeviceRepresentativeFragmentTestFeaturesNV {
     }
 }
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-pub enum PhysicalDeviceFragmentShadingRateEnumsFeaturesNV {
-    FragmentShadingRateEnums,
-    SupersampleFragmentShadingRates,
-    NoInvocationFragmentShadingRates,
+pub enum PhysicalDeviceShaderTerminateInvocationFeatures {
+    ShaderTerminateInvocation,
 }
-impl const From<PhysicalDeviceFragmentShadingRateEnumsFeaturesNV> for FeatureType {
-    fn from(feature: PhysicalDeviceFragmentShadingRateEnumsFeaturesNV) -> Self {
-        FeatureType::DeviceFragmentShadingRateEnumsFeaturesNV(feature)
+impl const From<PhysicalDeviceShaderTerminateInvocationFeatures> for FeatureType {
+    fn from(feature: PhysicalDeviceShaderTerminateInvocationFeatures) -> Self {
+        FeatureType::DeviceShaderTerminateInvocationFeatures(feature)
     }
 }
 #[derive(Clone, PartialEq, Eq)]
-pub enum DeviceFragmentShadingRateEnumsFeaturesNV {
-    FragmentShadingRateEnums,
-    SupersampleFragmentShadingRates,
-    NoInvocationFragmentShadingRates,
+pub enum DeviceShaderTerminateInvocationFeatures {
+    ShaderTerminateInvocation,
 }
-impl ToPhysicalFeature for DeviceFragmentShadingRateEnumsFeaturesNV {
-    type PhysicalDeviceFeatureTy = PhysicalDeviceFragmentShadingRateEnumsFeaturesNV;
+impl ToPhysicalFeature for DeviceShaderTerminateInvocationFeatures {
+    type PhysicalDeviceFeatureTy = PhysicalDeviceShaderTerminateInvocationFeatures;
     fn to_physical(&self) -> Self::PhysicalDeviceFeatureTy {
         match self {
-            DeviceFragmentShadingRateEnumsFeaturesNV::FragmentShadingRateEnums => {
-                PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::FragmentShadingRateEnums
-            }
-            DeviceFragmentShadingRateEnumsFeaturesNV::SupersampleFragmentShadingRates => {
-                PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::SupersampleFragmentShadingRates
-            }
-            DeviceFragmentShadingRateEnumsFeaturesNV::NoInvocationFragmentShadingRates => {
-                PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::NoInvocationFragmentShadingRates
+            DeviceShaderTerminateInvocationFeatures::ShaderTerminateInvocation => {
+                PhysicalDeviceShaderTerminateInvocationFeatures::ShaderTerminateInvocation
             }
         }
     }
 }
-impl From<DeviceFragmentShadingRateEnumsFeaturesNV> for DeviceFeature {
-    fn from(feature: DeviceFragmentShadingRateEnumsFeaturesNV) -> Self {
-        DeviceFeature::DeviceFragmentShadingRateEnumsFeaturesNV(feature)
+impl From<DeviceShaderTerminateInvocationFeatures> for DeviceFeature {
+    fn from(feature: DeviceShaderTerminateInvocationFeatures) -> Self {
+        DeviceFeature::DeviceShaderTerminateInvocationFeatures(feature)
     }
 }
-impl VkDeviceFeature for ash::vk::PhysicalDeviceFragmentShadingRateEnumsFeaturesNV {
-    type SubFeatureEnumTy = PhysicalDeviceFragmentShadingRateEnumsFeaturesNV;
+impl VkDeviceFeature for ash::vk::PhysicalDeviceShaderTerminateInvocationFeatures {
+    type SubFeatureEnumTy = PhysicalDeviceShaderTerminateInvocationFeatures;
     fn collect_feature(&self) -> rustc_hash::FxHashSet<Self::SubFeatureEnumTy> {
         let mut set = rustc_hash::FxHashSet::<Self::SubFeatureEnumTy>::default();
-        if self.fragment_shading_rate_enums != 0 {
-            set.insert(PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::FragmentShadingRateEnums);
-        }
-        if self.supersample_fragment_shading_rates != 0 {
-            set.insert(
-                PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::SupersampleFragmentShadingRates,
-            );
-        }
-        if self.no_invocation_fragment_shading_rates != 0 {
-            set.insert(
-                PhysicalDeviceFragmentShadingRateEnumsFeaturesNV::NoInvocationFragmentShadingRates,
-            );
+        if self.shader_terminate_invocation != 0 {
+            set.insert(PhysicalDeviceShaderTerminateInvocationFeatures::ShaderTerminateInvocation);
         }
         set
     }
 }
-impl SubPhysicalFeature for PhysicalDeviceFragmentShadingRateEnumsFeaturesNV {
-    type VkStruct = ash::vk::PhysicalDeviceFragmentShadingRateEnumsFeaturesNV;
+impl SubPhysicalFeature for PhysicalDeviceShaderTerminateInvocationFeatures {
+    type VkStruct = ash::vk::PhysicalDeviceShaderTerminateInvocationFeatures;
     fn register(&self, vk_struct: &mut Self::VkStruct) {
         match self {
-            Self::FragmentShadingRateEnums => vk_struct.fragment_shading_rate_enums = 1,
-            Self::SupersampleFragmentShadingRates => {
-                vk_struct.supersample_fragment_shading_rates = 1
-            }
-            Self::NoInvocationFragmentShadingRates => {
-                vk_struct.no_invocation_fragment_shading_rates = 1
-            }
+            Self::ShaderTerminateInvocation => vk_struct.shader_terminate_invocation = 1,
         }
     }
 }
@@ -7408,6 +7784,7 @@ pub enum FeatureType {
     DevicePresentWaitFeaturesKHR(PhysicalDevicePresentWaitFeaturesKHR),
     DeviceComputeShaderDerivativesFeaturesNV(PhysicalDeviceComputeShaderDerivativesFeaturesNV),
     DeviceVulkan13Features(PhysicalDeviceVulkan13Features),
+    DeviceFragmentShadingRateEnumsFeaturesNV(PhysicalDeviceFragmentShadingRateEnumsFeaturesNV),
     DeviceExternalMemoryRDMAFeaturesNV(PhysicalDeviceExternalMemoryRDMAFeaturesNV),
     DeviceProtectedMemoryFeatures(PhysicalDeviceProtectedMemoryFeatures),
     DeviceDynamicRenderingFeatures(PhysicalDeviceDynamicRenderingFeatures),
@@ -7493,8 +7870,8 @@ pub enum FeatureType {
     DeviceZeroInitializeWorkgroupMemoryFeatures(
         PhysicalDeviceZeroInitializeWorkgroupMemoryFeatures,
     ),
+    DeviceExtendedDynamicState3FeaturesEXT(PhysicalDeviceExtendedDynamicState3FeaturesEXT),
     DeviceRobustness2FeaturesEXT(PhysicalDeviceRobustness2FeaturesEXT),
-    DeviceShaderTerminateInvocationFeatures(PhysicalDeviceShaderTerminateInvocationFeatures),
     DeviceGlobalPriorityQueryFeaturesKHR(PhysicalDeviceGlobalPriorityQueryFeaturesKHR),
     DeviceShaderAtomicFloatFeaturesEXT(PhysicalDeviceShaderAtomicFloatFeaturesEXT),
     DeviceVertexAttributeDivisorFeaturesEXT(PhysicalDeviceVertexAttributeDivisorFeaturesEXT),
@@ -7509,10 +7886,11 @@ pub enum FeatureType {
     DeviceSubgroupSizeControlFeatures(PhysicalDeviceSubgroupSizeControlFeatures),
     DeviceShaderAtomicInt64Features(PhysicalDeviceShaderAtomicInt64Features),
     DeviceMeshShaderFeaturesNV(PhysicalDeviceMeshShaderFeaturesNV),
+    DeviceMeshShaderFeaturesEXT(PhysicalDeviceMeshShaderFeaturesEXT),
     DeviceShaderClockFeaturesKHR(PhysicalDeviceShaderClockFeaturesKHR),
     DeviceFragmentShaderInterlockFeaturesEXT(PhysicalDeviceFragmentShaderInterlockFeaturesEXT),
     DeviceRepresentativeFragmentTestFeaturesNV(PhysicalDeviceRepresentativeFragmentTestFeaturesNV),
-    DeviceFragmentShadingRateEnumsFeaturesNV(PhysicalDeviceFragmentShadingRateEnumsFeaturesNV),
+    DeviceShaderTerminateInvocationFeatures(PhysicalDeviceShaderTerminateInvocationFeatures),
     DeviceDeviceGeneratedCommandsFeaturesNV(PhysicalDeviceDeviceGeneratedCommandsFeaturesNV),
     DeviceExclusiveScissorFeaturesNV(PhysicalDeviceExclusiveScissorFeaturesNV),
     DeviceCornerSampledImageFeaturesNV(PhysicalDeviceCornerSampledImageFeaturesNV),
@@ -7540,6 +7918,7 @@ pub enum DeviceFeature {
     DevicePresentWaitFeaturesKHR(DevicePresentWaitFeaturesKHR),
     DeviceComputeShaderDerivativesFeaturesNV(DeviceComputeShaderDerivativesFeaturesNV),
     DeviceVulkan13Features(DeviceVulkan13Features),
+    DeviceFragmentShadingRateEnumsFeaturesNV(DeviceFragmentShadingRateEnumsFeaturesNV),
     DeviceExternalMemoryRDMAFeaturesNV(DeviceExternalMemoryRDMAFeaturesNV),
     DeviceProtectedMemoryFeatures(DeviceProtectedMemoryFeatures),
     DeviceDynamicRenderingFeatures(DeviceDynamicRenderingFeatures),
@@ -7615,8 +7994,8 @@ pub enum DeviceFeature {
     DeviceTextureCompressionASTCHDRFeatures(DeviceTextureCompressionASTCHDRFeatures),
     DevicePageableDeviceLocalMemoryFeaturesEXT(DevicePageableDeviceLocalMemoryFeaturesEXT),
     DeviceZeroInitializeWorkgroupMemoryFeatures(DeviceZeroInitializeWorkgroupMemoryFeatures),
+    DeviceExtendedDynamicState3FeaturesEXT(DeviceExtendedDynamicState3FeaturesEXT),
     DeviceRobustness2FeaturesEXT(DeviceRobustness2FeaturesEXT),
-    DeviceShaderTerminateInvocationFeatures(DeviceShaderTerminateInvocationFeatures),
     DeviceGlobalPriorityQueryFeaturesKHR(DeviceGlobalPriorityQueryFeaturesKHR),
     DeviceShaderAtomicFloatFeaturesEXT(DeviceShaderAtomicFloatFeaturesEXT),
     DeviceVertexAttributeDivisorFeaturesEXT(DeviceVertexAttributeDivisorFeaturesEXT),
@@ -7629,10 +8008,11 @@ pub enum DeviceFeature {
     DeviceSubgroupSizeControlFeatures(DeviceSubgroupSizeControlFeatures),
     DeviceShaderAtomicInt64Features(DeviceShaderAtomicInt64Features),
     DeviceMeshShaderFeaturesNV(DeviceMeshShaderFeaturesNV),
+    DeviceMeshShaderFeaturesEXT(DeviceMeshShaderFeaturesEXT),
     DeviceShaderClockFeaturesKHR(DeviceShaderClockFeaturesKHR),
     DeviceFragmentShaderInterlockFeaturesEXT(DeviceFragmentShaderInterlockFeaturesEXT),
     DeviceRepresentativeFragmentTestFeaturesNV(DeviceRepresentativeFragmentTestFeaturesNV),
-    DeviceFragmentShadingRateEnumsFeaturesNV(DeviceFragmentShadingRateEnumsFeaturesNV),
+    DeviceShaderTerminateInvocationFeatures(DeviceShaderTerminateInvocationFeatures),
     DeviceDeviceGeneratedCommandsFeaturesNV(DeviceDeviceGeneratedCommandsFeaturesNV),
     DeviceExclusiveScissorFeaturesNV(DeviceExclusiveScissorFeaturesNV),
     DeviceCornerSampledImageFeaturesNV(DeviceCornerSampledImageFeaturesNV),
@@ -7676,6 +8056,8 @@ pub(crate) union VkFeatureUnion {
     pub(crate) vk_physical_device_compute_shader_derivatives_features_nv:
         ash::vk::PhysicalDeviceComputeShaderDerivativesFeaturesNV,
     pub(crate) vk_physical_device_vulkan13_features: ash::vk::PhysicalDeviceVulkan13Features,
+    pub(crate) vk_physical_device_fragment_shading_rate_enums_features_nv:
+        ash::vk::PhysicalDeviceFragmentShadingRateEnumsFeaturesNV,
     pub(crate) vk_physical_device_external_memory_rdma_features_nv:
         ash::vk::PhysicalDeviceExternalMemoryRDMAFeaturesNV,
     pub(crate) vk_physical_device_protected_memory_features:
@@ -7810,10 +8192,10 @@ pub(crate) union VkFeatureUnion {
         ash::vk::PhysicalDevicePageableDeviceLocalMemoryFeaturesEXT,
     pub(crate) vk_physical_device_zero_initialize_workgroup_memory_features:
         ash::vk::PhysicalDeviceZeroInitializeWorkgroupMemoryFeatures,
+    pub(crate) vk_physical_device_extended_dynamic_state3_features_ext:
+        ash::vk::PhysicalDeviceExtendedDynamicState3FeaturesEXT,
     pub(crate) vk_physical_device_robustness2_features_ext:
         ash::vk::PhysicalDeviceRobustness2FeaturesEXT,
-    pub(crate) vk_physical_device_shader_terminate_invocation_features:
-        ash::vk::PhysicalDeviceShaderTerminateInvocationFeatures,
     pub(crate) vk_physical_device_global_priority_query_features_khr:
         ash::vk::PhysicalDeviceGlobalPriorityQueryFeaturesKHR,
     pub(crate) vk_physical_device_shader_atomic_float_features_ext:
@@ -7838,14 +8220,16 @@ pub(crate) union VkFeatureUnion {
         ash::vk::PhysicalDeviceShaderAtomicInt64Features,
     pub(crate) vk_physical_device_mesh_shader_features_nv:
         ash::vk::PhysicalDeviceMeshShaderFeaturesNV,
+    pub(crate) vk_physical_device_mesh_shader_features_ext:
+        ash::vk::PhysicalDeviceMeshShaderFeaturesEXT,
     pub(crate) vk_physical_device_shader_clock_features_khr:
         ash::vk::PhysicalDeviceShaderClockFeaturesKHR,
     pub(crate) vk_physical_device_fragment_shader_interlock_features_ext:
         ash::vk::PhysicalDeviceFragmentShaderInterlockFeaturesEXT,
     pub(crate) vk_physical_device_representative_fragment_test_features_nv:
         ash::vk::PhysicalDeviceRepresentativeFragmentTestFeaturesNV,
-    pub(crate) vk_physical_device_fragment_shading_rate_enums_features_nv:
-        ash::vk::PhysicalDeviceFragmentShadingRateEnumsFeaturesNV,
+    pub(crate) vk_physical_device_shader_terminate_invocation_features:
+        ash::vk::PhysicalDeviceShaderTerminateInvocationFeatures,
     pub(crate) vk_physical_device_device_generated_commands_features_nv:
         ash::vk::PhysicalDeviceDeviceGeneratedCommandsFeaturesNV,
     pub(crate) vk_physical_device_exclusive_scissor_features_nv:
@@ -7862,9 +8246,367 @@ pub struct Feature<const FEATURE: FeatureType> {
     pub device: std::sync::Arc<crate::device::Device>,
     pub(crate) _p: std::marker::PhantomData<usize>,
 }
+fn is_feature2_supported<T>(
+    physical_device: &crate::physical_device::PhysicalDevice,
+    feature: &T,
+) -> bool
+where
+    T: SubPhysicalFeature + Eq + std::hash::Hash,
+    T::VkStruct: VkDeviceFeature<SubFeatureEnumTy = T>,
+{
+    physical_device
+        .get_physical_device_features2::<T>()
+        .contains(feature)
+}
+pub(crate) fn is_feature_supported(
+    physical_device: &crate::physical_device::PhysicalDevice,
+    feature: &FeatureType,
+) -> bool {
+    match feature {
+        FeatureType::DeviceShaderAtomicFloat2FeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceCoverageReductionModeFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceSynchronization2Features(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceVertexInputDynamicStateFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceMultiviewFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderIntegerFunctions2FeaturesINTEL(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DevicePrivateDataFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DevicePipelineCreationCacheControlFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceMutableDescriptorTypeFeaturesVALVE(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceTimelineSemaphoreFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceRayTracingPipelineFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceVulkanMemoryModelFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceExtendedDynamicState2FeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderImageAtomicInt64FeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceInheritedViewportScissorFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceYcbcr2Plane444FormatsFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DevicePresentWaitFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceComputeShaderDerivativesFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceVulkan13Features(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceFragmentShadingRateEnumsFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceExternalMemoryRDMAFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceProtectedMemoryFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceDynamicRenderingFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceFragmentDensityMapFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderFloat16Int8Features(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceInvocationMaskFeaturesHUAWEI(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceRayQueryFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceSubpassShadingFeaturesHUAWEI(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderSubgroupExtendedTypesFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceWorkgroupMemoryExplicitLayoutFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceRayTracingMotionBlurFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceMaintenance4Features(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::Device4444FormatsFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DevicePrimitiveTopologyListRestartFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderIntegerDotProductFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceInlineUniformBlockFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceSeparateDepthStencilLayoutsFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceRGBA10X6FormatsFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShadingRateImageFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceDescriptorSetHostMappingFeaturesVALVE(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceBufferDeviceAddressFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DevicePipelineExecutablePropertiesFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceHostQueryResetFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceRasterizationOrderAttachmentAccessFeaturesARM(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceFragmentDensityMapOffsetFeaturesQCOM(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceTransformFeedbackFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DevicePerformanceQueryFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceDedicatedAllocationImageAliasingFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceImageViewMinLodFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderSubgroupUniformControlFlowFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceFeatures(feature) => physical_device
+            .get_physical_device_features()
+            .contains(feature),
+        FeatureType::DeviceIndexTypeUint8FeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceVulkan12Features(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceDiagnosticsConfigFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceFragmentShadingRateFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceLineRasterizationFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceProvokingVertexFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceExtendedDynamicStateFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DevicePresentIdFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceDeviceMemoryReportFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::Device16BitStorageFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceBufferDeviceAddressFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceCoherentMemoryFeaturesAMD(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceColorWriteEnableFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceMultiDrawFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceSamplerYcbcrConversionFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceCustomBorderColorFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceBorderColorSwizzleFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceAccelerationStructureFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceBlendOperationAdvancedFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceLinearColorAttachmentFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceConditionalRenderingFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceYcbcrImageArraysFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceImageRobustnessFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::Device8BitStorageFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceVulkan11Features(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceDepthClipControlFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceASTCDecodeFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceCooperativeMatrixFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceVariablePointersFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DevicePortabilitySubsetFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderImageFootprintFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceTexelBufferAlignmentFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceImagelessFramebufferFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderSMBuiltinsFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceFragmentShaderBarycentricFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceTextureCompressionASTCHDRFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DevicePageableDeviceLocalMemoryFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceZeroInitializeWorkgroupMemoryFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceExtendedDynamicState3FeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceRobustness2FeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceGlobalPriorityQueryFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderAtomicFloatFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceVertexAttributeDivisorFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceDescriptorIndexingFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceFragmentDensityMap2FeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceUniformBufferStandardLayoutFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceMemoryPriorityFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceScalarBlockLayoutFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderDemoteToHelperInvocationFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceSubgroupSizeControlFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderAtomicInt64Features(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceMeshShaderFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceMeshShaderFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderClockFeaturesKHR(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceFragmentShaderInterlockFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceRepresentativeFragmentTestFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderTerminateInvocationFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceDeviceGeneratedCommandsFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceExclusiveScissorFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceCornerSampledImageFeaturesNV(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceDepthClipEnableFeaturesEXT(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+        FeatureType::DeviceShaderDrawParametersFeatures(feature) => {
+            is_feature2_supported(physical_device, feature)
+        }
+    }
+}
 pub(crate) fn register_features(
     features: &rustc_hash::FxHashSet<FeatureType>,
-) -> ash::vk::PhysicalDeviceFeatures2 {
+) -> (ash::vk::PhysicalDeviceFeatures2, Vec<VkFeatureUnion>) {
     struct VkStructHeader {
         pub _s_type: ash::vk::StructureType,
         pub p_next: *mut std::ffi::c_void,
@@ -8006,6 +8748,10 @@ pub(crate) fn register_features(
                     });
                 feature.register(unsafe { std::mem::transmute(u) });
             }
+            FeatureType::DeviceFragmentShadingRateEnumsFeaturesNV(feature) => {
+                let u = map . entry (ash :: vk :: StructureType :: PHYSICAL_DEVICE_FRAGMENT_SHADING_RATE_ENUMS_FEATURES_NV) . or_insert (VkFeatureUnion { vk_physical_device_fragment_shading_rate_enums_features_nv : ash :: vk :: PhysicalDeviceFragmentShadingRateEnumsFeaturesNV :: default () }) ;
+                feature.register(unsafe { std::mem::transmute(u) });
+            }
             FeatureType::DeviceExternalMemoryRDMAFeaturesNV(feature) => {
                 let u = map
                     .entry(ash::vk::StructureType::PHYSICAL_DEVICE_EXTERNAL_MEMORY_RDMA_FEATURES_NV)
@@ -8574,6 +9320,10 @@ pub(crate) fn register_features(
                 let u = map . entry (ash :: vk :: StructureType :: PHYSICAL_DEVICE_ZERO_INITIALIZE_WORKGROUP_MEMORY_FEATURES) . or_insert (VkFeatureUnion { vk_physical_device_zero_initialize_workgroup_memory_features : ash :: vk :: PhysicalDeviceZeroInitializeWorkgroupMemoryFeatures :: default () }) ;
                 feature.register(unsafe { std::mem::transmute(u) });
             }
+            FeatureType::DeviceExtendedDynamicState3FeaturesEXT(feature) => {
+                let u = map . entry (ash :: vk :: StructureType :: PHYSICAL_DEVICE_EXTENDED_DYNAMIC_STATE_3_FEATURES_EXT) . or_insert (VkFeatureUnion { vk_physical_device_extended_dynamic_state3_features_ext : ash :: vk :: PhysicalDeviceExtendedDynamicState3FeaturesEXT :: default () }) ;
+                feature.register(unsafe { std::mem::transmute(u) });
+            }
             FeatureType::DeviceRobustness2FeaturesEXT(feature) => {
                 let u = map
                     .entry(ash::vk::StructureType::PHYSICAL_DEVICE_ROBUSTNESS_2_FEATURES_EXT)
@@ -8583,10 +9333,6 @@ pub(crate) fn register_features(
                     });
                 feature.register(unsafe { std::mem::transmute(u) });
             }
-            FeatureType::DeviceShaderTerminateInvocationFeatures(feature) => {
-                let u = map . entry (ash :: vk :: StructureType :: PHYSICAL_DEVICE_SHADER_TERMINATE_INVOCATION_FEATURES) . or_insert (VkFeatureUnion { vk_physical_device_shader_terminate_invocation_features : ash :: vk :: PhysicalDeviceShaderTerminateInvocationFeatures :: default () }) ;
-                feature.register(unsafe { std::mem::transmute(u) });
-            }
             FeatureType::DeviceGlobalPriorityQueryFeaturesKHR(feature) => {
                 let u = map
                     .entry(
@@ -8684,6 +9430,15 @@ pub(crate) fn register_features(
                     });
                 feature.register(unsafe { std::mem::transmute(u) });
             }
+            FeatureType::DeviceMeshShaderFeaturesEXT(feature) => {
+                let u = map
+                    .entry(ash::vk::StructureType::PHYSICAL_DEVICE_MESH_SHADER_FEATURES_EXT)
+                    .or_insert(VkFeatureUnion {
+                        vk_physical_device_mesh_shader_features_ext:
+                            ash::vk::PhysicalDeviceMeshShaderFeaturesEXT::default(),
+                    });
+                feature.register(unsafe { std::mem::transmute(u) });
+            }
             FeatureType::DeviceShaderClockFeaturesKHR(feature) => {
                 let u = map
                     .entry(ash::vk::StructureType::PHYSICAL_DEVICE_SHADER_CLOCK_FEATURES_KHR)
@@ -8701,8 +9456,8 @@ pub(crate) fn register_features(
                 let u = map . entry (ash :: vk :: StructureType :: PHYSICAL_DEVICE_REPRESENTATIVE_FRAGMENT_TEST_FEATURES_NV) . or_insert (VkFeatureUnion { vk_physical_device_representative_fragment_test_features_nv : ash :: vk :: PhysicalDeviceRepresentativeFragmentTestFeaturesNV :: default () }) ;
                 feature.register(unsafe { std::mem::transmute(u) });
             }
-            FeatureType::DeviceFragmentShadingRateEnumsFeaturesNV(feature) => {
-                let u = map . entry (ash :: vk :: StructureType :: PHYSICAL_DEVICE_FRAGMENT_SHADING_RATE_ENUMS_FEATURES_NV) . or_insert (VkFeatureUnion { vk_physical_device_fragment_shading_rate_enums_features_nv : ash :: vk :: PhysicalDeviceFragmentShadingRateEnumsFeaturesNV :: default () }) ;
+            FeatureType::DeviceShaderTerminateInvocationFeatures(feature) => {
+                let u = map . entry (ash :: vk :: StructureType :: PHYSICAL_DEVICE_SHADER_TERMINATE_INVOCATION_FEATURES) . or_insert (VkFeatureUnion { vk_physical_device_shader_terminate_invocation_features : ash :: vk :: PhysicalDeviceShaderTerminateInvocationFeatures :: default () }) ;
                 feature.register(unsafe { std::mem::transmute(u) });
             }
             FeatureType::DeviceDeviceGeneratedCommandsFeaturesNV(feature) => {
@@ -8747,10 +9502,11 @@ pub(crate) fn register_features(
             }
         }
     }
-    map.into_iter().for_each(|(_, mut feature_union)| unsafe {
-        let header = &mut feature_union as *mut _ as *mut VkStructHeader;
+    let mut feature_unions: Vec<VkFeatureUnion> = map.into_values().collect();
+    feature_unions.iter_mut().for_each(|feature_union| unsafe {
+        let header = feature_union as *mut _ as *mut VkStructHeader;
         (*header).p_next = feature2.p_next;
         feature2.p_next = header as *mut _;
     });
-    feature2
+    (feature2, feature_unions)
 }
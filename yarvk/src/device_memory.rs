@@ -1,5 +1,9 @@
+use crate::buffer::Buffer;
 use crate::device::Device;
+use crate::error::{Result, ResultContext};
+use crate::image::Image;
 use crate::physical_device::memory_properties::MemoryType;
+use ash::vk::Handle;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
@@ -7,6 +11,33 @@ pub struct DeviceMemory {
     pub device: Arc<Device>,
     pub(crate) vk_device_memory: ash::vk::DeviceMemory,
     pub size: ash::vk::DeviceSize,
+    pub memory_type: MemoryType,
+    // the raw handle of the image or buffer of a dedicated allocation
+    pub(crate) dedicated_to: Option<u64>,
+}
+
+// whether the implementation prefers or requires a resource to have its own allocation, see
+// `DeviceMemoryBuilder::dedicated_image` and `DeviceMemoryBuilder::dedicated_buffer`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DedicatedRequirements {
+    pub prefers_dedicated_allocation: bool,
+    pub requires_dedicated_allocation: bool,
+}
+
+impl DedicatedRequirements {
+    pub(crate) fn from_ash(requirements: &ash::vk::MemoryDedicatedRequirements) -> Self {
+        DedicatedRequirements {
+            prefers_dedicated_allocation: requirements.prefers_dedicated_allocation
+                == ash::vk::TRUE,
+            requires_dedicated_allocation: requirements.requires_dedicated_allocation
+                == ash::vk::TRUE,
+        }
+    }
+}
+
+enum Dedicated {
+    Image(ash::vk::Image, ash::vk::DeviceSize),
+    Buffer(ash::vk::Buffer, ash::vk::DeviceSize),
 }
 
 impl Drop for DeviceMemory {
@@ -27,6 +58,20 @@ impl DeviceMemory {
             device,
             allocation_size: 0,
             memory_type,
+            dedicated: None,
+        }
+    }
+    // a dedicated allocation can only be bound to its resource, at offset 0
+    pub(crate) fn check_dedicated(
+        &self,
+        handle: u64,
+        memory_offset: ash::vk::DeviceSize,
+        vuid: &'static str,
+    ) {
+        if let Some(dedicated_to) = self.dedicated_to {
+            if dedicated_to != handle || memory_offset != 0 {
+                panic!("{}", vuid);
+            }
         }
     }
     pub fn map_memory<F: FnOnce(&mut [u8])>(
@@ -34,16 +79,28 @@ impl DeviceMemory {
         offset: ash::vk::DeviceSize,
         size: ash::vk::DeviceSize,
         f: F,
-    ) -> Result<(), ash::vk::Result> {
+    ) -> Result<()> {
+        // MUST VUID-vkMapMemory-memory-00682
+        if !self.is_host_visible() {
+            panic!("VUID-vkMapMemory-memory-00682");
+        }
         // DONE VUID-vkMapMemory-memory-00678
         // Host Synchronization: memory
         unsafe {
-            let ptr = self.device.ash_device.map_memory(
-                self.vk_device_memory,
-                offset,
-                size,
-                ash::vk::MemoryMapFlags::empty(),
-            )?;
+            let ptr = self
+                .device
+                .ash_device
+                .map_memory(
+                    self.vk_device_memory,
+                    offset,
+                    size,
+                    ash::vk::MemoryMapFlags::empty(),
+                )
+                .context(
+                    "vkMapMemory",
+                    ash::vk::ObjectType::DEVICE_MEMORY,
+                    self.vk_device_memory.as_raw(),
+                )?;
             let mapped_memory = std::slice::from_raw_parts_mut(ptr as _, size as _);
             f(mapped_memory);
             self.device
@@ -52,12 +109,100 @@ impl DeviceMemory {
             Ok(())
         }
     }
+    // Map the whole memory and copy `size` bytes from `offset` back to host, the memory must be
+    // host visible, non-coherent memory is invalidated before reading.
+    pub fn read_memory(
+        &mut self,
+        offset: ash::vk::DeviceSize,
+        size: ash::vk::DeviceSize,
+    ) -> Result<Vec<u8>> {
+        // MUST VUID-vkMapMemory-offset-00679
+        if offset >= self.size {
+            panic!("VUID-vkMapMemory-offset-00679");
+        }
+        // MUST VUID-vkMapMemory-size-00681
+        if offset + size > self.size {
+            panic!("VUID-vkMapMemory-size-00681");
+        }
+        // MUST VUID-vkMapMemory-memory-00682
+        if !self.is_host_visible() {
+            panic!("VUID-vkMapMemory-memory-00682");
+        }
+        // DONE VUID-vkMapMemory-memory-00678
+        // Host Synchronization: memory
+        unsafe {
+            let ptr = self
+                .device
+                .ash_device
+                .map_memory(
+                    self.vk_device_memory,
+                    0,
+                    ash::vk::WHOLE_SIZE,
+                    ash::vk::MemoryMapFlags::empty(),
+                )
+                .context(
+                    "vkMapMemory",
+                    ash::vk::ObjectType::DEVICE_MEMORY,
+                    self.vk_device_memory.as_raw(),
+                )?;
+            // DONE VUID-VkMappedMemoryRange-offset-00687
+            // DONE VUID-VkMappedMemoryRange-size-01390
+            let range = ash::vk::MappedMemoryRange::builder()
+                .memory(self.vk_device_memory)
+                .offset(0)
+                .size(ash::vk::WHOLE_SIZE)
+                .build();
+            let result = self
+                .device
+                .ash_device
+                .invalidate_mapped_memory_ranges(&[range])
+                .map(|_| {
+                    std::slice::from_raw_parts(
+                        (ptr as *const u8).add(offset as usize),
+                        size as usize,
+                    )
+                    .to_vec()
+                })
+                .context(
+                    "vkInvalidateMappedMemoryRanges",
+                    ash::vk::ObjectType::DEVICE_MEMORY,
+                    self.vk_device_memory.as_raw(),
+                );
+            self.device
+                .ash_device
+                .unmap_memory(self.vk_device_memory);
+            result
+        }
+    }
+    fn is_host_visible(&self) -> bool {
+        self.memory_type
+            .property_flags
+            .contains(ash::vk::MemoryPropertyFlags::HOST_VISIBLE)
+    }
+    // The bytes of lazily allocated memory currently committed by the implementation
+    pub fn get_commitment(&self) -> ash::vk::DeviceSize {
+        // MUST VUID-vkGetDeviceMemoryCommitment-memory-00690
+        if !self
+            .memory_type
+            .property_flags
+            .contains(ash::vk::MemoryPropertyFlags::LAZILY_ALLOCATED)
+        {
+            panic!("VUID-vkGetDeviceMemoryCommitment-memory-00690");
+        }
+        unsafe {
+            // Host Synchronization: none
+            self.device
+                .ash_device
+                .get_device_memory_commitment(self.vk_device_memory)
+        }
+    }
 }
 
 pub struct DeviceMemoryBuilder {
     device: Arc<Device>,
     allocation_size: ash::vk::DeviceSize,
     memory_type: MemoryType,
+    dedicated: Option<Dedicated>,
 }
 
 impl DeviceMemoryBuilder {
@@ -65,23 +210,75 @@ impl DeviceMemoryBuilder {
         self.allocation_size = allocation_size;
         self
     }
-    pub fn build(self) -> Result<DeviceMemory, ash::vk::Result> {
+    // allocate the memory for `image` only, `allocation_size` must be the size of its memory
+    // requirements
+    pub fn dedicated_image(mut self, image: &Image<{ crate::image::State::Unbound }>) -> Self {
+        // MUST VUID-VkMemoryDedicatedAllocateInfo-image-01434
+        if image.is_sparse() {
+            panic!("VUID-VkMemoryDedicatedAllocateInfo-image-01434");
+        }
+        self.dedicated = Some(Dedicated::Image(
+            image.vk_image,
+            image.get_image_memory_requirements().size,
+        ));
+        self
+    }
+    // allocate the memory for `buffer` only, `allocation_size` must be the size of its memory
+    // requirements
+    pub fn dedicated_buffer(mut self, buffer: &Buffer<{ crate::buffer::State::Unbound }>) -> Self {
+        // MUST VUID-VkMemoryDedicatedAllocateInfo-buffer-01436
+        if buffer.is_sparse() {
+            panic!("VUID-VkMemoryDedicatedAllocateInfo-buffer-01436");
+        }
+        self.dedicated = Some(Dedicated::Buffer(
+            buffer.ash_vk_buffer,
+            buffer.get_buffer_memory_requirements().size,
+        ));
+        self
+    }
+    pub fn build(self) -> Result<DeviceMemory> {
+        let mut dedicated_info = ash::vk::MemoryDedicatedAllocateInfo::default();
+        let dedicated_to = match self.dedicated {
+            Some(Dedicated::Image(image, size)) => {
+                // MUST VUID-VkMemoryDedicatedAllocateInfo-image-02964
+                if self.allocation_size != size {
+                    panic!("VUID-VkMemoryDedicatedAllocateInfo-image-02964");
+                }
+                dedicated_info.image = image;
+                Some(image.as_raw())
+            }
+            Some(Dedicated::Buffer(buffer, size)) => {
+                // MUST VUID-VkMemoryDedicatedAllocateInfo-buffer-02965
+                if self.allocation_size != size {
+                    panic!("VUID-VkMemoryDedicatedAllocateInfo-buffer-02965");
+                }
+                dedicated_info.buffer = buffer;
+                Some(buffer.as_raw())
+            }
+            None => None,
+        };
         self.device.allocations.fetch_add(1, Ordering::Relaxed);
-        let vk_allocate_info = ash::vk::MemoryAllocateInfo::builder()
+        let mut vk_allocate_info = ash::vk::MemoryAllocateInfo::builder()
             .memory_type_index(self.memory_type.index)
-            .allocation_size(self.allocation_size)
-            .build();
+            .allocation_size(self.allocation_size);
+        if dedicated_to.is_some() {
+            vk_allocate_info = vk_allocate_info.push_next(&mut dedicated_info);
+        }
+        let vk_allocate_info = vk_allocate_info.build();
         // Host Synchronization: none
         let vk_device_memory = unsafe {
             self.device
                 .ash_device
-                .allocate_memory(&vk_allocate_info, None)?
+                .allocate_memory(&vk_allocate_info, None)
+                .context("vkAllocateMemory", ash::vk::ObjectType::DEVICE_MEMORY, 0)?
         };
 
         Ok(DeviceMemory {
             device: self.device,
             vk_device_memory,
             size: self.allocation_size,
+            memory_type: self.memory_type,
+            dedicated_to,
         })
     }
 }
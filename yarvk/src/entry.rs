@@ -27,4 +27,15 @@ impl Entry {
             })
             .collect())
     }
+    pub fn enumerate_instance_layer_properties(
+        &self,
+    ) -> Result<Vec<ash::vk::LayerProperties>, ash::vk::Result> {
+        self.ash_entry.enumerate_instance_layer_properties()
+    }
+    pub fn has_layer(&self, layer_name: &CStr) -> Result<bool, ash::vk::Result> {
+        Ok(self
+            .enumerate_instance_layer_properties()?
+            .iter()
+            .any(|layer| unsafe { CStr::from_ptr(layer.layer_name.as_ptr()) } == layer_name))
+    }
 }
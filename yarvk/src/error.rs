@@ -0,0 +1,116 @@
+use std::fmt::{Display, Formatter};
+
+// How a broken valid usage is reported, by layer:
+// * command recording (`cmd_*`), queue operations and methods of created objects panic with the
+//   VUID (the `// MUST` checks), a violation is a bug of the calling code
+// * builders panic as well, except `PipelineBuilder` and `FramebufferBuilder` whose inputs come
+//   from shaders, config or the swapchain extent at runtime, their `build` returns
+//   `Error::Validation`
+// * helpers consuming asset data (`cmd_upload_image`, `texture`) return `Error::Validation` when
+//   the data doesn't fit the image
+// * a failure returned by the driver is always `Error::Vulkan` or `Error::Operation`, it never
+//   panics
+// there is no conversion from `Error` back into `ash::vk::Result`, `Error::result` has to be
+// called explicitly since it drops the VUID and the operation context
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    // a vulkan error without any context
+    Vulkan(ash::vk::Result),
+    // `operation` (the vulkan command) failed on `object`, `handle` is null if the object is not
+    // created yet
+    Operation {
+        operation: &'static str,
+        object: ash::vk::ObjectType,
+        handle: u64,
+        result: ash::vk::Result,
+    },
+    // a valid usage the caller can recover from, e.g. a pipeline built from data-driven config
+    Validation { vuid: &'static str },
+    // none of the memory types in `memory_type_bits` has all the `property_flags`
+    NoMemoryType {
+        memory_type_bits: u32,
+        property_flags: ash::vk::MemoryPropertyFlags,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn result(&self) -> ash::vk::Result {
+        match self {
+            Error::Vulkan(result) => *result,
+            Error::Operation { result, .. } => *result,
+            Error::Validation { .. } => ash::vk::Result::ERROR_VALIDATION_FAILED_EXT,
+            Error::NoMemoryType { .. } => ash::vk::Result::ERROR_UNKNOWN,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Vulkan(result) => write!(f, "{}", result),
+            Error::Operation {
+                operation,
+                object,
+                handle,
+                result,
+            } => write!(
+                f,
+                "{} failed on {:?} {:#x}: {}",
+                operation, object, handle, result
+            ),
+            Error::Validation { vuid } => write!(f, "validation failed: {}", vuid),
+            Error::NoMemoryType {
+                memory_type_bits,
+                property_flags,
+            } => write!(
+                f,
+                "no memory type in {:#b} is {:?}",
+                memory_type_bits, property_flags
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Vulkan(result) => Some(result),
+            Error::Operation { result, .. } => Some(result),
+            Error::Validation { .. } => None,
+            Error::NoMemoryType { .. } => None,
+        }
+    }
+}
+
+impl From<ash::vk::Result> for Error {
+    fn from(result: ash::vk::Result) -> Self {
+        Error::Vulkan(result)
+    }
+}
+
+pub(crate) trait ResultContext<T> {
+    fn context(
+        self,
+        operation: &'static str,
+        object: ash::vk::ObjectType,
+        handle: u64,
+    ) -> Result<T>;
+}
+
+impl<T> ResultContext<T> for std::result::Result<T, ash::vk::Result> {
+    fn context(
+        self,
+        operation: &'static str,
+        object: ash::vk::ObjectType,
+        handle: u64,
+    ) -> Result<T> {
+        self.map_err(|result| Error::Operation {
+            operation,
+            object,
+            handle,
+            result,
+        })
+    }
+}
@@ -308,6 +308,7 @@ pub enum PhysicalDeviceExtensionType {
     FuchsiaBufferCollection,
     IntelShaderIntegerFunctions2,
     KhrWorkgroupMemoryExplicitLayout,
+    ExtMeshShader,
     ExtVertexInputDynamicState,
     NvRepresentativeFragmentTest,
     AmdBufferMarker,
@@ -350,6 +351,7 @@ pub enum PhysicalDeviceExtensionType {
     KhrExternalMemoryFd,
     KhrWin32KeyedMutex,
     NvExternalMemoryWin32,
+    ExtExtendedDynamicState3,
     NvShaderSubgroupPartitioned,
     ExtExternalMemoryHost,
     GoogleDisplayTiming,
@@ -682,6 +684,9 @@ impl PhysicalDeviceExtensionType {
                     b"VK_KHR_workgroup_memory_explicit_layout\0",
                 )
             },
+            Self::ExtMeshShader => unsafe {
+                std::ffi::CStr::from_bytes_with_nul_unchecked(b"VK_EXT_mesh_shader\0")
+            },
             Self::ExtVertexInputDynamicState => unsafe {
                 std::ffi::CStr::from_bytes_with_nul_unchecked(
                     b"VK_EXT_vertex_input_dynamic_state\0",
@@ -820,6 +825,9 @@ impl PhysicalDeviceExtensionType {
             Self::NvExternalMemoryWin32 => unsafe {
                 std::ffi::CStr::from_bytes_with_nul_unchecked(b"VK_NV_external_memory_win32\0")
             },
+            Self::ExtExtendedDynamicState3 => unsafe {
+                std::ffi::CStr::from_bytes_with_nul_unchecked(b"VK_EXT_extended_dynamic_state3\0")
+            },
             Self::NvShaderSubgroupPartitioned => unsafe {
                 std::ffi::CStr::from_bytes_with_nul_unchecked(
                     b"VK_NV_shader_subgroup_partitioned\0",
@@ -1038,6 +1046,7 @@ impl PhysicalDeviceExtensionType {
             Self::FuchsiaBufferCollection => &[Self::FuchsiaExternalMemory],
             Self::IntelShaderIntegerFunctions2 => &[],
             Self::KhrWorkgroupMemoryExplicitLayout => &[],
+            Self::ExtMeshShader => &[],
             Self::ExtVertexInputDynamicState => &[],
             Self::NvRepresentativeFragmentTest => &[],
             Self::AmdBufferMarker => &[],
@@ -1087,6 +1096,7 @@ impl PhysicalDeviceExtensionType {
             Self::KhrExternalMemoryFd => &[],
             Self::KhrWin32KeyedMutex => &[Self::KhrExternalMemoryWin32],
             Self::NvExternalMemoryWin32 => &[Self::NvExternalMemory],
+            Self::ExtExtendedDynamicState3 => &[],
             Self::NvShaderSubgroupPartitioned => &[],
             Self::ExtExternalMemoryHost => &[],
             Self::GoogleDisplayTiming => &[Self::KhrSwapchain],
@@ -1235,6 +1245,7 @@ impl PhysicalDeviceExtensionType {
             b"VK_KHR_workgroup_memory_explicit_layout\0" => {
                 Some(Self::KhrWorkgroupMemoryExplicitLayout)
             }
+            b"VK_EXT_mesh_shader\0" => Some(Self::ExtMeshShader),
             b"VK_EXT_vertex_input_dynamic_state\0" => Some(Self::ExtVertexInputDynamicState),
             b"VK_NV_representative_fragment_test\0" => Some(Self::NvRepresentativeFragmentTest),
             b"VK_AMD_buffer_marker\0" => Some(Self::AmdBufferMarker),
@@ -1279,6 +1290,7 @@ impl PhysicalDeviceExtensionType {
             b"VK_KHR_external_memory_fd\0" => Some(Self::KhrExternalMemoryFd),
             b"VK_KHR_win32_keyed_mutex\0" => Some(Self::KhrWin32KeyedMutex),
             b"VK_NV_external_memory_win32\0" => Some(Self::NvExternalMemoryWin32),
+            b"VK_EXT_extended_dynamic_state3\0" => Some(Self::ExtExtendedDynamicState3),
             b"VK_NV_shader_subgroup_partitioned\0" => Some(Self::NvShaderSubgroupPartitioned),
             b"VK_EXT_external_memory_host\0" => Some(Self::ExtExternalMemoryHost),
             b"VK_GOOGLE_display_timing\0" => Some(Self::GoogleDisplayTiming),
@@ -1436,6 +1448,7 @@ pub enum DeviceExtensionType {
     FuchsiaBufferCollection,
     IntelShaderIntegerFunctions2,
     KhrWorkgroupMemoryExplicitLayout,
+    ExtMeshShader,
     ExtVertexInputDynamicState,
     NvRepresentativeFragmentTest,
     AmdBufferMarker,
@@ -1483,6 +1496,7 @@ pub enum DeviceExtensionType {
     NvExternalMemoryWin32(
         InstanceExtension<{ PhysicalInstanceExtensionType::NvExternalMemoryCapabilities }>,
     ),
+    ExtExtendedDynamicState3,
     NvShaderSubgroupPartitioned,
     ExtExternalMemoryHost,
     GoogleDisplayTiming(InstanceExtension<{ PhysicalInstanceExtensionType::KhrSurface }>),
@@ -1629,6 +1643,7 @@ impl From<&DeviceExtensionType> for PhysicalDeviceExtensionType {
             DeviceExtensionType::KhrWorkgroupMemoryExplicitLayout => {
                 Self::KhrWorkgroupMemoryExplicitLayout
             }
+            DeviceExtensionType::ExtMeshShader => Self::ExtMeshShader,
             DeviceExtensionType::ExtVertexInputDynamicState => Self::ExtVertexInputDynamicState,
             DeviceExtensionType::NvRepresentativeFragmentTest => Self::NvRepresentativeFragmentTest,
             DeviceExtensionType::AmdBufferMarker => Self::AmdBufferMarker,
@@ -1675,6 +1690,7 @@ impl From<&DeviceExtensionType> for PhysicalDeviceExtensionType {
             DeviceExtensionType::KhrExternalMemoryFd => Self::KhrExternalMemoryFd,
             DeviceExtensionType::KhrWin32KeyedMutex => Self::KhrWin32KeyedMutex,
             DeviceExtensionType::NvExternalMemoryWin32(..) => Self::NvExternalMemoryWin32,
+            DeviceExtensionType::ExtExtendedDynamicState3 => Self::ExtExtendedDynamicState3,
             DeviceExtensionType::NvShaderSubgroupPartitioned => Self::NvShaderSubgroupPartitioned,
             DeviceExtensionType::ExtExternalMemoryHost => Self::ExtExternalMemoryHost,
             DeviceExtensionType::GoogleDisplayTiming(..) => Self::GoogleDisplayTiming,
@@ -1,11 +1,110 @@
+use crate::command::command_pool::CommandPool;
 use crate::device::Device;
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::KhrExternalFenceFd;
+use crate::physical_device::PhysicalDevice;
 use derive_more::Deref;
+use parking_lot::{Condvar, Mutex};
+use std::future::Future;
 use std::mem::ManuallyDrop;
+use std::os::raw::c_int;
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+impl PhysicalDevice {
+    pub fn get_external_fence_properties(
+        &self,
+        handle_type: ash::vk::ExternalFenceHandleTypeFlags,
+    ) -> ash::vk::ExternalFenceProperties {
+        let info = ash::vk::PhysicalDeviceExternalFenceInfo::builder()
+            .handle_type(handle_type)
+            .build();
+        let mut properties = ash::vk::ExternalFenceProperties::default();
+        unsafe {
+            // Host Synchronization: none
+            self.instance
+                .ash_instance
+                .get_physical_device_external_fence_properties(
+                    self.vk_physical_device,
+                    &info,
+                    &mut properties,
+                );
+        }
+        properties
+    }
+}
+
+fn check_fd_handle_type(handle_type: ash::vk::ExternalFenceHandleTypeFlags, vuid: &str) {
+    if handle_type != ash::vk::ExternalFenceHandleTypeFlags::OPAQUE_FD
+        && handle_type != ash::vk::ExternalFenceHandleTypeFlags::SYNC_FD
+    {
+        panic!("{}", vuid);
+    }
+}
+
+pub struct FenceBuilder {
+    device: Arc<Device>,
+    export_handle_types: ash::vk::ExternalFenceHandleTypeFlags,
+}
+
+impl FenceBuilder {
+    // the fence payload can be exported as any of `handle_types`, see `Fence::export_fd` and
+    // `SignaledFence::export_sync_fd`
+    pub fn exportable(
+        mut self,
+        handle_types: ash::vk::ExternalFenceHandleTypeFlags,
+        _extension: &DeviceExtension<{ KhrExternalFenceFd }>,
+    ) -> Self {
+        // MUST VUID-VkExportFenceCreateInfo-handleTypes-01446
+        for bit in 0..u32::BITS {
+            let handle_type = ash::vk::ExternalFenceHandleTypeFlags::from_raw(1 << bit);
+            if !handle_types.contains(handle_type) {
+                continue;
+            }
+            let properties = self
+                .device
+                .physical_device
+                .get_external_fence_properties(handle_type);
+            if !properties
+                .external_fence_features
+                .contains(ash::vk::ExternalFenceFeatureFlags::EXPORTABLE)
+                || !properties.compatible_handle_types.contains(handle_types)
+            {
+                panic!("VUID-VkExportFenceCreateInfo-handleTypes-01446");
+            }
+        }
+        self.export_handle_types = handle_types;
+        self
+    }
+
+    pub fn build(self) -> Result<UnsignaledFence, ash::vk::Result> {
+        let mut export_create_info = ash::vk::ExportFenceCreateInfo::builder()
+            .handle_types(self.export_handle_types)
+            .build();
+        let mut create_info = ash::vk::FenceCreateInfo::builder();
+        if !self.export_handle_types.is_empty() {
+            create_info = create_info.push_next(&mut export_create_info);
+        }
+        // Host Synchronization: none
+        let vk_fence = unsafe { self.device.ash_device.create_fence(&create_info, None)? };
+        Ok(UnsignaledFence(Fence {
+            device: self.device,
+            vk_fence,
+            export_handle_types: self.export_handle_types,
+            pending_command_pools: Vec::new(),
+        }))
+    }
+}
 
 pub struct Fence {
     pub device: Arc<Device>,
     pub(crate) vk_fence: ash::vk::Fence,
+    export_handle_types: ash::vk::ExternalFenceHandleTypeFlags,
+    // the pool of each command buffer submitted with this fence, they are pending until the
+    // fence is waited
+    pub(crate) pending_command_pools: Vec<Arc<CommandPool>>,
 }
 
 impl Drop for Fence {
@@ -20,13 +119,15 @@ impl Drop for Fence {
 }
 
 impl Fence {
+    pub fn builder(device: Arc<Device>) -> FenceBuilder {
+        FenceBuilder {
+            device,
+            export_handle_types: Default::default(),
+        }
+    }
+
     pub fn new(device: Arc<Device>) -> Result<UnsignaledFence, ash::vk::Result> {
-        let create_info = ash::vk::FenceCreateInfo::builder()
-            .flags(ash::vk::FenceCreateFlags::default())
-            .build();
-        // Host Synchronization: none
-        let vk_fence = unsafe { device.ash_device.create_fence(&create_info, None)? };
-        Ok(UnsignaledFence(Fence { device, vk_fence }))
+        Self::builder(device).build()
     }
 
     pub fn new_signaling<T>(
@@ -39,10 +140,46 @@ impl Fence {
         // Host Synchronization: none
         let vk_fence = unsafe { device.ash_device.create_fence(&create_info, None)? };
         Ok(SignalingFence {
-            inner: Fence { device, vk_fence },
+            inner: Fence {
+                device,
+                vk_fence,
+                export_handle_types: Default::default(),
+                pending_command_pools: Vec::new(),
+            },
             t,
         })
     }
+
+    fn get_fd(
+        &self,
+        handle_type: ash::vk::ExternalFenceHandleTypeFlags,
+    ) -> Result<c_int, ash::vk::Result> {
+        // MUST VUID-VkFenceGetFdInfoKHR-handleType-01453
+        if !self.export_handle_types.contains(handle_type) {
+            panic!("VUID-VkFenceGetFdInfoKHR-handleType-01453");
+        }
+        // MUST VUID-VkFenceGetFdInfoKHR-handleType-01456
+        check_fd_handle_type(handle_type, "VUID-VkFenceGetFdInfoKHR-handleType-01456");
+        // TODO VUID-VkFenceGetFdInfoKHR-fence-01455
+        let get_info = ash::vk::FenceGetFdInfoKHR::builder()
+            .fence(self.vk_fence)
+            .handle_type(handle_type)
+            .build();
+        unsafe {
+            // Host Synchronization: none
+            self.device
+                .external_fence_fd_loader()
+                .get_fence_fd(&get_info)
+        }
+    }
+
+    // export the payload as an opaque POSIX file descriptor, the caller owns the returned fd
+    pub fn export_fd(
+        &self,
+        _extension: &DeviceExtension<{ KhrExternalFenceFd }>,
+    ) -> Result<c_int, ash::vk::Result> {
+        self.get_fd(ash::vk::ExternalFenceHandleTypeFlags::OPAQUE_FD)
+    }
 }
 
 #[derive(Deref)]
@@ -52,6 +189,43 @@ impl UnsignaledFence {
     pub(crate) fn to_executing_fence<T>(self, t: T) -> SignalingFence<T> {
         SignalingFence { inner: self.0, t }
     }
+
+    // import a payload from a POSIX file descriptor, on success the ownership of `fd` is
+    // transferred to the implementation and the fence is waited like a submitted one
+    pub fn import_fd(
+        self,
+        handle_type: ash::vk::ExternalFenceHandleTypeFlags,
+        fd: c_int,
+        temporary: bool,
+        _extension: &DeviceExtension<{ KhrExternalFenceFd }>,
+    ) -> Result<SignalingFence<()>, ash::vk::Result> {
+        // DONE VUID-vkImportFenceFdKHR-fence-01463
+        // MUST VUID-VkImportFenceFdInfoKHR-handleType-01464
+        check_fd_handle_type(handle_type, "VUID-VkImportFenceFdInfoKHR-handleType-01464");
+        // MUST VUID-VkImportFenceFdInfoKHR-handleType-07306
+        if handle_type == ash::vk::ExternalFenceHandleTypeFlags::SYNC_FD && !temporary {
+            panic!("VUID-VkImportFenceFdInfoKHR-handleType-07306");
+        }
+        // TODO VUID-VkImportFenceFdInfoKHR-fd-01541
+        let flags = if temporary {
+            ash::vk::FenceImportFlags::TEMPORARY
+        } else {
+            ash::vk::FenceImportFlags::empty()
+        };
+        let import_info = ash::vk::ImportFenceFdInfoKHR::builder()
+            .fence(self.vk_fence)
+            .flags(flags)
+            .handle_type(handle_type)
+            .fd(fd)
+            .build();
+        unsafe {
+            // Host Synchronization: fence
+            self.device
+                .external_fence_fd_loader()
+                .import_fence_fd(&import_info)?;
+        }
+        Ok(self.to_executing_fence(()))
+    }
 }
 
 #[derive(Deref)]
@@ -72,13 +246,237 @@ impl<T> SignalingFence<T> {
                 .ash_device
                 .wait_for_fences(&[self.vk_fence], true, timeout)?;
             let fence = ManuallyDrop::new(self);
-            let fence_inner = std::ptr::read(&fence.inner);
+            let mut fence_inner = std::ptr::read(&fence.inner);
             let t = std::ptr::read(&fence.t);
+            for command_pool in fence_inner.pending_command_pools.drain(..) {
+                command_pool.pending_count.fetch_sub(1, Ordering::Release);
+            }
             Ok((SignaledFence(fence_inner), t))
         }
     }
 }
 
+impl<T> SignalingFence<T> {
+    fn status(&self) -> Result<bool, ash::vk::Result> {
+        // Host Synchronization: none
+        unsafe { self.device.ash_device.get_fence_status(self.vk_fence) }
+    }
+
+    // only called once `status` reports the fence is signaled or the device is lost
+    fn complete(self, status: Result<bool, ash::vk::Result>) -> FenceResult<T> {
+        match status {
+            Ok(_) => self.wait(),
+            Err(error) => {
+                // the device is lost, leak the fence rather than panic on destroying it in use
+                std::mem::forget(self);
+                Err(error)
+            }
+        }
+    }
+}
+
+impl<T: Send + 'static> SignalingFence<T> {
+    // Poll the fence status in the future itself, a pending fence is handed over to the reactor
+    // thread of its device, which owns it until it is signaled, so dropping the future early
+    // never destroys a fence in use. Works with any executor.
+    pub fn wait_async(self) -> FenceFuture<T> {
+        FenceFuture {
+            fence: Some(self),
+            shared: Arc::new(FenceFutureShared {
+                result: Mutex::new(None),
+                waker: Mutex::new(None),
+            }),
+        }
+    }
+}
+
+type FenceResult<T> = Result<(SignaledFence, T), ash::vk::Result>;
+
+struct FenceFutureShared<T> {
+    result: Mutex<Option<FenceResult<T>>>,
+    waker: Mutex<Option<Waker>>,
+}
+
+trait PendingFence: Send {
+    fn device(&self) -> &Arc<Device>;
+    fn vk_fence(&self) -> ash::vk::Fence;
+    fn status(&self) -> Result<bool, ash::vk::Result>;
+    fn complete(self: Box<Self>, status: Result<bool, ash::vk::Result>);
+}
+
+struct PendingSignalingFence<T> {
+    fence: SignalingFence<T>,
+    shared: Arc<FenceFutureShared<T>>,
+}
+
+impl<T: Send> PendingFence for PendingSignalingFence<T> {
+    fn device(&self) -> &Arc<Device> {
+        &self.fence.device
+    }
+    fn vk_fence(&self) -> ash::vk::Fence {
+        self.fence.vk_fence
+    }
+    fn status(&self) -> Result<bool, ash::vk::Result> {
+        self.fence.status()
+    }
+    fn complete(self: Box<Self>, status: Result<bool, ash::vk::Result>) {
+        let result = self.fence.complete(status);
+        *self.shared.result.lock() = Some(result);
+        if let Some(waker) = self.shared.waker.lock().take() {
+            waker.wake();
+        }
+    }
+}
+
+// how long the reactor blocks on the pending fences before picking up newly registered ones, in
+// nanoseconds
+const REACTOR_WAIT_TIMEOUT: u64 = 100_000_000;
+
+// Waits on the pending fences of one device, started by the first `FenceFuture` of the device
+// that has to wait and shut down when the device is dropped. The pending fences hold the device,
+// so the device is never dropped while the reactor has fences to wait on.
+pub(crate) struct FenceReactor {
+    state: Mutex<FenceReactorState>,
+    condvar: Condvar,
+}
+
+struct FenceReactorState {
+    pending: Vec<Box<dyn PendingFence>>,
+    shutdown: bool,
+}
+
+impl Device {
+    fn fence_reactor(&self) -> Arc<FenceReactor> {
+        self.fence_reactor
+            .lock()
+            .get_or_insert_with(FenceReactor::start)
+            .clone()
+    }
+}
+
+impl FenceReactor {
+    fn start() -> Arc<Self> {
+        let reactor = Arc::new(FenceReactor {
+            state: Mutex::new(FenceReactorState {
+                pending: Vec::new(),
+                shutdown: false,
+            }),
+            condvar: Condvar::new(),
+        });
+        let thread_reactor = reactor.clone();
+        std::thread::Builder::new()
+            .name("yarvk-fence-reactor".to_string())
+            .spawn(move || thread_reactor.run())
+            .expect("failed to spawn the fence reactor thread");
+        reactor
+    }
+
+    fn register(&self, fence: Box<dyn PendingFence>) {
+        self.state.lock().pending.push(fence);
+        self.condvar.notify_one();
+    }
+
+    // not joined, the device may be dropped on the reactor thread by a completed fence
+    pub(crate) fn shutdown(&self) {
+        self.state.lock().shutdown = true;
+        self.condvar.notify_one();
+    }
+
+    fn run(&self) {
+        loop {
+            let batch = {
+                let mut state = self.state.lock();
+                while state.pending.is_empty() && !state.shutdown {
+                    self.condvar.wait(&mut state);
+                }
+                if state.shutdown {
+                    return;
+                }
+                std::mem::take(&mut state.pending)
+            };
+            let device = batch[0].device().clone();
+            let vk_fences = batch
+                .iter()
+                .map(|fence| fence.vk_fence())
+                .collect::<Vec<_>>();
+            let result = unsafe {
+                // Host Synchronization: none
+                device
+                    .ash_device
+                    .wait_for_fences(&vk_fences, false, REACTOR_WAIT_TIMEOUT)
+            };
+            if result == Err(ash::vk::Result::ERROR_DEVICE_LOST) {
+                // none of the fences will be signaled
+                for fence in batch {
+                    fence.complete(Err(ash::vk::Result::ERROR_DEVICE_LOST));
+                }
+                continue;
+            }
+            let mut still_pending = Vec::with_capacity(batch.len());
+            for fence in batch {
+                match fence.status() {
+                    Ok(false) => still_pending.push(fence),
+                    status => fence.complete(status),
+                }
+            }
+            self.state.lock().pending.append(&mut still_pending);
+        }
+    }
+}
+
+pub struct FenceFuture<T: Send + 'static> {
+    // the fence until it is handed over to the reactor
+    fence: Option<SignalingFence<T>>,
+    shared: Arc<FenceFutureShared<T>>,
+}
+
+// the fence is never pinned, it is only moved out of the future
+impl<T: Send + 'static> Unpin for FenceFuture<T> {}
+
+impl<T: Send + 'static> Future for FenceFuture<T> {
+    type Output = FenceResult<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if let Some(fence) = this.fence.take() {
+            return match fence.status() {
+                Ok(false) => {
+                    *this.shared.waker.lock() = Some(cx.waker().clone());
+                    let reactor = fence.device.fence_reactor();
+                    reactor.register(Box::new(PendingSignalingFence {
+                        fence,
+                        shared: this.shared.clone(),
+                    }));
+                    Poll::Pending
+                }
+                status => Poll::Ready(fence.complete(status)),
+            };
+        }
+        if let Some(result) = this.shared.result.lock().take() {
+            return Poll::Ready(result);
+        }
+        *this.shared.waker.lock() = Some(cx.waker().clone());
+        // the reactor may complete the fence between the check and storing the waker
+        match this.shared.result.lock().take() {
+            Some(result) => Poll::Ready(result),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<T: Send + 'static> Drop for FenceFuture<T> {
+    fn drop(&mut self) {
+        // never polled, the reactor keeps the fence alive until it is signaled
+        if let Some(fence) = self.fence.take() {
+            let reactor = fence.device.fence_reactor();
+            reactor.register(Box::new(PendingSignalingFence {
+                fence,
+                shared: self.shared.clone(),
+            }));
+        }
+    }
+}
+
 impl<T> Drop for SignalingFence<T> {
     fn drop(&mut self) {
         // MUST VUID-vkDestroyFence-fence-01120
@@ -98,6 +496,17 @@ impl SignaledFence {
             Ok(UnsignaledFence(self.0))
         }
     }
+
+    // export the payload as a sync file descriptor, the caller owns the returned fd, exporting
+    // with copy transference resets the fence
+    pub fn export_sync_fd(
+        self,
+        _extension: &DeviceExtension<{ KhrExternalFenceFd }>,
+    ) -> Result<(UnsignaledFence, c_int), ash::vk::Result> {
+        // DONE VUID-VkFenceGetFdInfoKHR-handleType-01454
+        let fd = self.get_fd(ash::vk::ExternalFenceHandleTypeFlags::SYNC_FD)?;
+        Ok((UnsignaledFence(self.0), fd))
+    }
 }
 
 // impl Device {
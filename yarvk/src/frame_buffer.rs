@@ -1,9 +1,11 @@
 use crate::device::Device;
 use crate::device_features::Feature;
 use crate::device_features::PhysicalDeviceVulkan12Features::ImagelessFramebuffer;
+use crate::error::{Error, Result, ResultContext};
 use crate::image::image_view::ImageView;
 use crate::render_pass::attachment::AttachmentIndex;
 use crate::render_pass::RenderPass;
+use ash::vk::Handle;
 use std::collections::btree_map::BTreeMap;
 use std::sync::Arc;
 
@@ -20,10 +22,36 @@ impl FramebufferCreateFlags {
     }
 }
 
+// the description of an attachment of an imageless framebuffer, the image views passed to
+// `RenderPassBeginInfoBuilder::attachments` must match it
+#[derive(Clone)]
+pub struct FramebufferAttachmentImageInfo {
+    pub flags: ash::vk::ImageCreateFlags,
+    pub usage: ash::vk::ImageUsageFlags,
+    pub width: u32,
+    pub height: u32,
+    pub layer_count: u32,
+    pub view_formats: Vec<ash::vk::Format>,
+}
+
+impl FramebufferAttachmentImageInfo {
+    pub(crate) fn ash_builder(&self) -> ash::vk::FramebufferAttachmentImageInfoBuilder {
+        ash::vk::FramebufferAttachmentImageInfo::builder()
+            .flags(self.flags)
+            .usage(self.usage)
+            .width(self.width)
+            .height(self.height)
+            .layer_count(self.layer_count)
+            .view_formats(self.view_formats.as_slice())
+    }
+}
+
 pub struct Framebuffer {
     pub device: Arc<Device>,
     pub render_pass: Arc<RenderPass>,
     _attachments: BTreeMap<u32, Arc<ImageView>>,
+    // empty if the framebuffer is not imageless
+    pub(crate) attachment_image_infos: Vec<FramebufferAttachmentImageInfo>,
     pub(crate) ash_vk_framebuffer: ash::vk::Framebuffer,
 }
 
@@ -48,6 +76,7 @@ impl Framebuffer {
             width: Default::default(),
             height: Default::default(),
             layers: Default::default(),
+            attachment_image_infos: Default::default(),
         }
     }
 }
@@ -59,6 +88,7 @@ pub struct FramebufferBuilder {
     width: u32,
     height: u32,
     layers: u32,
+    attachment_image_infos: Vec<FramebufferAttachmentImageInfo>,
 }
 
 impl FramebufferBuilder {
@@ -90,7 +120,88 @@ impl FramebufferBuilder {
         self.layers = layers;
         self
     }
-    pub fn build(self, device: Arc<Device>) -> Result<Arc<Framebuffer>, ash::vk::Result> {
+    // Create the framebuffer without image views, one info for each attachment of the render
+    // pass, the views are given by `RenderPassBeginInfoBuilder::attachments` every time the render
+    // pass begins
+    pub fn imageless(
+        mut self,
+        attachment_image_infos: &[FramebufferAttachmentImageInfo],
+        feature: Feature<{ ImagelessFramebuffer.into() }>,
+    ) -> Self {
+        self.flags |= FramebufferCreateFlags::Imageless(feature).to_ash();
+        self.attachment_image_infos = attachment_image_infos.to_vec();
+        self
+    }
+    // attachments which don't match the render pass or are too small for the framebuffer are
+    // reported as `Error::Validation`
+    fn check_attachments(&self) -> Result<()> {
+        let attachment_descriptions = &self.render_pass.vk_attachments;
+        // MUST VUID-VkFramebufferCreateInfo-attachmentCount-00876
+        // a framebuffer without attachments is only valid for a render pass without attachments
+        if self.attachments.len() != attachment_descriptions.len()
+            || self
+                .attachments
+                .keys()
+                .enumerate()
+                .any(|(position, index)| *index as usize != position)
+        {
+            return Err(Error::Validation {
+                vuid: "VUID-VkFramebufferCreateInfo-attachmentCount-00876",
+            });
+        }
+        for (index, image_view) in &self.attachments {
+            let description = &attachment_descriptions[*index as usize];
+            let image_create_info = &image_view.image.image_create_info;
+            // MUST VUID-VkFramebufferCreateInfo-pAttachments-00880
+            if image_view.format != description.format {
+                return Err(Error::Validation {
+                    vuid: "VUID-VkFramebufferCreateInfo-pAttachments-00880",
+                });
+            }
+            // MUST VUID-VkFramebufferCreateInfo-pAttachments-00881
+            if image_create_info.samples != description.samples {
+                return Err(Error::Validation {
+                    vuid: "VUID-VkFramebufferCreateInfo-pAttachments-00881",
+                });
+            }
+            let mip_width = (image_create_info.extent.width >> image_view.base_mip_level).max(1);
+            let mip_height = (image_create_info.extent.height >> image_view.base_mip_level).max(1);
+            // MUST VUID-VkFramebufferCreateInfo-flags-04533
+            if mip_width < self.width {
+                return Err(Error::Validation {
+                    vuid: "VUID-VkFramebufferCreateInfo-flags-04533",
+                });
+            }
+            // MUST VUID-VkFramebufferCreateInfo-flags-04534
+            if mip_height < self.height {
+                return Err(Error::Validation {
+                    vuid: "VUID-VkFramebufferCreateInfo-flags-04534",
+                });
+            }
+            // MUST VUID-VkFramebufferCreateInfo-flags-04535
+            if image_view.layer_count < self.layers {
+                return Err(Error::Validation {
+                    vuid: "VUID-VkFramebufferCreateInfo-flags-04535",
+                });
+            }
+        }
+        Ok(())
+    }
+    pub fn build(self, device: Arc<Device>) -> Result<Arc<Framebuffer>> {
+        let imageless = self
+            .flags
+            .contains(ash::vk::FramebufferCreateFlags::IMAGELESS);
+        // MUST VUID-VkFramebufferCreateInfo-flags-03190
+        if imageless && self.attachment_image_infos.is_empty() {
+            panic!("VUID-VkFramebufferCreateInfo-flags-03190");
+        }
+        // MUST VUID-VkFramebufferCreateInfo-flags-03191
+        if imageless && self.attachment_image_infos.len() != self.render_pass.vk_attachments.len() {
+            panic!("VUID-VkFramebufferCreateInfo-flags-03191");
+        }
+        if !imageless {
+            self.check_attachments()?;
+        }
         // DONE VUID-VkFramebufferCreateInfo-flags-02778
         let ash_vk_attachments = self
             .attachments
@@ -111,17 +222,35 @@ impl FramebufferBuilder {
             frame_buffer_create_info.attachment_count = self.render_pass.vk_attachments.len() as _;
             frame_buffer_create_info.p_attachments = std::ptr::null();
         }
+        let ash_vk_attachment_image_infos = self
+            .attachment_image_infos
+            .iter()
+            .map(|info| info.ash_builder().build())
+            .collect::<Vec<_>>();
+        let attachments_create_info = ash::vk::FramebufferAttachmentsCreateInfo::builder()
+            .attachment_image_infos(ash_vk_attachment_image_infos.as_slice())
+            .build();
+        if imageless {
+            frame_buffer_create_info.p_next =
+                &attachments_create_info as *const _ as *const std::ffi::c_void;
+        }
         let ash_vk_framebuffer = unsafe {
             // Host Synchronization: none
             device
                 .ash_device
-                .create_framebuffer(&frame_buffer_create_info, None)?
+                .create_framebuffer(&frame_buffer_create_info, None)
+                .context(
+                    "vkCreateFramebuffer",
+                    ash::vk::ObjectType::RENDER_PASS,
+                    self.render_pass.ash_vk_renderpass.as_raw(),
+                )?
         };
 
         Ok(Arc::new(Framebuffer {
             device,
             render_pass: self.render_pass,
             _attachments: self.attachments,
+            attachment_image_infos: self.attachment_image_infos,
             ash_vk_framebuffer,
         }))
     }
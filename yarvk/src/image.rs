@@ -1,11 +1,19 @@
+use crate::barrier::ImageMemoryBarrier;
 use crate::buffer::Buffer;
 use crate::command::command_buffer::RenderPassScope::OUTSIDE;
 use crate::command::command_buffer::State::RECORDING;
-use crate::command::command_buffer::{CommandBuffer, Level};
+use crate::command::command_buffer::{CommandBuffer, Level, StagingBuffer};
 use crate::device::Device;
-use crate::device_memory::DeviceMemory;
+use crate::device_features::{
+    PhysicalDeviceFeatures, PhysicalDeviceVulkan11Features,
+    PhysicalDeviceYcbcrImageArraysFeaturesEXT,
+};
+use crate::device_memory::{DedicatedRequirements, DeviceMemory};
+use crate::error::{Error, Result, ResultContext};
+use crate::image::image_subresource_range::ImageSubresourceRange;
 use crate::image::State::{Bound, Unbound};
 use crate::physical_device::SharingMode;
+use crate::pipeline::pipeline_stage_flags::PipelineStageFlags;
 use ash::vk::Handle;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
@@ -32,6 +40,7 @@ pub struct ImageCreateInfo {
     pub usage: ash::vk::ImageUsageFlags,
     pub sharing_mode: SharingMode,
     pub initial_layout: ash::vk::ImageLayout,
+    pub view_formats: Vec<ash::vk::Format>,
 }
 
 pub struct ImageBuilder {
@@ -84,8 +93,198 @@ impl ImageBuilder {
         self.inner.initial_layout = initial_layout;
         self
     }
-    pub fn build(self) -> Result<Image<{ Unbound }>, ash::vk::Result> {
+    // The formats views of a MUTABLE_FORMAT image can be created with
+    pub fn view_formats(mut self, view_formats: &[ash::vk::Format]) -> Self {
+        self.inner.view_formats = view_formats.to_vec();
+        self
+    }
+    pub fn build(self) -> Result<Image<{ Unbound }>> {
         let image_create_info = self.inner;
+        // MUST VUID-VkImageCreateInfo-usage-00963
+        if image_create_info
+            .usage
+            .contains(ash::vk::ImageUsageFlags::TRANSIENT_ATTACHMENT)
+            && !(ash::vk::ImageUsageFlags::TRANSIENT_ATTACHMENT
+                | ash::vk::ImageUsageFlags::COLOR_ATTACHMENT
+                | ash::vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT
+                | ash::vk::ImageUsageFlags::INPUT_ATTACHMENT)
+                .contains(image_create_info.usage)
+        {
+            panic!("VUID-VkImageCreateInfo-usage-00963");
+        }
+        if image_create_info
+            .flags
+            .contains(ash::vk::ImageCreateFlags::CUBE_COMPATIBLE)
+        {
+            // MUST VUID-VkImageCreateInfo-flags-00949
+            if image_create_info.image_type != ash::vk::ImageType::TYPE_2D {
+                panic!("VUID-VkImageCreateInfo-flags-00949");
+            }
+            // MUST VUID-VkImageCreateInfo-imageType-00954
+            if image_create_info.extent.width != image_create_info.extent.height
+                || image_create_info.array_layers < 6
+            {
+                panic!("VUID-VkImageCreateInfo-imageType-00954");
+            }
+        }
+        let extent = image_create_info.extent;
+        match image_create_info.image_type {
+            ash::vk::ImageType::TYPE_1D => {
+                // MUST VUID-VkImageCreateInfo-imageType-00956
+                if extent.height != 1 || extent.depth != 1 {
+                    panic!("VUID-VkImageCreateInfo-imageType-00956");
+                }
+            }
+            ash::vk::ImageType::TYPE_2D => {
+                // MUST VUID-VkImageCreateInfo-imageType-00957
+                if extent.depth != 1 {
+                    panic!("VUID-VkImageCreateInfo-imageType-00957");
+                }
+            }
+            ash::vk::ImageType::TYPE_3D => {
+                // MUST VUID-VkImageCreateInfo-imageType-00961
+                if image_create_info.array_layers != 1 {
+                    panic!("VUID-VkImageCreateInfo-imageType-00961");
+                }
+            }
+            _ => {}
+        }
+        // MUST VUID-VkImageCreateInfo-flags-00950
+        if image_create_info
+            .flags
+            .contains(ash::vk::ImageCreateFlags::TYPE_2D_ARRAY_COMPATIBLE)
+            && image_create_info.image_type != ash::vk::ImageType::TYPE_3D
+        {
+            panic!("VUID-VkImageCreateInfo-flags-00950");
+        }
+        let flags = image_create_info.flags;
+        let has_feature = |feature: PhysicalDeviceFeatures| {
+            self.device.enabled_features.contains(&feature.into())
+        };
+        // MUST VUID-VkImageCreateInfo-flags-00969
+        if flags.contains(ash::vk::ImageCreateFlags::SPARSE_BINDING)
+            && !has_feature(PhysicalDeviceFeatures::SparseBinding)
+        {
+            panic!("VUID-VkImageCreateInfo-flags-00969");
+        }
+        // MUST VUID-VkImageCreateInfo-flags-01890
+        if flags.contains(ash::vk::ImageCreateFlags::PROTECTED)
+            && !self
+                .device
+                .enabled_features
+                .contains(&PhysicalDeviceVulkan11Features::ProtectedMemory.into())
+        {
+            panic!("VUID-VkImageCreateInfo-flags-01890");
+        }
+        // MUST VUID-VkImageCreateInfo-flags-00987
+        if flags.intersects(
+            ash::vk::ImageCreateFlags::SPARSE_RESIDENCY | ash::vk::ImageCreateFlags::SPARSE_ALIASED,
+        ) && !flags.contains(ash::vk::ImageCreateFlags::SPARSE_BINDING)
+        {
+            panic!("VUID-VkImageCreateInfo-flags-00987");
+        }
+        // MUST VUID-VkImageCreateInfo-flags-01924
+        if flags.contains(ash::vk::ImageCreateFlags::SPARSE_ALIASED)
+            && !has_feature(PhysicalDeviceFeatures::SparseResidencyAliased)
+        {
+            panic!("VUID-VkImageCreateInfo-flags-01924");
+        }
+        if flags.contains(ash::vk::ImageCreateFlags::SPARSE_RESIDENCY) {
+            // MUST VUID-VkImageCreateInfo-tiling-04121
+            if image_create_info.tiling == ash::vk::ImageTiling::LINEAR {
+                panic!("VUID-VkImageCreateInfo-tiling-04121");
+            }
+            match image_create_info.image_type {
+                // MUST VUID-VkImageCreateInfo-imageType-00970
+                ash::vk::ImageType::TYPE_1D => panic!("VUID-VkImageCreateInfo-imageType-00970"),
+                ash::vk::ImageType::TYPE_2D => {
+                    // MUST VUID-VkImageCreateInfo-imageType-00971
+                    if !has_feature(PhysicalDeviceFeatures::SparseResidencyImage2D) {
+                        panic!("VUID-VkImageCreateInfo-imageType-00971");
+                    }
+                    let (feature, vuid) = match image_create_info.samples {
+                        // MUST VUID-VkImageCreateInfo-imageType-00973
+                        ash::vk::SampleCountFlags::TYPE_2 => (
+                            PhysicalDeviceFeatures::SparseResidency2Samples,
+                            "VUID-VkImageCreateInfo-imageType-00973",
+                        ),
+                        // MUST VUID-VkImageCreateInfo-imageType-00974
+                        ash::vk::SampleCountFlags::TYPE_4 => (
+                            PhysicalDeviceFeatures::SparseResidency4Samples,
+                            "VUID-VkImageCreateInfo-imageType-00974",
+                        ),
+                        // MUST VUID-VkImageCreateInfo-imageType-00975
+                        ash::vk::SampleCountFlags::TYPE_8 => (
+                            PhysicalDeviceFeatures::SparseResidency8Samples,
+                            "VUID-VkImageCreateInfo-imageType-00975",
+                        ),
+                        // MUST VUID-VkImageCreateInfo-imageType-00976
+                        ash::vk::SampleCountFlags::TYPE_16 => (
+                            PhysicalDeviceFeatures::SparseResidency16Samples,
+                            "VUID-VkImageCreateInfo-imageType-00976",
+                        ),
+                        _ => (PhysicalDeviceFeatures::SparseResidencyImage2D, ""),
+                    };
+                    if !has_feature(feature) {
+                        panic!("{}", vuid);
+                    }
+                }
+                ash::vk::ImageType::TYPE_3D => {
+                    // MUST VUID-VkImageCreateInfo-imageType-00972
+                    if !has_feature(PhysicalDeviceFeatures::SparseResidencyImage3D) {
+                        panic!("VUID-VkImageCreateInfo-imageType-00972");
+                    }
+                }
+                _ => {}
+            }
+        }
+        if requires_ycbcr_conversion(image_create_info.format) {
+            // MUST VUID-VkImageCreateInfo-format-06410
+            if image_create_info.mip_levels != 1 {
+                panic!("VUID-VkImageCreateInfo-format-06410");
+            }
+            // MUST VUID-VkImageCreateInfo-format-06411
+            if image_create_info.samples != ash::vk::SampleCountFlags::TYPE_1 {
+                panic!("VUID-VkImageCreateInfo-format-06411");
+            }
+            // MUST VUID-VkImageCreateInfo-format-06412
+            if image_create_info.image_type != ash::vk::ImageType::TYPE_2D {
+                panic!("VUID-VkImageCreateInfo-format-06412");
+            }
+            // MUST VUID-VkImageCreateInfo-format-06413
+            if image_create_info.array_layers != 1
+                && !self
+                    .device
+                    .enabled_features
+                    .contains(&PhysicalDeviceYcbcrImageArraysFeaturesEXT::YcbcrImageArrays.into())
+            {
+                panic!("VUID-VkImageCreateInfo-format-06413");
+            }
+        }
+        // MUST VUID-VkImageCreateInfo-format-01577
+        if format_plane_count(image_create_info.format) == 1
+            && flags.contains(ash::vk::ImageCreateFlags::DISJOINT)
+        {
+            panic!("VUID-VkImageCreateInfo-format-01577");
+        }
+        // MUST VUID-VkImageCreateInfo-flags-04738
+        if !image_create_info
+            .flags
+            .contains(ash::vk::ImageCreateFlags::MUTABLE_FORMAT)
+            && image_create_info.view_formats.len() > 1
+        {
+            panic!("VUID-VkImageCreateInfo-flags-04738");
+        }
+        // SILENCE VK_KHR_image_format_list is promoted to 1.2, use the core struct without an
+        // extension token.
+        if !image_create_info.view_formats.is_empty()
+            && self.device.physical_device.instance.api_version < ash::vk::API_VERSION_1_2
+        {
+            panic!("view formats require api version 1.2 or later");
+        }
+        let mut format_list = ash::vk::ImageFormatListCreateInfo::builder()
+            .view_formats(image_create_info.view_formats.as_slice())
+            .build();
         let mut vk_iamge_create_info = ash::vk::ImageCreateInfo::builder()
             .flags(image_create_info.flags)
             .image_type(image_create_info.image_type)
@@ -110,12 +309,16 @@ impl ImageBuilder {
             }
         }
 
+        if !image_create_info.view_formats.is_empty() {
+            vk_iamge_create_info = vk_iamge_create_info.push_next(&mut format_list);
+        }
         let vk_image_create_info = vk_iamge_create_info.build();
         let vk_image = unsafe {
             // Host Synchronization: none
             self.device
                 .ash_device
-                .create_image(&vk_image_create_info, None)?
+                .create_image(&vk_image_create_info, None)
+                .context("vkCreateImage", ash::vk::ObjectType::IMAGE, 0)?
         };
         Ok(Image {
             device: self.device,
@@ -163,26 +366,235 @@ impl Image<{ Unbound }> {
                 .get_image_memory_requirements(self.vk_image)
         }
     }
+    // the memory requirements along with whether a dedicated allocation is preferred or required
+    pub fn get_image_memory_requirements2(
+        &self,
+    ) -> (ash::vk::MemoryRequirements, DedicatedRequirements) {
+        let mut dedicated_requirements = ash::vk::MemoryDedicatedRequirements::default();
+        let mut requirements = ash::vk::MemoryRequirements2::builder()
+            .push_next(&mut dedicated_requirements)
+            .build();
+        let info = ash::vk::ImageMemoryRequirementsInfo2::builder()
+            .image(self.vk_image)
+            .build();
+        unsafe {
+            // Host Synchronization: none
+            self.device
+                .ash_device
+                .get_image_memory_requirements2(&info, &mut requirements);
+        }
+        (
+            requirements.memory_requirements,
+            DedicatedRequirements::from_ash(&dedicated_requirements),
+        )
+    }
     pub fn bind_memory(
         self,
         memory: &DeviceMemory,
         memory_offset: ash::vk::DeviceSize,
-    ) -> Result<Arc<Image<{ Bound }>>, ash::vk::Result> {
+    ) -> Result<Arc<Image<{ Bound }>>> {
         // DONE VUID-vkBindImageMemory-image-01044
+        // MUST VUID-vkBindImageMemory-image-01045
+        if self.is_sparse() {
+            panic!("VUID-vkBindImageMemory-image-01045");
+        }
+        // MUST VUID-vkBindImageMemory-memory-01509
+        memory.check_dedicated(
+            self.vk_image.as_raw(),
+            memory_offset,
+            "VUID-vkBindImageMemory-memory-01509",
+        );
         unsafe {
             // Host Synchronization: image
-            self.device.ash_device.bind_image_memory(
-                self.vk_image,
-                memory.vk_device_memory,
-                memory_offset,
-            )?;
+            self.device
+                .ash_device
+                .bind_image_memory(self.vk_image, memory.vk_device_memory, memory_offset)
+                .context(
+                    "vkBindImageMemory",
+                    ash::vk::ObjectType::IMAGE,
+                    self.vk_image.as_raw(),
+                )?;
+        }
+        Ok(Arc::new(unsafe { std::mem::transmute(self) }))
+    }
+
+    // memory requirements of one plane of a DISJOINT multi-planar image
+    pub fn get_image_plane_memory_requirements(&self, plane: u32) -> ash::vk::MemoryRequirements {
+        // MUST VUID-VkImageMemoryRequirementsInfo2-image-01589
+        if !self.is_disjoint() {
+            panic!("VUID-VkImageMemoryRequirementsInfo2-image-01589");
+        }
+        // MUST VUID-VkImagePlaneMemoryRequirementsInfo-planeAspect-02281
+        if plane >= format_plane_count(self.image_create_info.format) {
+            panic!("VUID-VkImagePlaneMemoryRequirementsInfo-planeAspect-02281");
+        }
+        let mut plane_info = ash::vk::ImagePlaneMemoryRequirementsInfo::builder()
+            .plane_aspect(plane_aspect(plane))
+            .build();
+        let info = ash::vk::ImageMemoryRequirementsInfo2::builder()
+            .image(self.vk_image)
+            .push_next(&mut plane_info)
+            .build();
+        let mut requirements = ash::vk::MemoryRequirements2::default();
+        unsafe {
+            // Host Synchronization: none
+            self.device
+                .ash_device
+                .get_image_memory_requirements2(&info, &mut requirements);
+        }
+        requirements.memory_requirements
+    }
+
+    // bind each plane of a DISJOINT multi-planar image, `planes` is indexed by plane
+    pub fn bind_planes(
+        self,
+        planes: &[(&DeviceMemory, ash::vk::DeviceSize)],
+    ) -> Result<Arc<Image<{ Bound }>>> {
+        // MUST VUID-VkBindImageMemoryInfo-image-01617
+        if !self.is_disjoint()
+            || planes.len() != format_plane_count(self.image_create_info.format) as usize
+        {
+            panic!("VUID-VkBindImageMemoryInfo-image-01617");
+        }
+        let mut plane_infos = (0..planes.len() as u32)
+            .map(|plane| {
+                ash::vk::BindImagePlaneMemoryInfo::builder()
+                    .plane_aspect(plane_aspect(plane))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        let vk_bind_infos = planes
+            .iter()
+            .zip(plane_infos.iter_mut())
+            .map(|((memory, memory_offset), plane_info)| {
+                ash::vk::BindImageMemoryInfo::builder()
+                    .image(self.vk_image)
+                    .memory(memory.vk_device_memory)
+                    .memory_offset(*memory_offset)
+                    .push_next(plane_info)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        unsafe {
+            // Host Synchronization: none
+            self.device
+                .ash_device
+                .bind_image_memory2(vk_bind_infos.as_slice())
+                .context(
+                    "vkBindImageMemory2",
+                    ash::vk::ObjectType::IMAGE,
+                    self.vk_image.as_raw(),
+                )?;
         }
         Ok(Arc::new(unsafe { std::mem::transmute(self) }))
     }
+
+    fn is_disjoint(&self) -> bool {
+        self.image_create_info
+            .flags
+            .contains(ash::vk::ImageCreateFlags::DISJOINT)
+    }
+
+    // sparse images are bound through Queue::bind_sparse instead
+    pub fn into_sparse(self) -> Arc<Image<{ Bound }>> {
+        if !self.is_sparse() {
+            panic!("image is not created with SPARSE_BINDING");
+        }
+        Arc::new(unsafe { std::mem::transmute(self) })
+    }
+
+    pub(crate) fn is_sparse(&self) -> bool {
+        self.image_create_info
+            .flags
+            .contains(ash::vk::ImageCreateFlags::SPARSE_BINDING)
+    }
+}
+
+impl<const STATE: State> Image<STATE> {
+    pub fn get_image_sparse_memory_requirements(
+        &self,
+    ) -> Vec<ash::vk::SparseImageMemoryRequirements> {
+        // Host Synchronization: none
+        unsafe {
+            self.device
+                .ash_device
+                .get_image_sparse_memory_requirements(self.vk_image)
+        }
+    }
+}
+
+impl Image<{ Bound }> {
+    // last known layout, the initial layout until a tracked transition is recorded
+    pub fn layout(&self) -> ash::vk::ImageLayout {
+        self.device
+            .image_layouts
+            .read()
+            .get(&self.vk_image)
+            .copied()
+            .unwrap_or(self.image_create_info.initial_layout)
+    }
+    // for layouts changed outside tracked barriers, e.g. by render pass final layouts
+    pub fn set_layout(&self, layout: ash::vk::ImageLayout) {
+        self.device
+            .image_layouts
+            .write()
+            .insert(self.vk_image, layout);
+    }
+    pub(crate) fn full_subresource_range(&self) -> ImageSubresourceRange {
+        ImageSubresourceRange::builder()
+            .aspect_mask(format_aspect_mask(self.image_create_info.format))
+            .level_count(ash::vk::REMAINING_MIP_LEVELS)
+            .layer_count(ash::vk::REMAINING_ARRAY_LAYERS)
+            .build()
+    }
+}
+
+impl Device {
+    pub fn bind_images(
+        &self,
+        bind_infos: Vec<(Image<{ Unbound }>, &DeviceMemory, ash::vk::DeviceSize)>,
+    ) -> Result<Vec<Arc<Image<{ Bound }>>>> {
+        // DONE VUID-VkBindImageMemoryInfo-image-01044
+        let vk_bind_infos = bind_infos
+            .iter()
+            .map(|(image, memory, memory_offset)| {
+                // MUST VUID-VkBindImageMemoryInfo-image-01045
+                if image.is_sparse() {
+                    panic!("VUID-VkBindImageMemoryInfo-image-01045");
+                }
+                // MUST VUID-VkBindImageMemoryInfo-memory-01509
+                memory.check_dedicated(
+                    image.vk_image.as_raw(),
+                    *memory_offset,
+                    "VUID-VkBindImageMemoryInfo-memory-01509",
+                );
+                ash::vk::BindImageMemoryInfo::builder()
+                    .image(image.vk_image)
+                    .memory(memory.vk_device_memory)
+                    .memory_offset(*memory_offset)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        unsafe {
+            // Host Synchronization: none
+            self.ash_device
+                .bind_image_memory2(vk_bind_infos.as_slice())
+                .context(
+                    "vkBindImageMemory2",
+                    ash::vk::ObjectType::DEVICE,
+                    self.ash_device.handle().as_raw(),
+                )?;
+        }
+        Ok(bind_infos
+            .into_iter()
+            .map(|(image, _, _)| Arc::new(unsafe { std::mem::transmute(image) }))
+            .collect())
+    }
 }
 
 impl<const STATE: State> Drop for Image<STATE> {
     fn drop(&mut self) {
+        self.device.image_layouts.write().remove(&self.vk_image);
         // Host Synchronization: image
         unsafe {
             if !self.presentable {
@@ -194,6 +606,292 @@ impl<const STATE: State> Drop for Image<STATE> {
 
 pub type ImageFormatListCreateInfo = Vec<ash::vk::Format>;
 
+pub(crate) fn is_depth_stencil_format(format: ash::vk::Format) -> bool {
+    matches!(
+        format,
+        ash::vk::Format::D16_UNORM_S8_UINT
+            | ash::vk::Format::D24_UNORM_S8_UINT
+            | ash::vk::Format::D32_SFLOAT_S8_UINT
+    )
+}
+
+// all aspects of a single-plane format
+pub(crate) fn format_aspect_mask(format: ash::vk::Format) -> ash::vk::ImageAspectFlags {
+    match format {
+        ash::vk::Format::D16_UNORM
+        | ash::vk::Format::X8_D24_UNORM_PACK32
+        | ash::vk::Format::D32_SFLOAT => ash::vk::ImageAspectFlags::DEPTH,
+        ash::vk::Format::S8_UINT => ash::vk::ImageAspectFlags::STENCIL,
+        format if is_depth_stencil_format(format) => {
+            ash::vk::ImageAspectFlags::DEPTH | ash::vk::ImageAspectFlags::STENCIL
+        }
+        _ => ash::vk::ImageAspectFlags::COLOR,
+    }
+}
+
+// the size in bytes and the extent in texels of one texel block, `None` for combined
+// depth/stencil, multi-planar and other formats without a single block layout
+pub(crate) fn texel_block_size(format: ash::vk::Format) -> Option<(u64, u32, u32)> {
+    use ash::vk::Format;
+    let block = match format {
+        Format::R4G4_UNORM_PACK8
+        | Format::R8_UNORM
+        | Format::R8_SNORM
+        | Format::R8_USCALED
+        | Format::R8_SSCALED
+        | Format::R8_UINT
+        | Format::R8_SINT
+        | Format::R8_SRGB
+        | Format::S8_UINT => (1, 1, 1),
+        Format::R4G4B4A4_UNORM_PACK16
+        | Format::B4G4R4A4_UNORM_PACK16
+        | Format::R5G6B5_UNORM_PACK16
+        | Format::B5G6R5_UNORM_PACK16
+        | Format::R5G5B5A1_UNORM_PACK16
+        | Format::B5G5R5A1_UNORM_PACK16
+        | Format::A1R5G5B5_UNORM_PACK16
+        | Format::R8G8_UNORM
+        | Format::R8G8_SNORM
+        | Format::R8G8_USCALED
+        | Format::R8G8_SSCALED
+        | Format::R8G8_UINT
+        | Format::R8G8_SINT
+        | Format::R8G8_SRGB
+        | Format::R16_UNORM
+        | Format::R16_SNORM
+        | Format::R16_USCALED
+        | Format::R16_SSCALED
+        | Format::R16_UINT
+        | Format::R16_SINT
+        | Format::R16_SFLOAT
+        | Format::D16_UNORM => (2, 1, 1),
+        Format::R8G8B8_UNORM
+        | Format::R8G8B8_SNORM
+        | Format::R8G8B8_USCALED
+        | Format::R8G8B8_SSCALED
+        | Format::R8G8B8_UINT
+        | Format::R8G8B8_SINT
+        | Format::R8G8B8_SRGB
+        | Format::B8G8R8_UNORM
+        | Format::B8G8R8_SNORM
+        | Format::B8G8R8_USCALED
+        | Format::B8G8R8_SSCALED
+        | Format::B8G8R8_UINT
+        | Format::B8G8R8_SINT
+        | Format::B8G8R8_SRGB => (3, 1, 1),
+        Format::R8G8B8A8_UNORM
+        | Format::R8G8B8A8_SNORM
+        | Format::R8G8B8A8_USCALED
+        | Format::R8G8B8A8_SSCALED
+        | Format::R8G8B8A8_UINT
+        | Format::R8G8B8A8_SINT
+        | Format::R8G8B8A8_SRGB
+        | Format::B8G8R8A8_UNORM
+        | Format::B8G8R8A8_SNORM
+        | Format::B8G8R8A8_USCALED
+        | Format::B8G8R8A8_SSCALED
+        | Format::B8G8R8A8_UINT
+        | Format::B8G8R8A8_SINT
+        | Format::B8G8R8A8_SRGB
+        | Format::A8B8G8R8_UNORM_PACK32
+        | Format::A8B8G8R8_SNORM_PACK32
+        | Format::A8B8G8R8_USCALED_PACK32
+        | Format::A8B8G8R8_SSCALED_PACK32
+        | Format::A8B8G8R8_UINT_PACK32
+        | Format::A8B8G8R8_SINT_PACK32
+        | Format::A8B8G8R8_SRGB_PACK32
+        | Format::A2R10G10B10_UNORM_PACK32
+        | Format::A2R10G10B10_SNORM_PACK32
+        | Format::A2R10G10B10_USCALED_PACK32
+        | Format::A2R10G10B10_SSCALED_PACK32
+        | Format::A2R10G10B10_UINT_PACK32
+        | Format::A2R10G10B10_SINT_PACK32
+        | Format::A2B10G10R10_UNORM_PACK32
+        | Format::A2B10G10R10_SNORM_PACK32
+        | Format::A2B10G10R10_USCALED_PACK32
+        | Format::A2B10G10R10_SSCALED_PACK32
+        | Format::A2B10G10R10_UINT_PACK32
+        | Format::A2B10G10R10_SINT_PACK32
+        | Format::R16G16_UNORM
+        | Format::R16G16_SNORM
+        | Format::R16G16_USCALED
+        | Format::R16G16_SSCALED
+        | Format::R16G16_UINT
+        | Format::R16G16_SINT
+        | Format::R16G16_SFLOAT
+        | Format::R32_UINT
+        | Format::R32_SINT
+        | Format::R32_SFLOAT
+        | Format::B10G11R11_UFLOAT_PACK32
+        | Format::E5B9G9R9_UFLOAT_PACK32
+        | Format::X8_D24_UNORM_PACK32
+        | Format::D32_SFLOAT => (4, 1, 1),
+        Format::R16G16B16_UNORM
+        | Format::R16G16B16_SNORM
+        | Format::R16G16B16_USCALED
+        | Format::R16G16B16_SSCALED
+        | Format::R16G16B16_UINT
+        | Format::R16G16B16_SINT
+        | Format::R16G16B16_SFLOAT => (6, 1, 1),
+        Format::R16G16B16A16_UNORM
+        | Format::R16G16B16A16_SNORM
+        | Format::R16G16B16A16_USCALED
+        | Format::R16G16B16A16_SSCALED
+        | Format::R16G16B16A16_UINT
+        | Format::R16G16B16A16_SINT
+        | Format::R16G16B16A16_SFLOAT
+        | Format::R32G32_UINT
+        | Format::R32G32_SINT
+        | Format::R32G32_SFLOAT
+        | Format::R64_UINT
+        | Format::R64_SINT
+        | Format::R64_SFLOAT => (8, 1, 1),
+        Format::R32G32B32_UINT | Format::R32G32B32_SINT | Format::R32G32B32_SFLOAT => (12, 1, 1),
+        Format::R32G32B32A32_UINT
+        | Format::R32G32B32A32_SINT
+        | Format::R32G32B32A32_SFLOAT
+        | Format::R64G64_UINT
+        | Format::R64G64_SINT
+        | Format::R64G64_SFLOAT => (16, 1, 1),
+        Format::R64G64B64_UINT | Format::R64G64B64_SINT | Format::R64G64B64_SFLOAT => (24, 1, 1),
+        Format::R64G64B64A64_UINT | Format::R64G64B64A64_SINT | Format::R64G64B64A64_SFLOAT => {
+            (32, 1, 1)
+        }
+        Format::BC1_RGB_UNORM_BLOCK
+        | Format::BC1_RGB_SRGB_BLOCK
+        | Format::BC1_RGBA_UNORM_BLOCK
+        | Format::BC1_RGBA_SRGB_BLOCK
+        | Format::BC4_UNORM_BLOCK
+        | Format::BC4_SNORM_BLOCK
+        | Format::ETC2_R8G8B8_UNORM_BLOCK
+        | Format::ETC2_R8G8B8_SRGB_BLOCK
+        | Format::ETC2_R8G8B8A1_UNORM_BLOCK
+        | Format::ETC2_R8G8B8A1_SRGB_BLOCK
+        | Format::EAC_R11_UNORM_BLOCK
+        | Format::EAC_R11_SNORM_BLOCK => (8, 4, 4),
+        Format::BC2_UNORM_BLOCK
+        | Format::BC2_SRGB_BLOCK
+        | Format::BC3_UNORM_BLOCK
+        | Format::BC3_SRGB_BLOCK
+        | Format::BC5_UNORM_BLOCK
+        | Format::BC5_SNORM_BLOCK
+        | Format::BC6H_UFLOAT_BLOCK
+        | Format::BC6H_SFLOAT_BLOCK
+        | Format::BC7_UNORM_BLOCK
+        | Format::BC7_SRGB_BLOCK
+        | Format::ETC2_R8G8B8A8_UNORM_BLOCK
+        | Format::ETC2_R8G8B8A8_SRGB_BLOCK
+        | Format::EAC_R11G11_UNORM_BLOCK
+        | Format::EAC_R11G11_SNORM_BLOCK
+        | Format::ASTC_4X4_UNORM_BLOCK
+        | Format::ASTC_4X4_SRGB_BLOCK => (16, 4, 4),
+        Format::ASTC_5X4_UNORM_BLOCK | Format::ASTC_5X4_SRGB_BLOCK => (16, 5, 4),
+        Format::ASTC_5X5_UNORM_BLOCK | Format::ASTC_5X5_SRGB_BLOCK => (16, 5, 5),
+        Format::ASTC_6X5_UNORM_BLOCK | Format::ASTC_6X5_SRGB_BLOCK => (16, 6, 5),
+        Format::ASTC_6X6_UNORM_BLOCK | Format::ASTC_6X6_SRGB_BLOCK => (16, 6, 6),
+        Format::ASTC_8X5_UNORM_BLOCK | Format::ASTC_8X5_SRGB_BLOCK => (16, 8, 5),
+        Format::ASTC_8X6_UNORM_BLOCK | Format::ASTC_8X6_SRGB_BLOCK => (16, 8, 6),
+        Format::ASTC_8X8_UNORM_BLOCK | Format::ASTC_8X8_SRGB_BLOCK => (16, 8, 8),
+        Format::ASTC_10X5_UNORM_BLOCK | Format::ASTC_10X5_SRGB_BLOCK => (16, 10, 5),
+        Format::ASTC_10X6_UNORM_BLOCK | Format::ASTC_10X6_SRGB_BLOCK => (16, 10, 6),
+        Format::ASTC_10X8_UNORM_BLOCK | Format::ASTC_10X8_SRGB_BLOCK => (16, 10, 8),
+        Format::ASTC_10X10_UNORM_BLOCK | Format::ASTC_10X10_SRGB_BLOCK => (16, 10, 10),
+        Format::ASTC_12X10_UNORM_BLOCK | Format::ASTC_12X10_SRGB_BLOCK => (16, 12, 10),
+        Format::ASTC_12X12_UNORM_BLOCK | Format::ASTC_12X12_SRGB_BLOCK => (16, 12, 12),
+        _ => return None,
+    };
+    Some(block)
+}
+
+// the hardware encodes to sRGB on write for these formats, shaders should output linear colors
+pub fn is_srgb_format(format: ash::vk::Format) -> bool {
+    matches!(
+        format,
+        ash::vk::Format::R8_SRGB
+            | ash::vk::Format::R8G8_SRGB
+            | ash::vk::Format::R8G8B8_SRGB
+            | ash::vk::Format::B8G8R8_SRGB
+            | ash::vk::Format::R8G8B8A8_SRGB
+            | ash::vk::Format::B8G8R8A8_SRGB
+            | ash::vk::Format::A8B8G8R8_SRGB_PACK32
+            | ash::vk::Format::BC1_RGB_SRGB_BLOCK
+            | ash::vk::Format::BC1_RGBA_SRGB_BLOCK
+            | ash::vk::Format::BC2_SRGB_BLOCK
+            | ash::vk::Format::BC3_SRGB_BLOCK
+            | ash::vk::Format::BC7_SRGB_BLOCK
+            | ash::vk::Format::ETC2_R8G8B8_SRGB_BLOCK
+            | ash::vk::Format::ETC2_R8G8B8A1_SRGB_BLOCK
+            | ash::vk::Format::ETC2_R8G8B8A8_SRGB_BLOCK
+            | ash::vk::Format::ASTC_4X4_SRGB_BLOCK
+            | ash::vk::Format::ASTC_5X4_SRGB_BLOCK
+            | ash::vk::Format::ASTC_5X5_SRGB_BLOCK
+            | ash::vk::Format::ASTC_6X5_SRGB_BLOCK
+            | ash::vk::Format::ASTC_6X6_SRGB_BLOCK
+            | ash::vk::Format::ASTC_8X5_SRGB_BLOCK
+            | ash::vk::Format::ASTC_8X6_SRGB_BLOCK
+            | ash::vk::Format::ASTC_8X8_SRGB_BLOCK
+            | ash::vk::Format::ASTC_10X5_SRGB_BLOCK
+            | ash::vk::Format::ASTC_10X6_SRGB_BLOCK
+            | ash::vk::Format::ASTC_10X8_SRGB_BLOCK
+            | ash::vk::Format::ASTC_10X10_SRGB_BLOCK
+            | ash::vk::Format::ASTC_12X10_SRGB_BLOCK
+            | ash::vk::Format::ASTC_12X12_SRGB_BLOCK
+    )
+}
+
+pub(crate) fn format_plane_count(format: ash::vk::Format) -> u32 {
+    match format {
+        ash::vk::Format::G8_B8_R8_3PLANE_420_UNORM
+        | ash::vk::Format::G8_B8_R8_3PLANE_422_UNORM
+        | ash::vk::Format::G8_B8_R8_3PLANE_444_UNORM
+        | ash::vk::Format::G10X6_B10X6_R10X6_3PLANE_420_UNORM_3PACK16
+        | ash::vk::Format::G10X6_B10X6_R10X6_3PLANE_422_UNORM_3PACK16
+        | ash::vk::Format::G10X6_B10X6_R10X6_3PLANE_444_UNORM_3PACK16
+        | ash::vk::Format::G12X4_B12X4_R12X4_3PLANE_420_UNORM_3PACK16
+        | ash::vk::Format::G12X4_B12X4_R12X4_3PLANE_422_UNORM_3PACK16
+        | ash::vk::Format::G12X4_B12X4_R12X4_3PLANE_444_UNORM_3PACK16
+        | ash::vk::Format::G16_B16_R16_3PLANE_420_UNORM
+        | ash::vk::Format::G16_B16_R16_3PLANE_422_UNORM
+        | ash::vk::Format::G16_B16_R16_3PLANE_444_UNORM => 3,
+        ash::vk::Format::G8_B8R8_2PLANE_420_UNORM
+        | ash::vk::Format::G8_B8R8_2PLANE_422_UNORM
+        | ash::vk::Format::G8_B8R8_2PLANE_444_UNORM
+        | ash::vk::Format::G10X6_B10X6R10X6_2PLANE_420_UNORM_3PACK16
+        | ash::vk::Format::G10X6_B10X6R10X6_2PLANE_422_UNORM_3PACK16
+        | ash::vk::Format::G10X6_B10X6R10X6_2PLANE_444_UNORM_3PACK16
+        | ash::vk::Format::G12X4_B12X4R12X4_2PLANE_420_UNORM_3PACK16
+        | ash::vk::Format::G12X4_B12X4R12X4_2PLANE_422_UNORM_3PACK16
+        | ash::vk::Format::G12X4_B12X4R12X4_2PLANE_444_UNORM_3PACK16
+        | ash::vk::Format::G16_B16R16_2PLANE_420_UNORM
+        | ash::vk::Format::G16_B16R16_2PLANE_422_UNORM
+        | ash::vk::Format::G16_B16R16_2PLANE_444_UNORM => 2,
+        _ => 1,
+    }
+}
+
+pub(crate) fn requires_ycbcr_conversion(format: ash::vk::Format) -> bool {
+    format_plane_count(format) > 1
+        || matches!(
+            format,
+            ash::vk::Format::G8B8G8R8_422_UNORM
+                | ash::vk::Format::B8G8R8G8_422_UNORM
+                | ash::vk::Format::G10X6B10X6G10X6R10X6_422_UNORM_4PACK16
+                | ash::vk::Format::B10X6G10X6R10X6G10X6_422_UNORM_4PACK16
+                | ash::vk::Format::G12X4B12X4G12X4R12X4_422_UNORM_4PACK16
+                | ash::vk::Format::B12X4G12X4R12X4G12X4_422_UNORM_4PACK16
+                | ash::vk::Format::G16B16G16R16_422_UNORM
+                | ash::vk::Format::B16G16R16G16_422_UNORM
+        )
+}
+
+fn plane_aspect(plane: u32) -> ash::vk::ImageAspectFlags {
+    match plane {
+        0 => ash::vk::ImageAspectFlags::PLANE_0,
+        1 => ash::vk::ImageAspectFlags::PLANE_1,
+        _ => ash::vk::ImageAspectFlags::PLANE_2,
+    }
+}
+
 impl<const LEVEL: Level> CommandBuffer<LEVEL, { RECORDING }, { OUTSIDE }> {
     // DONE VUID-vkCmdCopyBufferToImage-commandBuffer-recording
     pub fn cmd_copy_buffer_to_image(
@@ -221,4 +919,197 @@ impl<const LEVEL: Level> CommandBuffer<LEVEL, { RECORDING }, { OUTSIDE }> {
             );
         }
     }
+
+    // DONE VUID-vkCmdCopyImageToBuffer-commandBuffer-recording
+    // DONE VUID-vkCmdCopyImageToBuffer-renderpass
+    pub fn cmd_copy_image_to_buffer(
+        &mut self,
+        src_image: Arc<Image>,
+        src_image_layout: ash::vk::ImageLayout,
+        dst_buffer: Arc<Buffer>,
+        regions: &[ash::vk::BufferImageCopy],
+    ) {
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ash_device.cmd_copy_image_to_buffer(
+                self.vk_command_buffer,
+                src_image.vk_image,
+                src_image_layout,
+                dst_buffer.ash_vk_buffer,
+                regions,
+            );
+        }
+        self.holding_resources
+            .read_images
+            .insert(src_image.vk_image.as_raw(), src_image);
+        self.holding_resources
+            .write_buffers
+            .insert(dst_buffer.ash_vk_buffer.as_raw(), dst_buffer);
+    }
+
+    // DONE VUID-vkCmdBlitImage-commandBuffer-recording
+    // DONE VUID-vkCmdBlitImage-renderpass
+    pub fn cmd_blit_image(
+        &mut self,
+        src_image: Arc<Image>,
+        src_image_layout: ash::vk::ImageLayout,
+        dst_image: Arc<Image>,
+        dst_image_layout: ash::vk::ImageLayout,
+        regions: &[ash::vk::ImageBlit],
+        filter: ash::vk::Filter,
+    ) {
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ash_device.cmd_blit_image(
+                self.vk_command_buffer,
+                src_image.vk_image,
+                src_image_layout,
+                dst_image.vk_image,
+                dst_image_layout,
+                regions,
+                filter,
+            );
+        }
+        self.holding_resources
+            .read_images
+            .insert(src_image.vk_image.as_raw(), src_image);
+        self.holding_resources
+            .write_images
+            .insert(dst_image.vk_image.as_raw(), dst_image);
+    }
+
+    // the next staging buffer of the current recording, at least `size` bytes large, a staging
+    // buffer left by an earlier recording is reused if it's large enough
+    fn next_staging_buffer(&mut self, size: ash::vk::DeviceSize) -> Result<&mut StagingBuffer> {
+        let index = self.holding_resources.staging_buffers_in_use;
+        let staging_buffers = &mut self.holding_resources.staging_buffers;
+        if index == staging_buffers.len() || staging_buffers[index].buffer.size < size {
+            let buffer = Buffer::builder(self.device.clone())
+                .size(size)
+                .usage(ash::vk::BufferUsageFlags::TRANSFER_SRC)
+                .sharing_mode(SharingMode::EXCLUSIVE)
+                .build()?;
+            let memory_requirements = buffer.get_buffer_memory_requirements();
+            // buffers always support a host visible and coherent memory type
+            let memory_type = self
+                .device
+                .physical_device
+                .memory_properties()
+                .find_memory_type(
+                    &memory_requirements,
+                    ash::vk::MemoryPropertyFlags::HOST_VISIBLE
+                        | ash::vk::MemoryPropertyFlags::HOST_COHERENT,
+                )
+                .unwrap();
+            let memory = DeviceMemory::builder(memory_type, self.device.clone())
+                .allocation_size(memory_requirements.size)
+                .build()?;
+            let buffer = buffer.bind_memory(&memory, 0)?;
+            let staging_buffer = StagingBuffer { buffer, memory };
+            if index == staging_buffers.len() {
+                staging_buffers.push(staging_buffer);
+            } else {
+                staging_buffers[index] = staging_buffer;
+            }
+        }
+        self.holding_resources.staging_buffers_in_use += 1;
+        Ok(&mut self.holding_resources.staging_buffers[index])
+    }
+
+    // Copy `data` into all layers of an image with a single mip level through a staging buffer,
+    // the image is transitioned to TRANSFER_DST_OPTIMAL before the copy and to `final_layout`
+    // after it. The staging buffer is kept by the command buffer and reused when it's recorded
+    // again
+    pub fn cmd_upload_image(
+        &mut self,
+        image: Arc<Image>,
+        data: &[u8],
+        final_layout: ash::vk::ImageLayout,
+        dst_stage: PipelineStageFlags,
+    ) -> Result<()> {
+        // other mip levels would be transitioned to `final_layout` without any content
+        if image.image_create_info.mip_levels != 1 {
+            panic!("cmd_upload_image only uploads images with a single mip level");
+        }
+        // MUST VUID-VkBufferImageCopy-aspectMask-00212
+        // combined depth/stencil and multi-planar formats need one copy per aspect or plane
+        let (block_size, block_width, block_height) =
+            texel_block_size(image.image_create_info.format).ok_or(Error::Validation {
+                vuid: "VUID-VkBufferImageCopy-aspectMask-00212",
+            })?;
+        let extent = image.image_create_info.extent;
+        let required_size = ((extent.width + block_width - 1) / block_width) as u64
+            * ((extent.height + block_height - 1) / block_height) as u64
+            * extent.depth as u64
+            * image.image_create_info.array_layers as u64
+            * block_size;
+        // MUST VUID-vkCmdCopyBufferToImage-pRegions-00171
+        if data.len() as u64 != required_size {
+            return Err(Error::Validation {
+                vuid: "VUID-vkCmdCopyBufferToImage-pRegions-00171",
+            });
+        }
+        let staging_buffer = self.next_staging_buffer(data.len() as _)?;
+        staging_buffer
+            .memory
+            .map_memory(0, data.len() as _, |mapped| {
+                mapped.copy_from_slice(data);
+            })?;
+        let staging_buffer = staging_buffer.buffer.clone();
+
+        let subresource_range = image.full_subresource_range();
+        let barrier = ImageMemoryBarrier::transition(
+            image.clone(),
+            ash::vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+        )
+        .dst_access_mask(ash::vk::AccessFlags::TRANSFER_WRITE)
+        .build();
+        self.cmd_pipeline_barrier(
+            &[PipelineStageFlags::TopOfPipe],
+            &[PipelineStageFlags::Transfer],
+            ash::vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            &[barrier],
+        );
+        let region = ash::vk::BufferImageCopy::builder()
+            .image_subresource(ash::vk::ImageSubresourceLayers {
+                aspect_mask: subresource_range.aspect_mask,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: image.image_create_info.array_layers,
+            })
+            .image_extent(image.image_create_info.extent)
+            .build();
+        self.cmd_copy_buffer_to_image(
+            staging_buffer,
+            image.clone(),
+            ash::vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            &[region],
+        );
+        let dst_access_mask = match final_layout {
+            ash::vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL => ash::vk::AccessFlags::SHADER_READ,
+            ash::vk::ImageLayout::TRANSFER_SRC_OPTIMAL => ash::vk::AccessFlags::TRANSFER_READ,
+            ash::vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL => {
+                ash::vk::AccessFlags::COLOR_ATTACHMENT_READ
+                    | ash::vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+            }
+            _ => ash::vk::AccessFlags::MEMORY_READ,
+        };
+        let barrier = ImageMemoryBarrier::transition(image, final_layout)
+            .src_access_mask(ash::vk::AccessFlags::TRANSFER_WRITE)
+            .dst_access_mask(dst_access_mask)
+            .build();
+        self.cmd_pipeline_barrier(
+            &[PipelineStageFlags::Transfer],
+            &[dst_stage],
+            ash::vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            &[barrier],
+        );
+        Ok(())
+    }
 }
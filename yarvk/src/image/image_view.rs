@@ -4,8 +4,9 @@ use crate::device_features::{
     PhysicalDeviceFragmentDensityMapFeaturesEXT,
 };
 use crate::image::image_subresource_range::ImageSubresourceRange;
-use crate::image::Image;
 use crate::image::State::Bound;
+use crate::image::{requires_ycbcr_conversion, Image};
+use crate::sampler::SamplerYcbcrConversion;
 use ash::vk::{ComponentMapping, Format};
 use std::sync::Arc;
 
@@ -73,6 +74,11 @@ impl ImageViewCreateFlags {
 pub struct ImageView {
     pub image: Arc<Image<{ Bound }>>,
     pub(crate) ash_vk_image_view: ash::vk::ImageView,
+    pub(crate) aspect_mask: ash::vk::ImageAspectFlags,
+    pub(crate) format: Format,
+    pub(crate) base_mip_level: u32,
+    pub(crate) layer_count: u32,
+    pub ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
 }
 
 impl ImageView {
@@ -101,7 +107,7 @@ pub struct ImageViewBuilder {
     flags: ash::vk::ImageViewCreateFlags,
     components: ComponentMapping,
     subresource_range: ImageSubresourceRange,
-    vk_sampler_ycbcr_conversion_info: Option<ash::vk::SamplerYcbcrConversionInfo>,
+    ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
 }
 
 impl ImageViewBuilder {
@@ -124,7 +130,7 @@ impl ImageViewBuilder {
                 a: ash::vk::ComponentSwizzle::IDENTITY,
             },
             subresource_range: ImageSubresourceRange::default(),
-            vk_sampler_ycbcr_conversion_info: None,
+            ycbcr_conversion: None,
         }
     }
     pub fn view_type(mut self, view_type: ImageViewType) -> Self {
@@ -147,14 +153,138 @@ impl ImageViewBuilder {
         self.subresource_range = subresource_range;
         self
     }
-    pub fn sampler_ycbcr_conversion_info(
-        mut self,
-        sampler_ycbcr_conversion_info: ash::vk::SamplerYcbcrConversionInfo,
-    ) -> Self {
-        self.vk_sampler_ycbcr_conversion_info = Some(sampler_ycbcr_conversion_info);
+    pub fn ycbcr_conversion(mut self, ycbcr_conversion: Arc<SamplerYcbcrConversion>) -> Self {
+        self.ycbcr_conversion = Some(ycbcr_conversion);
         self
     }
-    pub fn build(mut self) -> Result<Arc<ImageView>, ash::vk::Result> {
+    pub fn build(self) -> Result<Arc<ImageView>, ash::vk::Result> {
+        let image_create_info = &self.image.image_create_info;
+        let range = &self.subresource_range.0;
+        // MUST VUID-VkImageViewCreateInfo-subresourceRange-01478
+        if range.base_mip_level >= image_create_info.mip_levels {
+            panic!("VUID-VkImageViewCreateInfo-subresourceRange-01478");
+        }
+        // MUST VUID-VkImageViewCreateInfo-subresourceRange-01718
+        if range.level_count != ash::vk::REMAINING_MIP_LEVELS
+            && range.base_mip_level + range.level_count > image_create_info.mip_levels
+        {
+            panic!("VUID-VkImageViewCreateInfo-subresourceRange-01718");
+        }
+        if image_create_info.image_type != ash::vk::ImageType::TYPE_3D {
+            // MUST VUID-VkImageViewCreateInfo-image-01482
+            if range.base_array_layer >= image_create_info.array_layers {
+                panic!("VUID-VkImageViewCreateInfo-image-01482");
+            }
+            // MUST VUID-VkImageViewCreateInfo-subresourceRange-01483
+            if range.layer_count != ash::vk::REMAINING_ARRAY_LAYERS
+                && range.base_array_layer + range.layer_count > image_create_info.array_layers
+            {
+                panic!("VUID-VkImageViewCreateInfo-subresourceRange-01483");
+            }
+        }
+        // slices of a 3D image are viewed as array layers by 2D and 2D array views
+        let mut array_layers = image_create_info.array_layers;
+        if image_create_info.image_type == ash::vk::ImageType::TYPE_3D
+            && (self.view_type == ash::vk::ImageViewType::TYPE_2D
+                || self.view_type == ash::vk::ImageViewType::TYPE_2D_ARRAY)
+        {
+            // MUST VUID-VkImageViewCreateInfo-image-01005
+            if !image_create_info
+                .flags
+                .contains(ash::vk::ImageCreateFlags::TYPE_2D_ARRAY_COMPATIBLE)
+            {
+                panic!("VUID-VkImageViewCreateInfo-image-01005");
+            }
+            // MUST VUID-VkImageViewCreateInfo-image-04970
+            if range.level_count != 1 {
+                panic!("VUID-VkImageViewCreateInfo-image-04970");
+            }
+            array_layers = (image_create_info.extent.depth >> range.base_mip_level).max(1);
+            // MUST VUID-VkImageViewCreateInfo-image-02724
+            if range.base_array_layer >= array_layers {
+                panic!("VUID-VkImageViewCreateInfo-image-02724");
+            }
+            // MUST VUID-VkImageViewCreateInfo-subresourceRange-02725
+            if range.layer_count != ash::vk::REMAINING_ARRAY_LAYERS
+                && range.base_array_layer + range.layer_count > array_layers
+            {
+                panic!("VUID-VkImageViewCreateInfo-subresourceRange-02725");
+            }
+        }
+        let layer_count = if range.layer_count == ash::vk::REMAINING_ARRAY_LAYERS {
+            array_layers - range.base_array_layer
+        } else {
+            range.layer_count
+        };
+        match self.view_type {
+            ash::vk::ImageViewType::TYPE_1D
+            | ash::vk::ImageViewType::TYPE_2D
+            | ash::vk::ImageViewType::TYPE_3D => {
+                // MUST VUID-VkImageViewCreateInfo-imageViewType-04973
+                if layer_count != 1 {
+                    panic!("VUID-VkImageViewCreateInfo-imageViewType-04973");
+                }
+            }
+            ash::vk::ImageViewType::CUBE | ash::vk::ImageViewType::CUBE_ARRAY => {
+                // MUST VUID-VkImageViewCreateInfo-image-01003
+                if !image_create_info
+                    .flags
+                    .contains(ash::vk::ImageCreateFlags::CUBE_COMPATIBLE)
+                {
+                    panic!("VUID-VkImageViewCreateInfo-image-01003");
+                }
+                // MUST VUID-VkImageViewCreateInfo-viewType-02960
+                if self.view_type == ash::vk::ImageViewType::CUBE && layer_count != 6 {
+                    panic!("VUID-VkImageViewCreateInfo-viewType-02960");
+                }
+                // MUST VUID-VkImageViewCreateInfo-viewType-02961
+                if self.view_type == ash::vk::ImageViewType::CUBE_ARRAY
+                    && (layer_count == 0 || layer_count % 6 != 0)
+                {
+                    panic!("VUID-VkImageViewCreateInfo-viewType-02961");
+                }
+            }
+            _ => {}
+        }
+        if image_create_info
+            .flags
+            .contains(ash::vk::ImageCreateFlags::MUTABLE_FORMAT)
+        {
+            // TODO VUID-VkImageViewCreateInfo-image-01761
+            // MUST VUID-VkImageViewCreateInfo-pNext-01585
+            if !image_create_info.view_formats.is_empty()
+                && !image_create_info.view_formats.contains(&self.format)
+            {
+                panic!("VUID-VkImageViewCreateInfo-pNext-01585");
+            }
+        } else {
+            // MUST VUID-VkImageViewCreateInfo-image-01762
+            if self.format != image_create_info.format {
+                panic!("VUID-VkImageViewCreateInfo-image-01762");
+            }
+        }
+        if let Some(ycbcr_conversion) = &self.ycbcr_conversion {
+            // MUST VUID-VkImageViewCreateInfo-pNext-01970
+            let identity = ash::vk::ComponentSwizzle::IDENTITY;
+            if self.components.r != identity
+                || self.components.g != identity
+                || self.components.b != identity
+                || self.components.a != identity
+            {
+                panic!("VUID-VkImageViewCreateInfo-pNext-01970");
+            }
+            if ycbcr_conversion.format != self.format {
+                panic!("the format of the Y'CbCr conversion must match the view format");
+            }
+        } else if requires_ycbcr_conversion(self.format)
+            && range.aspect_mask.contains(ash::vk::ImageAspectFlags::COLOR)
+            && image_create_info
+                .usage
+                .contains(ash::vk::ImageUsageFlags::SAMPLED)
+        {
+            // MUST VUID-VkImageViewCreateInfo-format-06415
+            panic!("VUID-VkImageViewCreateInfo-format-06415");
+        }
         let mut create_view_info = ash::vk::ImageViewCreateInfo::builder();
         create_view_info = create_view_info.image(self.image.vk_image);
         create_view_info = create_view_info.view_type(self.view_type);
@@ -162,8 +292,12 @@ impl ImageViewBuilder {
         create_view_info = create_view_info.components(self.components);
         create_view_info = create_view_info.subresource_range(self.subresource_range.0);
         create_view_info = create_view_info.format(self.format);
-        if let Some(vk_sampler_ycbcr_conversion_info) = &mut self.vk_sampler_ycbcr_conversion_info {
-            create_view_info = create_view_info.push_next(vk_sampler_ycbcr_conversion_info);
+        let mut ycbcr_conversion_info;
+        if let Some(ycbcr_conversion) = &self.ycbcr_conversion {
+            ycbcr_conversion_info = ash::vk::SamplerYcbcrConversionInfo::builder()
+                .conversion(ycbcr_conversion.ash_vk_sampler_ycbcr_conversion)
+                .build();
+            create_view_info = create_view_info.push_next(&mut ycbcr_conversion_info);
         }
         let vk_image_view = unsafe {
             // Host Synchronization: none
@@ -174,6 +308,11 @@ impl ImageViewBuilder {
         Ok(Arc::new(ImageView {
             image: self.image,
             ash_vk_image_view: vk_image_view,
+            aspect_mask: self.subresource_range.aspect_mask,
+            format: self.format,
+            base_mip_level: range.base_mip_level,
+            layer_count,
+            ycbcr_conversion: self.ycbcr_conversion,
         }))
     }
 }
@@ -10,29 +10,12 @@ use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 use std::sync::Arc;
 
-// pub trait HasLayer {
-//     fn has_layer(&self, layer_name: &'static CStr) -> bool;
-// }
-//
-// impl HasLayer for Vec<ash::vk::LayerProperties> {
-//     fn has_layer(&self, layer_name: &'static CStr) -> bool {
-//         for layer in self {
-//             if layer
-//                 .layer_name
-//                 .starts_with(unsafe { std::mem::transmute(layer_name.to_bytes()) })
-//             {
-//                 return true;
-//             }
-//         }
-//         false
-//     }
-// }
-
 pub struct ApplicationInfo {
     application_name: CString,
     application_version: u32,
     engine_name: CString,
     engine_version: u32,
+    api_version: u32,
 }
 
 impl ApplicationInfo {
@@ -42,8 +25,7 @@ impl ApplicationInfo {
             .application_version(self.application_version)
             .engine_name(self.engine_name.as_c_str())
             .engine_version(self.engine_version)
-            // DONE VUID-VkApplicationInfo-apiVersion-04010
-            .api_version(ash::vk::API_VERSION_1_3)
+            .api_version(self.api_version)
     }
 }
 
@@ -65,6 +47,7 @@ impl Default for ApplicationInfoBuilder {
                 application_version: 0,
                 engine_name: unsafe { CString::new("yarvk_engine").unwrap_unchecked() },
                 engine_version: 0,
+                api_version: ash::vk::API_VERSION_1_3,
             },
         }
     }
@@ -94,6 +77,15 @@ impl ApplicationInfoBuilder {
         self.inner.engine_version = engine_version;
         self
     }
+    pub fn api_version(mut self, api_version: u32) -> Self {
+        // DONE VUID-VkApplicationInfo-apiVersion-04010
+        // yarvk calls 1.1 core functions (e.g. vkGetPhysicalDeviceFeatures2) unconditionally
+        if api_version < ash::vk::API_VERSION_1_1 {
+            panic!("yarvk requires api version 1.1 or later");
+        }
+        self.inner.api_version = api_version;
+        self
+    }
 
     pub fn build(self) -> ApplicationInfo {
         self.inner
@@ -106,7 +98,10 @@ pub struct InstanceBuilder {
     application_info: ApplicationInfo,
     enabled_layers: FxHashSet<&'static CStr>,
     enabled_extensions: FxHashSet<PhysicalInstanceExtensionType>,
+    raw_extensions: FxHashSet<CString>,
     debug_utils_messenger_create_info_exts: Vec<DebugUtilsMessengerCreateInfoEXT>,
+    enabled_validation_features: Vec<ash::vk::ValidationFeatureEnableEXT>,
+    disabled_validation_features: Vec<ash::vk::ValidationFeatureDisableEXT>,
 }
 
 impl InstanceBuilder {
@@ -135,6 +130,14 @@ impl InstanceBuilder {
         self.add_extension_inner(extension);
         self
     }
+    // Enable an extension by name, no `InstanceExtension` token can be retrieved for it and its
+    // dependencies are not enabled automatically.
+    pub fn add_extension_raw(mut self, extension: &CStr) -> Self {
+        self.raw_extensions.insert(extension.to_owned());
+        self
+    }
+    // chained into VkInstanceCreateInfo, so messages of vkCreateInstance and vkDestroyInstance
+    // are captured as well
     pub fn debug_utils_messenger_exts(
         mut self,
         exts: Vec<DebugUtilsMessengerCreateInfoEXT>,
@@ -142,6 +145,28 @@ impl InstanceBuilder {
         self.debug_utils_messenger_create_info_exts = exts;
         self
     }
+    // configure the validation layer, e.g. turn on GPU-assisted or synchronization validation
+    pub fn validation_features(
+        mut self,
+        enabled: &[ash::vk::ValidationFeatureEnableEXT],
+        disabled: &[ash::vk::ValidationFeatureDisableEXT],
+    ) -> Self {
+        // MUST VUID-VkValidationFeaturesEXT-pEnabledValidationFeatures-02967
+        if enabled.contains(&ash::vk::ValidationFeatureEnableEXT::GPU_ASSISTED_RESERVE_BINDING_SLOT)
+            && !enabled.contains(&ash::vk::ValidationFeatureEnableEXT::GPU_ASSISTED)
+        {
+            panic!("VUID-VkValidationFeaturesEXT-pEnabledValidationFeatures-02967");
+        }
+        // MUST VUID-VkValidationFeaturesEXT-pEnabledValidationFeatures-02968
+        if enabled.contains(&ash::vk::ValidationFeatureEnableEXT::GPU_ASSISTED)
+            && enabled.contains(&ash::vk::ValidationFeatureEnableEXT::DEBUG_PRINTF)
+        {
+            panic!("VUID-VkValidationFeaturesEXT-pEnabledValidationFeatures-02968");
+        }
+        self.enabled_validation_features = enabled.to_vec();
+        self.disabled_validation_features = disabled.to_vec();
+        self
+    }
     pub fn build(mut self) -> Result<Arc<Instance>, ash::vk::Result> {
         let supported_extensions = self.entry.enumerate_instance_extension_properties(None)?;
 
@@ -166,6 +191,12 @@ impl InstanceBuilder {
         {
             self.add_extension_inner(&PhysicalInstanceExtensionType::ExtDebugUtils);
         }
+        // SILENCE EXTENSION: VK_EXT_validation_features if validation features are configured
+        let has_validation_features = !self.enabled_validation_features.is_empty()
+            || !self.disabled_validation_features.is_empty();
+        if has_validation_features {
+            self.add_extension_inner(&PhysicalInstanceExtensionType::ExtValidationFeatures);
+        }
         // SILENCE VUID-VkInstanceCreateInfo-flags-06559
         if self
             .flags
@@ -179,11 +210,21 @@ impl InstanceBuilder {
             .iter()
             .map(|layer| layer.as_ptr())
             .collect();
-        let enabled_extension_names_raw: Vec<*const c_char> = self
+        let mut enabled_extension_names_raw: Vec<*const c_char> = self
             .enabled_extensions
             .iter()
             .map(|extension| extension.to_cstr().as_ptr())
             .collect();
+        // skip raw extensions which are also added as typed extensions
+        for raw_extension in &self.raw_extensions {
+            if !self
+                .enabled_extensions
+                .iter()
+                .any(|extension| extension.to_cstr() == raw_extension.as_c_str())
+            {
+                enabled_extension_names_raw.push(raw_extension.as_ptr());
+            }
+        }
 
         let ash_vk_application_info = self.application_info.ash_builder().build();
         let mut builder = ash::vk::InstanceCreateInfo::builder()
@@ -198,15 +239,26 @@ impl InstanceBuilder {
         for debug_utils in &mut ash_vk_debug_utils {
             builder = builder.push_next(debug_utils);
         }
+        let mut ash_vk_validation_features = ash::vk::ValidationFeaturesEXT::builder()
+            .enabled_validation_features(self.enabled_validation_features.as_slice())
+            .disabled_validation_features(self.disabled_validation_features.as_slice())
+            .build();
+        if has_validation_features {
+            builder = builder.push_next(&mut ash_vk_validation_features);
+        }
         let create_info = builder.build();
         // Host Synchronization: none
         let ash_instance = unsafe { self.entry.ash_entry.create_instance(&create_info, None)? };
+        let extension_fns = InstanceExtensionFns::load(&self.entry, &ash_instance);
         Ok(Arc::new(Instance {
             entry: self.entry,
             ash_instance,
+            extension_fns,
             _debug_utils_messenger_create_info_exts: self.debug_utils_messenger_create_info_exts,
             // enabled_layers: self.enabled_layers,
             enabled_extensions: self.enabled_extensions,
+            api_version: self.application_info.api_version,
+            destroy_on_drop: true,
         }))
     }
 }
@@ -214,9 +266,36 @@ impl InstanceBuilder {
 pub struct Instance {
     pub(crate) entry: Arc<Entry>,
     pub(crate) ash_instance: ash::Instance,
+    // keeps the callbacks alive until vkDestroyInstance
     _debug_utils_messenger_create_info_exts: Vec<DebugUtilsMessengerCreateInfoEXT>,
     // pub(crate) enabled_layers: FxHashSet<&'static CStr>,
     pub(crate) enabled_extensions: FxHashSet<PhysicalInstanceExtensionType>,
+    pub api_version: u32,
+    destroy_on_drop: bool,
+    extension_fns: InstanceExtensionFns,
+}
+
+// physical device level commands of device extensions, loaded once at instance creation, they
+// are resolved through the instance no matter which device enables the extension
+pub(crate) struct InstanceExtensionFns {
+    fragment_shading_rate: ash::vk::KhrFragmentShadingRateFn,
+    sample_locations: ash::vk::ExtSampleLocationsFn,
+}
+
+impl InstanceExtensionFns {
+    fn load(entry: &Entry, ash_instance: &ash::Instance) -> Self {
+        let mut load = |name: &CStr| unsafe {
+            std::mem::transmute(
+                entry
+                    .ash_entry
+                    .get_instance_proc_addr(ash_instance.handle(), name.as_ptr()),
+            )
+        };
+        InstanceExtensionFns {
+            fragment_shading_rate: ash::vk::KhrFragmentShadingRateFn::load(&mut load),
+            sample_locations: ash::vk::ExtSampleLocationsFn::load(&mut load),
+        }
+    }
 }
 
 impl PartialEq for Instance {
@@ -235,8 +314,45 @@ impl Instance {
             application_info: ApplicationInfoBuilder::default().build(),
             enabled_layers: Default::default(),
             enabled_extensions: Default::default(),
+            raw_extensions: Default::default(),
             debug_utils_messenger_create_info_exts: vec![],
+            enabled_validation_features: vec![],
+            disabled_validation_features: vec![],
+        }
+    }
+    /// Wrap an instance created outside of yarvk.
+    ///
+    /// # Safety
+    ///
+    /// `ash_instance` must be created from `entry` with `enabled_extensions` enabled and an
+    /// api version of `api_version`, which must be 1.1 or later. If `destroy_on_drop` is false,
+    /// the caller must destroy the instance after the returned [`Instance`] is dropped.
+    pub unsafe fn from_raw(
+        entry: Arc<Entry>,
+        ash_instance: ash::Instance,
+        enabled_extensions: FxHashSet<PhysicalInstanceExtensionType>,
+        api_version: u32,
+        destroy_on_drop: bool,
+    ) -> Arc<Instance> {
+        if api_version < ash::vk::API_VERSION_1_1 {
+            panic!("yarvk requires api version 1.1 or later");
         }
+        let extension_fns = InstanceExtensionFns::load(&entry, &ash_instance);
+        Arc::new(Instance {
+            entry,
+            ash_instance,
+            extension_fns,
+            _debug_utils_messenger_create_info_exts: vec![],
+            enabled_extensions,
+            api_version,
+            destroy_on_drop,
+        })
+    }
+    pub(crate) fn fragment_shading_rate_fn(&self) -> &ash::vk::KhrFragmentShadingRateFn {
+        &self.extension_fns.fragment_shading_rate
+    }
+    pub(crate) fn sample_locations_fn(&self) -> &ash::vk::ExtSampleLocationsFn {
+        &self.extension_fns.sample_locations
     }
     pub fn enumerate_physical_devices(
         self: &Arc<Self>,
@@ -249,22 +365,78 @@ impl Instance {
         }
         Ok(arc_devices)
     }
+    pub fn enumerate_physical_device_groups(
+        self: &Arc<Self>,
+    ) -> Result<Vec<PhysicalDeviceGroup>, ash::vk::Result> {
+        let groups = unsafe {
+            // Host Synchronization: none
+            let len = self.ash_instance.enumerate_physical_device_groups_len()?;
+            let mut groups = vec![ash::vk::PhysicalDeviceGroupProperties::default(); len];
+            self.ash_instance
+                .enumerate_physical_device_groups(groups.as_mut_slice())?;
+            groups
+        };
+        groups
+            .iter()
+            .map(|group| {
+                let physical_devices = group.physical_devices
+                    [..group.physical_device_count as usize]
+                    .iter()
+                    .map(|pdevice| PhysicalDevice::new(self.clone(), *pdevice))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(PhysicalDeviceGroup {
+                    physical_devices,
+                    subset_allocation: group.subset_allocation == ash::vk::TRUE,
+                })
+            })
+            .collect()
+    }
+    pub fn is_extension_enabled(&self, extension: PhysicalInstanceExtensionType) -> bool {
+        self.enabled_extensions.contains(&extension)
+    }
     pub fn get_extension<const EXT: PhysicalInstanceExtensionType>(
         self: &Arc<Self>,
-    ) -> Option<InstanceExtension<EXT>> {
+    ) -> Result<InstanceExtension<EXT>, ExtensionNotEnabled> {
         if self.enabled_extensions.contains(&EXT) {
-            Some(InstanceExtension::<EXT> {
+            Ok(InstanceExtension::<EXT> {
                 instance: self.clone(),
                 _p: Default::default(),
             })
         } else {
-            None
+            Err(ExtensionNotEnabled(EXT))
         }
     }
 }
 
+// Physical devices which can be used to create a single logical device, see
+// `DeviceBuilder::device_group`
+pub struct PhysicalDeviceGroup {
+    pub physical_devices: Vec<Arc<PhysicalDevice>>,
+    pub subset_allocation: bool,
+}
+
+// Returned by `Instance::get_extension` when the extension is not added by
+// `InstanceBuilder::add_extension`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExtensionNotEnabled(pub PhysicalInstanceExtensionType);
+
+impl std::fmt::Display for ExtensionNotEnabled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "instance extension {:?} is not enabled, add it with InstanceBuilder::add_extension",
+            self.0.to_cstr()
+        )
+    }
+}
+
+impl std::error::Error for ExtensionNotEnabled {}
+
 impl Drop for Instance {
     fn drop(&mut self) {
+        if !self.destroy_on_drop {
+            return;
+        }
         unsafe {
             // Done VUID-vkDestroyInstance-instance-00629
             // TODO VUID-vkDestroyInstance-instance-00630
@@ -6,16 +6,19 @@
 // use std::ffi::CStr;
 // use std::pin::Pin;
 // use std::sync::Arc;
+pub mod acceleration_structure;
 pub mod buffer;
 pub mod debug_utils_messenger;
 pub mod device;
 pub mod device_features;
 pub mod device_memory;
+pub mod error;
 pub mod extensions;
 pub mod fence;
 pub mod frame_buffer;
 pub mod image;
 pub mod instance;
+pub mod mesh_shader;
 pub mod physical_device;
 pub mod queue;
 pub mod render_pass;
@@ -30,12 +33,23 @@ pub mod window;
 pub mod barrier;
 pub mod descriptor_pool;
 pub mod pipeline;
+pub mod query_pool;
 pub mod sampler;
 pub mod semaphore;
 pub mod shader_module;
+pub mod sparse;
+#[cfg(feature = "image")]
+pub mod texture;
+#[cfg(feature = "spirv-reflect")]
+pub mod shader_reflection;
+pub mod transform_feedback;
 pub mod utils;
 
 pub use ash::util::read_spv;
+pub use error::{Error, Result};
+pub use ash::vk::AccelerationStructureBuildRangeInfoKHR;
+pub use ash::vk::AccelerationStructureInstanceKHR;
+pub use ash::vk::AccelerationStructureTypeKHR;
 pub use ash::vk::AccessFlags;
 pub use ash::vk::AttachmentDescriptionFlags;
 pub use ash::vk::AttachmentLoadOp;
@@ -43,7 +57,9 @@ pub use ash::vk::AttachmentStoreOp;
 pub use ash::vk::BlendOp;
 pub use ash::vk::BorderColor;
 pub use ash::vk::BufferImageCopy;
+pub use ash::vk::BuildAccelerationStructureFlagsKHR;
 pub use ash::vk::BufferUsageFlags;
+pub use ash::vk::ChromaLocation;
 pub use ash::vk::ClearColorValue;
 pub use ash::vk::ClearDepthStencilValue;
 pub use ash::vk::ClearValue;
@@ -63,9 +79,13 @@ pub use ash::vk::Extent2D;
 pub use ash::vk::Extent3D;
 pub use ash::vk::Filter;
 pub use ash::vk::Format;
+pub use ash::vk::FormatFeatureFlags;
+pub use ash::vk::FormatProperties;
 pub use ash::vk::FrontFace;
+pub use ash::vk::GeometryFlagsKHR;
 pub use ash::vk::ImageAspectFlags;
 pub use ash::vk::ImageCreateFlags;
+pub use ash::vk::ImageFormatProperties;
 pub use ash::vk::ImageLayout;
 pub use ash::vk::ImageSubresourceLayers;
 pub use ash::vk::ImageTiling;
@@ -132,12 +152,18 @@ pub use ash::vk::PhysicalDeviceVulkan12Properties;
 pub use ash::vk::PhysicalDeviceVulkan13Properties;
 pub use ash::vk::PipelineBindPoint;
 pub use ash::vk::PresentModeKHR;
+pub use ash::vk::QueryResultFlags;
+pub use ash::vk::QueryType;
 pub use ash::vk::QueueFlags;
+pub use ash::vk::QueueGlobalPriorityKHR;
 pub use ash::vk::Rect2D;
 pub use ash::vk::SampleCountFlags;
 pub use ash::vk::SamplerAddressMode;
 pub use ash::vk::SamplerCreateFlags;
 pub use ash::vk::SamplerMipmapMode;
+pub use ash::vk::SamplerReductionMode;
+pub use ash::vk::SamplerYcbcrModelConversion;
+pub use ash::vk::SamplerYcbcrRange;
 pub use ash::vk::StencilOp;
 pub use ash::vk::StencilOpState;
 pub use ash::vk::SubpassContents;
@@ -0,0 +1,102 @@
+use crate::buffer::Buffer;
+use crate::command::command_buffer::RenderPassScope::INSIDE;
+use crate::command::command_buffer::State::RECORDING;
+use crate::command::command_buffer::{CommandBuffer, Level};
+use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceMeshShaderFeaturesEXT::MeshShader;
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::ExtMeshShader;
+use ash::vk::Handle;
+use std::sync::Arc;
+
+impl<const LEVEL: Level> CommandBuffer<LEVEL, { RECORDING }, { INSIDE }> {
+    // DONE VUID-vkCmdDrawMeshTasksEXT-commandBuffer-recording
+    // DONE VUID-vkCmdDrawMeshTasksEXT-renderpass
+    // TODO VUID-vkCmdDrawMeshTasksEXT-MeshEXT-07087
+    // TODO VUID-vkCmdDrawMeshTasksEXT-TaskEXT-07322 and the other group count limits of
+    // VkPhysicalDeviceMeshShaderPropertiesEXT
+    pub fn cmd_draw_mesh_tasks(
+        &mut self,
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+        _extension: &DeviceExtension<{ ExtMeshShader }>,
+        _feature: &Feature<{ MeshShader.into() }>,
+    ) {
+        #[cfg(debug_assertions)]
+        self.check_bound_descriptor_sets(ash::vk::PipelineBindPoint::GRAPHICS);
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.mesh_shader_loader().cmd_draw_mesh_tasks(
+                self.vk_command_buffer,
+                group_count_x,
+                group_count_y,
+                group_count_z,
+            );
+        }
+    }
+
+    // `buffer` holds `draw_count` VkDrawMeshTasksIndirectCommandEXT starting at `offset`
+    // DONE VUID-vkCmdDrawMeshTasksIndirectEXT-commandBuffer-recording
+    // DONE VUID-vkCmdDrawMeshTasksIndirectEXT-renderpass
+    // TODO VUID-vkCmdDrawMeshTasksIndirectEXT-MeshEXT-07091
+    // TODO VUID-vkCmdDrawMeshTasksIndirectEXT-drawCount-02718
+    pub fn cmd_draw_mesh_tasks_indirect(
+        &mut self,
+        buffer: Arc<Buffer>,
+        offset: ash::vk::DeviceSize,
+        draw_count: u32,
+        stride: u32,
+        _extension: &DeviceExtension<{ ExtMeshShader }>,
+        _feature: &Feature<{ MeshShader.into() }>,
+    ) {
+        let command_size =
+            std::mem::size_of::<ash::vk::DrawMeshTasksIndirectCommandEXT>() as ash::vk::DeviceSize;
+        // MUST VUID-vkCmdDrawMeshTasksIndirectEXT-buffer-02709
+        if !buffer
+            .usage
+            .contains(ash::vk::BufferUsageFlags::INDIRECT_BUFFER)
+        {
+            panic!("VUID-vkCmdDrawMeshTasksIndirectEXT-buffer-02709");
+        }
+        // MUST VUID-vkCmdDrawMeshTasksIndirectEXT-offset-02710
+        if offset % 4 != 0 {
+            panic!("VUID-vkCmdDrawMeshTasksIndirectEXT-offset-02710");
+        }
+        if draw_count > 1 {
+            // MUST VUID-vkCmdDrawMeshTasksIndirectEXT-drawCount-07088
+            if stride % 4 != 0 || (stride as ash::vk::DeviceSize) < command_size {
+                panic!("VUID-vkCmdDrawMeshTasksIndirectEXT-drawCount-07088");
+            }
+            // MUST VUID-vkCmdDrawMeshTasksIndirectEXT-drawCount-07090
+            if stride as ash::vk::DeviceSize * (draw_count - 1) as ash::vk::DeviceSize
+                + offset
+                + command_size
+                > buffer.size
+            {
+                panic!("VUID-vkCmdDrawMeshTasksIndirectEXT-drawCount-07090");
+            }
+        }
+        // MUST VUID-vkCmdDrawMeshTasksIndirectEXT-drawCount-07089
+        if draw_count == 1 && offset + command_size > buffer.size {
+            panic!("VUID-vkCmdDrawMeshTasksIndirectEXT-drawCount-07089");
+        }
+        #[cfg(debug_assertions)]
+        self.check_bound_descriptor_sets(ash::vk::PipelineBindPoint::GRAPHICS);
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.mesh_shader_loader().cmd_draw_mesh_tasks_indirect(
+                self.vk_command_buffer,
+                buffer.ash_vk_buffer,
+                offset,
+                draw_count,
+                stride,
+            );
+        }
+        self.holding_resources
+            .read_buffers
+            .insert(buffer.ash_vk_buffer.as_raw(), buffer);
+    }
+}
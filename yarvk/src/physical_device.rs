@@ -1,13 +1,18 @@
-use crate::device_features::{PhysicalDeviceFeatures, SubPhysicalFeature, VkDeviceFeature};
+use crate::device_features::{
+    is_feature_supported, FeatureType, PhysicalDeviceFeatures, SubPhysicalFeature, VkDeviceFeature,
+};
 use crate::extensions::{
     PhysicalDeviceExtensionType,
 };
 use crate::instance::Instance;
 use crate::physical_device::queue_falmily_properties::QueueFamilyProperties;
+use parking_lot::Mutex;
 use rustc_hash::FxHashSet;
 use std::ffi::CStr;
 use std::sync::Arc;
 
+pub mod device_selector;
+pub mod format_properties;
 pub mod memory_properties;
 pub mod physical_device_properties;
 pub mod queue_falmily_properties;
@@ -28,6 +33,12 @@ pub struct PhysicalDevice {
     pub instance: Arc<Instance>,
     pub(crate) vk_physical_device: ash::vk::PhysicalDevice,
     pub(crate) supported_extensions: FxHashSet<PhysicalDeviceExtensionType>,
+    // properties never change for a physical device, queried once at creation
+    pub(crate) properties: ash::vk::PhysicalDeviceProperties,
+    // (sample_counts, fragment_size) pairs queried once by
+    // `get_physical_device_fragment_shading_rates`
+    pub(crate) fragment_shading_rates:
+        Mutex<Option<Vec<(ash::vk::SampleCountFlags, ash::vk::Extent2D)>>>,
 }
 
 impl PhysicalDevice {
@@ -44,10 +55,18 @@ impl PhysicalDevice {
                         unsafe { CStr::from_ptr(ext_props.extension_name.as_ptr()) }
                     )
                 }).collect();
+        // Host Synchronization: none
+        let properties = unsafe {
+            instance
+                .ash_instance
+                .get_physical_device_properties(vk_physical_device)
+        };
         Ok(Arc::new(Self {
             instance,
             vk_physical_device,
             supported_extensions,
+            properties,
+            fragment_shading_rates: Mutex::new(None),
         }))
     }
 
@@ -61,6 +80,15 @@ impl PhysicalDevice {
         }
     }
 
+    pub fn supported_features(&self) -> FxHashSet<PhysicalDeviceFeatures> {
+        self.get_physical_device_features()
+    }
+
+    pub fn supports_feature(&self, feature: FeatureType) -> bool {
+        is_feature_supported(self, &feature)
+    }
+
+    // SILENCE vkGetPhysicalDeviceFeatures2 is core since 1.1, see get_physical_device_properties2
     pub fn get_physical_device_features2<T: SubPhysicalFeature>(
         &self,
     ) -> FxHashSet<<T::VkStruct as VkDeviceFeature>::SubFeatureEnumTy> {
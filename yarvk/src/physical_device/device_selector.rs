@@ -0,0 +1,141 @@
+use crate::extensions::{
+    InstanceExtension, PhysicalDeviceExtensionType, PhysicalInstanceExtensionType,
+};
+use crate::physical_device::queue_falmily_properties::QueueFamilyProperties;
+use crate::physical_device::PhysicalDevice;
+use crate::surface::Surface;
+use raw_window_handle::HasRawWindowHandle;
+use std::sync::Arc;
+
+pub struct SelectedDevice {
+    pub physical_device: Arc<PhysicalDevice>,
+    pub queue_family: QueueFamilyProperties,
+    // Some if the selector requires surface support
+    pub surface: Option<Arc<Surface>>,
+}
+
+pub struct DeviceSelector<'a> {
+    queue_flags: ash::vk::QueueFlags,
+    required_extensions: Vec<PhysicalDeviceExtensionType>,
+    surface: Option<(
+        InstanceExtension<{ PhysicalInstanceExtensionType::KhrSurface }>,
+        &'a dyn HasRawWindowHandle,
+    )>,
+}
+
+impl<'a> DeviceSelector<'a> {
+    pub fn builder() -> DeviceSelectorBuilder<'a> {
+        DeviceSelectorBuilder {
+            queue_flags: ash::vk::QueueFlags::empty(),
+            required_extensions: vec![],
+            surface: None,
+        }
+    }
+
+    fn score(physical_device: &PhysicalDevice) -> u32 {
+        match physical_device.get_physical_device_properties().device_type {
+            ash::vk::PhysicalDeviceType::DISCRETE_GPU => 4,
+            ash::vk::PhysicalDeviceType::INTEGRATED_GPU => 3,
+            ash::vk::PhysicalDeviceType::VIRTUAL_GPU => 2,
+            ash::vk::PhysicalDeviceType::CPU => 1,
+            _ => 0,
+        }
+    }
+
+    // Find the first queue family of `physical_device` that meets all requirements
+    fn select_queue_family(
+        &self,
+        physical_device: &Arc<PhysicalDevice>,
+    ) -> Result<Option<SelectedDevice>, ash::vk::Result> {
+        if !self
+            .required_extensions
+            .iter()
+            .all(|ext| physical_device.supported_extensions.contains(ext))
+        {
+            return Ok(None);
+        }
+        for queue_family in physical_device.get_physical_device_queue_family_properties() {
+            if !queue_family.property.queue_flags.contains(self.queue_flags) {
+                continue;
+            }
+            let surface = match &self.surface {
+                Some((surface_ext, window_handle)) => {
+                    match Surface::get_physical_device_surface_support(
+                        surface_ext.clone(),
+                        *window_handle,
+                        &queue_family,
+                    )? {
+                        Some(surface) => Some(surface),
+                        None => continue,
+                    }
+                }
+                None => None,
+            };
+            return Ok(Some(SelectedDevice {
+                physical_device: physical_device.clone(),
+                queue_family,
+                surface,
+            }));
+        }
+        Ok(None)
+    }
+
+    // Return the suitable device with the highest score, discrete gpus are preferred
+    pub fn select(
+        &self,
+        physical_devices: &[Arc<PhysicalDevice>],
+    ) -> Result<Option<SelectedDevice>, ash::vk::Result> {
+        let mut best: Option<(u32, SelectedDevice)> = None;
+        for physical_device in physical_devices {
+            let score = Self::score(physical_device);
+            if let Some((best_score, _)) = &best {
+                if *best_score >= score {
+                    continue;
+                }
+            }
+            if let Some(selected) = self.select_queue_family(physical_device)? {
+                best = Some((score, selected));
+            }
+        }
+        Ok(best.map(|(_, selected)| selected))
+    }
+}
+
+pub struct DeviceSelectorBuilder<'a> {
+    queue_flags: ash::vk::QueueFlags,
+    required_extensions: Vec<PhysicalDeviceExtensionType>,
+    surface: Option<(
+        InstanceExtension<{ PhysicalInstanceExtensionType::KhrSurface }>,
+        &'a dyn HasRawWindowHandle,
+    )>,
+}
+
+impl<'a> DeviceSelectorBuilder<'a> {
+    pub fn queue_flags(mut self, queue_flags: ash::vk::QueueFlags) -> Self {
+        self.queue_flags = queue_flags;
+        self
+    }
+
+    pub fn add_required_extension(mut self, extension: PhysicalDeviceExtensionType) -> Self {
+        self.required_extensions.push(extension);
+        self
+    }
+
+    // The selected queue family must support presenting to `window_handle`
+    pub fn surface_support(
+        mut self,
+        surface_ext: InstanceExtension<{ PhysicalInstanceExtensionType::KhrSurface }>,
+        window_handle: &'a dyn HasRawWindowHandle,
+    ) -> Self {
+        self.surface = Some((surface_ext, window_handle));
+        self
+    }
+
+    pub fn build(self) -> DeviceSelector<'a> {
+        DeviceSelector {
+            queue_flags: self.queue_flags,
+            required_extensions: self.required_extensions,
+            surface: self.surface,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::physical_device::PhysicalDevice;
+
+impl PhysicalDevice {
+    pub fn get_format_properties(&self, format: ash::vk::Format) -> ash::vk::FormatProperties {
+        unsafe {
+            // Host Synchronization: none
+            self.instance
+                .ash_instance
+                .get_physical_device_format_properties(self.vk_physical_device, format)
+        }
+    }
+
+    // Returns None if the combination of parameters is not supported
+    pub fn get_image_format_properties(
+        &self,
+        format: ash::vk::Format,
+        image_type: ash::vk::ImageType,
+        tiling: ash::vk::ImageTiling,
+        usage: ash::vk::ImageUsageFlags,
+        flags: ash::vk::ImageCreateFlags,
+    ) -> Result<Option<ash::vk::ImageFormatProperties>, ash::vk::Result> {
+        // MUST VUID-vkGetPhysicalDeviceImageFormatProperties-tiling-02248
+        if tiling == ash::vk::ImageTiling::DRM_FORMAT_MODIFIER_EXT {
+            panic!("VUID-vkGetPhysicalDeviceImageFormatProperties-tiling-02248");
+        }
+        // MUST VUID-vkGetPhysicalDeviceImageFormatProperties-usage-requiredbitmask
+        if usage.is_empty() {
+            panic!("VUID-vkGetPhysicalDeviceImageFormatProperties-usage-requiredbitmask");
+        }
+        let result = unsafe {
+            // Host Synchronization: none
+            self.instance
+                .ash_instance
+                .get_physical_device_image_format_properties(
+                    self.vk_physical_device,
+                    format,
+                    image_type,
+                    tiling,
+                    usage,
+                    flags,
+                )
+        };
+        match result {
+            Ok(properties) => Ok(Some(properties)),
+            Err(ash::vk::Result::ERROR_FORMAT_NOT_SUPPORTED) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::ExtMemoryBudget;
 use crate::physical_device::PhysicalDevice;
 use std::sync::Arc;
 
@@ -14,6 +16,38 @@ pub struct PhysicalDeviceMemoryProperties {
     pub memory_types: Vec<MemoryType>,
 }
 
+impl PhysicalDeviceMemoryProperties {
+    pub fn find_memory_type(
+        &self,
+        memory_requirements: &ash::vk::MemoryRequirements,
+        flags: ash::vk::MemoryPropertyFlags,
+    ) -> Option<MemoryType> {
+        self.memory_types
+            .iter()
+            .find(|memory_type| {
+                (1 << memory_type.index) & memory_requirements.memory_type_bits != 0
+                    && memory_type.property_flags.contains(flags)
+            })
+            .cloned()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryHeapBudget {
+    pub heap: ash::vk::MemoryHeap,
+    // the bytes the process can allocate from the heap before allocations may fail or cause
+    // performance degradation
+    pub budget: ash::vk::DeviceSize,
+    // the bytes currently used by the process
+    pub usage: ash::vk::DeviceSize,
+}
+
+// a snapshot of the heap usage, query again after allocating or freeing memory
+#[derive(Clone, Debug)]
+pub struct MemoryBudget {
+    pub heaps: Vec<MemoryHeapBudget>,
+}
+
 impl PhysicalDevice {
     pub fn memory_properties(self: &Arc<Self>) -> PhysicalDeviceMemoryProperties {
         let vk_physical_device_memory_properties = unsafe {
@@ -58,4 +92,29 @@ impl PhysicalDevice {
         };
         t
     }
+
+    pub fn memory_budget(&self, _extension: &DeviceExtension<{ ExtMemoryBudget }>) -> MemoryBudget {
+        let mut budget_properties = ash::vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+        let mut prop2 = ash::vk::PhysicalDeviceMemoryProperties2::builder()
+            .push_next(&mut budget_properties)
+            .build();
+        unsafe {
+            // Host Synchronization: none
+            self.instance
+                .ash_instance
+                .get_physical_device_memory_properties2(self.vk_physical_device, &mut prop2);
+        };
+        let memory_properties = prop2.memory_properties;
+        MemoryBudget {
+            heaps: memory_properties.memory_heaps[..memory_properties.memory_heap_count as usize]
+                .iter()
+                .enumerate()
+                .map(|(index, heap)| MemoryHeapBudget {
+                    heap: *heap,
+                    budget: budget_properties.heap_budget[index],
+                    usage: budget_properties.heap_usage[index],
+                })
+                .collect(),
+        }
+    }
 }
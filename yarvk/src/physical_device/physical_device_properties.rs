@@ -4,14 +4,11 @@ use ash::vk::ExtendsPhysicalDeviceProperties2;
 
 impl PhysicalDevice {
     pub fn get_physical_device_properties(&self) -> ash::vk::PhysicalDeviceProperties {
-        unsafe {
-            // Host Synchronization: none
-            self.instance
-                .ash_instance
-                .get_physical_device_properties(self.vk_physical_device)
-        }
+        self.properties
     }
 
+    // SILENCE VK_KHR_get_physical_device_properties2 is promoted to 1.1 and the instance is
+    // always created with API 1.1 or later, so the core entry point needs no extension token.
     pub fn get_physical_device_properties2<T: ExtendsPhysicalDeviceProperties2 + Default>(
         &self,
     ) -> T {
@@ -47,5 +47,27 @@ impl PhysicalDevice {
         })
         .collect()
     }
+    // Find a family for async transfers, families without graphics are preferred, dedicated
+    // transfer families first, and a graphics family is returned if there is no other choice.
+    pub fn find_transfer_queue_family(self: &Arc<Self>) -> Option<QueueFamilyProperties> {
+        self.get_physical_device_queue_family_properties()
+            .into_iter()
+            .filter_map(|queue_family| {
+                let flags = queue_family.property.queue_flags;
+                // graphics and compute queues support transfer operations implicitly
+                let score = if flags.contains(ash::vk::QueueFlags::GRAPHICS) {
+                    1
+                } else if flags.contains(ash::vk::QueueFlags::COMPUTE) {
+                    2
+                } else if flags.contains(ash::vk::QueueFlags::TRANSFER) {
+                    3
+                } else {
+                    return None;
+                };
+                Some((score, queue_family))
+            })
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, queue_family)| queue_family)
+    }
     // TODO get_physical_device_queue_family_properties2
 }
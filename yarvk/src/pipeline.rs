@@ -2,10 +2,13 @@ use crate::command::command_buffer::State::RECORDING;
 use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
 use crate::descriptor_pool::DescriptorSetLayout;
 use crate::device::Device;
+use crate::error::Error;
 use crate::pipeline::color_blend_state::PipelineColorBlendStateCreateInfo;
 use crate::pipeline::depth_stencil_state::PipelineDepthStencilStateCreateInfo;
+use crate::pipeline::dynamic_state::DynamicState;
 use crate::pipeline::input_assembly_state::PipelineInputAssemblyStateCreateInfo;
 use crate::pipeline::multisample_state::PipelineMultisampleStateCreateInfo;
+use crate::pipeline::pipeline_cache::PipelineCache;
 
 use crate::pipeline::rasterization_state::PipelineRasterizationStateCreateInfo;
 use crate::pipeline::shader_stage::{PipelineShaderStageCreateInfo};
@@ -17,16 +20,21 @@ use crate::render_pass::subpass::SubpassIndex;
 use crate::render_pass::RenderPass;
 
 use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::hash_map::Entry;
 use std::sync::Arc;
 use crate::shader_module::ShaderModule;
 
 pub mod color_blend_state;
 pub mod depth_stencil_state;
+pub mod dynamic_state;
+pub mod fragment_shading_rate;
 pub mod input_assembly_state;
 pub mod multisample_state;
+pub mod pipeline_cache;
 pub mod pipeline_stage_flags;
 pub mod primitive_topology;
 pub mod rasterization_state;
+pub mod ray_tracing_pipeline;
 pub mod shader_stage;
 pub mod vertex_input_state;
 pub mod viewport_state;
@@ -34,6 +42,7 @@ pub mod viewport_state;
 pub struct PipelineLayout {
     pub device: Arc<Device>,
     pub(crate) ash_vk_pipeline_layout: ash::vk::PipelineLayout,
+    pub set_layouts: Vec<Arc<DescriptorSetLayout>>,
 }
 
 impl PipelineLayout {
@@ -95,6 +104,7 @@ impl PipelineLayoutBuilder {
             Ok(Arc::new(PipelineLayout {
                 device: self.device,
                 ash_vk_pipeline_layout,
+                set_layouts: self.set_layouts,
             }))
         }
     }
@@ -134,6 +144,7 @@ pub struct Pipeline {
     pub device: Arc<Device>,
     _render_pass_holder: Option<Arc<RenderPass>>,
     _shader_modules_holder: Vec<Arc<ShaderModule>>,
+    pub layout: Arc<PipelineLayout>,
     ash_vk_pipeline: ash::vk::Pipeline,
 }
 
@@ -154,6 +165,8 @@ impl Pipeline {
             layout,
             dynamic_states: Default::default(),
             render_pass: None,
+            pipeline_cache: None,
+            duplicate_stage: false,
         }
     }
 }
@@ -173,6 +186,8 @@ pub struct PipelineBuilder<'a> {
     layout: Arc<PipelineLayout>,
     dynamic_states: FxHashSet<ash::vk::DynamicState>,
     render_pass: Option<(Arc<RenderPass>, SubpassIndex)>,
+    pipeline_cache: Option<Arc<PipelineCache>>,
+    duplicate_stage: bool,
 }
 
 impl<'a> PipelineBuilder<'a> {
@@ -182,8 +197,13 @@ impl<'a> PipelineBuilder<'a> {
     }
     pub fn add_stage(mut self, stage: PipelineShaderStageCreateInfo<'a>) -> Self {
         // MUST VUID-VkGraphicsPipelineCreateInfo-stage-00726
-        if let Some(_) = self.stages.insert(stage.stage, stage) {
-            panic!("VUID-VkGraphicsPipelineCreateInfo-stage-00726")
+        // reported by `build`, the stage added first is kept, a task stage and a mesh stage are
+        // different stages so they are added together
+        match self.stages.entry(stage.stage) {
+            Entry::Occupied(_) => self.duplicate_stage = true,
+            Entry::Vacant(entry) => {
+                entry.insert(stage);
+            }
         }
         self
     }
@@ -240,11 +260,20 @@ impl<'a> PipelineBuilder<'a> {
         self.color_blend_state = color_blend_state;
         self
     }
+    pub fn add_dynamic_state(mut self, dynamic_state: DynamicState) -> Self {
+        // DONE VUID-VkPipelineDynamicStateCreateInfo-pDynamicStates-01442
+        self.dynamic_states.insert(dynamic_state.to_ash());
+        self
+    }
     pub fn render_pass(mut self, render_pass: Arc<RenderPass>, subpass: SubpassIndex) -> Self {
         // DONE VUID-VkGraphicsPipelineCreateInfo-renderPass-06046
         self.render_pass = Some((render_pass, subpass));
         self
     }
+    pub fn pipeline_cache(mut self, pipeline_cache: Arc<PipelineCache>) -> Self {
+        self.pipeline_cache = Some(pipeline_cache);
+        self
+    }
     // All vendors suggest to avoid using pipeline derivatives, and the API design is a little
     // tricky (need build a tree to avoid reference loop. So I just leave it unimplemented
     // pub fn base_pipeline_handle(mut self, base_pipeline_handle: Arc<Pipeline>) -> Self {
@@ -257,79 +286,236 @@ impl<'a> PipelineBuilder<'a> {
     //     self.flags |= ash::vk::PipelineCreateFlags::ALLOW_DERIVATIVES;
     //     self
     // }
-    pub fn build(mut self) -> Result<Pipeline, ash::vk::Result> {
-        // stages
-        let mut shader_modules_holder = Vec::with_capacity(self.stages.len());
-        let mut ash_vk_stages = Vec::with_capacity(self.stages.len());
-        for (_, info) in self.stages {
-            ash_vk_stages.push(info.ash_builder());
-            shader_modules_holder.push(info.module);
+    pub fn build(self) -> crate::error::Result<Pipeline> {
+        let device = self.device.clone();
+        let mut pipelines = Pipeline::build_many(&device, vec![self])?;
+        Ok(pipelines.pop().unwrap())
+    }
+    // every valid usage of the builder's input is reported as `Error::Validation`, so pipelines
+    // built from data-driven config never panic
+    fn check(&self) -> crate::error::Result<()> {
+        if self.duplicate_stage {
+            return Err(Error::Validation {
+                vuid: "VUID-VkGraphicsPipelineCreateInfo-stage-00726",
+            });
+        }
+        // MUST VUID-VkGraphicsPipelineCreateInfo-pStages-02095
+        let primitive_shading = self.stages.keys().any(|stage| {
+            stage.intersects(
+                ash::vk::ShaderStageFlags::VERTEX
+                    | ash::vk::ShaderStageFlags::TESSELLATION_CONTROL
+                    | ash::vk::ShaderStageFlags::TESSELLATION_EVALUATION
+                    | ash::vk::ShaderStageFlags::GEOMETRY,
+            )
+        });
+        if primitive_shading && self.is_mesh_shading() {
+            return Err(Error::Validation {
+                vuid: "VUID-VkGraphicsPipelineCreateInfo-pStages-02095",
+            });
+        }
+        // MUST VUID-VkGraphicsPipelineCreateInfo-stage-02096
+        if !self.stages.contains_key(&ash::vk::ShaderStageFlags::VERTEX)
+            && !self.stages.contains_key(&ash::vk::ShaderStageFlags::MESH_EXT)
+        {
+            return Err(Error::Validation {
+                vuid: "VUID-VkGraphicsPipelineCreateInfo-stage-02096",
+            });
+        }
+        self.rasterization_state
+            .check_line_width(&self.device, &self.dynamic_states)?;
+        self.multisample_state
+            .check_sample_locations(&self.dynamic_states)?;
+        if let Some((render_pass, subpass_index)) = &self.render_pass {
+            // MUST VUID-VkGraphicsPipelineCreateInfo-renderPass-06042
+            let color_attachment_count =
+                render_pass.color_attachment_counts[subpass_index.0 as usize] as usize;
+            if color_attachment_count != 0
+                && !self.rasterization_state.rasterizer_discard_enable
+                && self.color_blend_state.attachment_count() != color_attachment_count
+            {
+                return Err(Error::Validation {
+                    vuid: "VUID-VkGraphicsPipelineCreateInfo-renderPass-06042",
+                });
+            }
+        }
+        Ok(())
+    }
+    fn is_mesh_shading(&self) -> bool {
+        self.stages.keys().any(|stage| {
+            stage.intersects(
+                ash::vk::ShaderStageFlags::TASK_EXT | ash::vk::ShaderStageFlags::MESH_EXT,
+            )
+        })
+    }
+    // the vertex input state caches its descriptions in itself, so `self` is mutable
+    fn create_infos(&mut self) -> Box<PipelineCreateInfos> {
+        let mut infos = Box::new(PipelineCreateInfos {
+            stages: self
+                .stages
+                .values()
+                .map(|info| info.ash_builder())
+                .collect(),
+            vertex_input_state: self
+                .pipeline_vertex_input_state_create_info
+                .ash_builder()
+                .build(),
+            input_assembly_state: self.input_assembly_state.ash_builder().build(),
+            tessellation_state: self.tessellation_state.ash_builder().build(),
+            viewport_state: self.viewport_state.ash_builder().build(),
+            depth_clip_state: self.rasterization_state.ash_depth_clip_state(),
+            line_state: self.rasterization_state.ash_line_state(&self.device),
+            conservative_state: self.rasterization_state.ash_conservative_state(),
+            rasterization_state: Default::default(),
+            sample_locations_state: self.multisample_state.ash_sample_locations_state(),
+            multisample_state: Default::default(),
+            depth_stencil_state: self.depth_stencil_state.ash_builder().build(),
+            color_blend_state: self.color_blend_state.ash_builder().build(),
+            dynamic_states: self.dynamic_states.iter().cloned().collect(),
+            dynamic_state: Default::default(),
+        });
+        let mut rasterization_state = self.rasterization_state.ash_builder();
+        if let Some(depth_clip_state) = &mut infos.depth_clip_state {
+            rasterization_state = rasterization_state.push_next(depth_clip_state);
+        }
+        if let Some(line_state) = &mut infos.line_state {
+            rasterization_state = rasterization_state.push_next(line_state);
         }
-        // vertex input
-        let ash_vk_vertex_input_state = self
-            .pipeline_vertex_input_state_create_info
-            .ash_builder()
+        if let Some(conservative_state) = &mut infos.conservative_state {
+            rasterization_state = rasterization_state.push_next(conservative_state);
+        }
+        infos.rasterization_state = rasterization_state.build();
+        let mut multisample_state = self.multisample_state.ash_builder();
+        if let Some(sample_locations_state) = &mut infos.sample_locations_state {
+            multisample_state = multisample_state.push_next(sample_locations_state);
+        }
+        infos.multisample_state = multisample_state.build();
+        infos.dynamic_state = ash::vk::PipelineDynamicStateCreateInfo::builder()
+            .dynamic_states(infos.dynamic_states.as_slice())
             .build();
-        // input assembly
-        let ash_vk_input_assembly_state = self.input_assembly_state.ash_builder().build();
-        // tessellation
-        let ash_vk_tessellation_state = self.tessellation_state.ash_builder().build();
-        // view port
-        let ash_vk_viewport_state = self.viewport_state.ash_builder().build();
-        // rasterization
-        let ash_vk_rasterization_state = self.rasterization_state.ash_builder().build();
-        // multisample
-        let ash_vk_multisample_state = self.multisample_state.ash_builder().build();
-        // depth stencil
-        let ash_vk_depth_stencil_state = self.depth_stencil_state.ash_builder().build();
-        // color blend
-        let ash_vk_color_blend_state = self.color_blend_state.ash_builder().build();
-        // dynamic states
-        let ash_vk_dynamic_states = self.dynamic_states.into_iter().collect::<Vec<_>>();
-        let ash_vk_pipeline_dynamic_state_create_info =
-            ash::vk::PipelineDynamicStateCreateInfo::builder()
-                .dynamic_states(ash_vk_dynamic_states.as_slice())
-                .build();
+        infos
+    }
+    fn create_info(&self, infos: &PipelineCreateInfos) -> ash::vk::GraphicsPipelineCreateInfo {
         let mut create_info_builder = ash::vk::GraphicsPipelineCreateInfo::builder()
             .flags(self.flags)
-            .stages(ash_vk_stages.as_slice())
-            .vertex_input_state(&ash_vk_vertex_input_state)
-            .input_assembly_state(&ash_vk_input_assembly_state)
-            .tessellation_state(&ash_vk_tessellation_state)
-            .viewport_state(&ash_vk_viewport_state)
-            .rasterization_state(&ash_vk_rasterization_state)
-            .multisample_state(&ash_vk_multisample_state)
-            .depth_stencil_state(&ash_vk_depth_stencil_state)
-            .color_blend_state(&ash_vk_color_blend_state)
+            .stages(infos.stages.as_slice())
+            .tessellation_state(&infos.tessellation_state)
+            .viewport_state(&infos.viewport_state)
+            .rasterization_state(&infos.rasterization_state)
+            .multisample_state(&infos.multisample_state)
+            .depth_stencil_state(&infos.depth_stencil_state)
+            .color_blend_state(&infos.color_blend_state)
             .layout(self.layout.ash_vk_pipeline_layout)
-            .dynamic_state(&ash_vk_pipeline_dynamic_state_create_info);
-        let mut render_pass_holder = None;
-        if let Some((render_pass, subpass_index)) = self.render_pass {
+            .dynamic_state(&infos.dynamic_state);
+        // a mesh pipeline generates its primitives in the mesh stage, without vertex input and
+        // input assembly
+        if !self.is_mesh_shading() {
+            create_info_builder = create_info_builder
+                .vertex_input_state(&infos.vertex_input_state)
+                .input_assembly_state(&infos.input_assembly_state);
+        }
+        if let Some((render_pass, subpass_index)) = &self.render_pass {
             create_info_builder = create_info_builder
                 .render_pass(render_pass.ash_vk_renderpass)
                 .subpass(subpass_index.0);
-            render_pass_holder = Some(render_pass);
         }
-        let create_info = create_info_builder.build();
-        // TODO pipeline caching
-        let ash_vk_pipeline = unsafe {
-            match self.device.ash_device.create_graphics_pipelines(
-                ash::vk::PipelineCache::null(),
-                &[create_info],
+        create_info_builder.build()
+    }
+    fn into_pipeline(self, ash_vk_pipeline: ash::vk::Pipeline) -> Pipeline {
+        Pipeline {
+            device: self.device,
+            _render_pass_holder: self.render_pass.map(|(render_pass, _)| render_pass),
+            _shader_modules_holder: self.stages.into_values().map(|info| info.module).collect(),
+            layout: self.layout,
+            ash_vk_pipeline,
+        }
+    }
+}
+
+// the structures pointed to by a VkGraphicsPipelineCreateInfo, boxed so the pointers between
+// them stay valid while the create infos of several pipelines are alive
+struct PipelineCreateInfos {
+    stages: Vec<ash::vk::PipelineShaderStageCreateInfo>,
+    vertex_input_state: ash::vk::PipelineVertexInputStateCreateInfo,
+    input_assembly_state: ash::vk::PipelineInputAssemblyStateCreateInfo,
+    tessellation_state: ash::vk::PipelineTessellationStateCreateInfo,
+    viewport_state: ash::vk::PipelineViewportStateCreateInfo,
+    depth_clip_state: Option<ash::vk::PipelineRasterizationDepthClipStateCreateInfoEXT>,
+    line_state: Option<ash::vk::PipelineRasterizationLineStateCreateInfoEXT>,
+    conservative_state: Option<ash::vk::PipelineRasterizationConservativeStateCreateInfoEXT>,
+    rasterization_state: ash::vk::PipelineRasterizationStateCreateInfo,
+    sample_locations_state: Option<ash::vk::PipelineSampleLocationsStateCreateInfoEXT>,
+    multisample_state: ash::vk::PipelineMultisampleStateCreateInfo,
+    depth_stencil_state: ash::vk::PipelineDepthStencilStateCreateInfo,
+    color_blend_state: ash::vk::PipelineColorBlendStateCreateInfo,
+    dynamic_states: Vec<ash::vk::DynamicState>,
+    dynamic_state: ash::vk::PipelineDynamicStateCreateInfo,
+}
+
+impl Pipeline {
+    // Create all pipelines in one vkCreateGraphicsPipelines call, which drivers may compile in
+    // parallel, all `builders` must use the same pipeline cache (or none). PipelineBuilder and
+    // PipelineCache are Send + Sync, so builds can also be spread across threads
+    pub fn build_many(
+        device: &Arc<Device>,
+        mut builders: Vec<PipelineBuilder>,
+    ) -> crate::error::Result<Vec<Pipeline>> {
+        if builders.is_empty() {
+            return Ok(Vec::new());
+        }
+        let pipeline_cache = builders[0].pipeline_cache.clone();
+        for builder in &builders {
+            // DONE VUID-vkCreateGraphicsPipelines-device-parameter
+            if builder.device != *device {
+                panic!("pipeline is not built for this device");
+            }
+            let same_cache = match (&builder.pipeline_cache, &pipeline_cache) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            };
+            if !same_cache {
+                panic!("all pipelines of build_many must use the same pipeline cache");
+            }
+            builder.check()?;
+        }
+        let infos = builders
+            .iter_mut()
+            .map(|builder| builder.create_infos())
+            .collect::<Vec<_>>();
+        let create_infos = builders
+            .iter()
+            .zip(&infos)
+            .map(|(builder, infos)| builder.create_info(infos))
+            .collect::<Vec<_>>();
+        let ash_vk_pipeline_cache = pipeline_cache
+            .as_ref()
+            .map_or(ash::vk::PipelineCache::null(), |pipeline_cache| {
+                pipeline_cache.ash_vk_pipeline_cache
+            });
+        let ash_vk_pipelines = unsafe {
+            // Host Synchronization: none, pipelineCache is not created with EXTERNALLY_SYNCHRONIZED
+            match device.ash_device.create_graphics_pipelines(
+                ash_vk_pipeline_cache,
+                create_infos.as_slice(),
                 None,
             ) {
-                Ok(pipelines) => pipelines[0],
-                Err((_, error)) => {
+                Ok(pipelines) => pipelines,
+                Err((pipelines, error)) => {
+                    // pipelines which are created successfully must be destroyed
+                    for pipeline in pipelines {
+                        if pipeline != ash::vk::Pipeline::null() {
+                            device.ash_device.destroy_pipeline(pipeline, None);
+                        }
+                    }
                     return Err(error.into());
                 }
             }
         };
-        Ok(Pipeline {
-            device: self.device,
-            _render_pass_holder: render_pass_holder,
-            _shader_modules_holder: shader_modules_holder,
-            ash_vk_pipeline,
-        })
+        Ok(builders
+            .into_iter()
+            .zip(ash_vk_pipelines)
+            .map(|(builder, ash_vk_pipeline)| builder.into_pipeline(ash_vk_pipeline))
+            .collect())
     }
 }
 
@@ -352,6 +538,10 @@ impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RE
         pipeline_bind_point: ash::vk::PipelineBindPoint,
         pipeline: &Pipeline,
     ) {
+        #[cfg(debug_assertions)]
+        self.holding_resources
+            .bound_pipeline_layouts
+            .insert(pipeline_bind_point, pipeline.layout.clone());
         unsafe {
             // Host Synchronization: commandBuffer, VkCommandPool
             let _pool = self.command_pool.vk_command_pool.write();
@@ -1,6 +1,14 @@
+use crate::command::command_buffer::State::RECORDING;
+use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
 use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceExtendedDynamicState3FeaturesEXT::{
+    ExtendedDynamicState3ColorBlendEnable, ExtendedDynamicState3ColorWriteMask,
+};
 use crate::device_features::PhysicalDeviceFeatures::{DualSrcBlend, LogicOp};
 use crate::device_features::PhysicalDeviceRasterizationOrderAttachmentAccessFeaturesARM::RasterizationOrderColorAttachmentAccess;
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::ExtExtendedDynamicState3;
+use smallvec::SmallVec;
 
 // DONE VUID-VkPipelineColorBlendAttachmentState-srcColorBlendFactor-00608
 // DONE VUID-VkPipelineColorBlendAttachmentState-dstColorBlendFactor-00609
@@ -72,6 +80,26 @@ impl PipelineColorBlendAttachmentState {
             inner: Default::default(),
         }
     }
+    // blending disabled, writes all components
+    pub fn opaque() -> PipelineColorBlendAttachmentState {
+        PipelineColorBlendAttachmentState {
+            color_write_mask: ash::vk::ColorComponentFlags::RGBA,
+            ..Default::default()
+        }
+    }
+    // non-premultiplied "over" blending: src * src_alpha + dst * (1 - src_alpha)
+    pub fn alpha_blend() -> PipelineColorBlendAttachmentState {
+        PipelineColorBlendAttachmentState {
+            blend_enable: true,
+            src_color_blend_factor: ash::vk::BlendFactor::SRC_ALPHA,
+            dst_color_blend_factor: ash::vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+            color_blend_op: ash::vk::BlendOp::ADD,
+            src_alpha_blend_factor: ash::vk::BlendFactor::ONE,
+            dst_alpha_blend_factor: ash::vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+            alpha_blend_op: ash::vk::BlendOp::ADD,
+            color_write_mask: ash::vk::ColorComponentFlags::RGBA,
+        }
+    }
     pub(crate) fn ash_builder(&self) -> ash::vk::PipelineColorBlendAttachmentStateBuilder {
         ash::vk::PipelineColorBlendAttachmentState::builder()
             .blend_enable(self.blend_enable)
@@ -165,6 +193,9 @@ impl PipelineColorBlendStateCreateInfo {
         }
         builder
     }
+    pub(crate) fn attachment_count(&self) -> usize {
+        self.attachments.len()
+    }
 
     pub fn builder() -> PipelineColorBlendStateCreateInfoBuilder {
         PipelineColorBlendStateCreateInfoBuilder {
@@ -206,3 +237,73 @@ impl PipelineColorBlendStateCreateInfoBuilder {
         self.inner
     }
 }
+
+impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RECORDING }, SCOPE> {
+    // DONE VUID-vkCmdSetBlendConstants-commandBuffer-recording
+    pub fn cmd_set_blend_constants(&mut self, blend_constants: [f32; 4]) {
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device
+                .ash_device
+                .cmd_set_blend_constants(self.vk_command_buffer, &blend_constants);
+        }
+    }
+
+    // `color_blend_enables[i]` enables blending of the attachment `first_attachment + i`
+    // DONE VUID-vkCmdSetColorBlendEnableEXT-commandBuffer-recording
+    // DONE VUID-vkCmdSetColorBlendEnableEXT-extendedDynamicState3ColorBlendEnable-07355
+    pub fn cmd_set_color_blend_enable(
+        &mut self,
+        first_attachment: u32,
+        color_blend_enables: &[bool],
+        _extension: &DeviceExtension<{ ExtExtendedDynamicState3 }>,
+        _feature: &Feature<{ ExtendedDynamicState3ColorBlendEnable.into() }>,
+    ) {
+        // MUST VUID-vkCmdSetColorBlendEnableEXT-attachmentCount-arraylength
+        if color_blend_enables.is_empty() {
+            panic!("VUID-vkCmdSetColorBlendEnableEXT-attachmentCount-arraylength");
+        }
+        let vk_color_blend_enables = color_blend_enables
+            .iter()
+            .map(|enable| *enable as ash::vk::Bool32)
+            .collect::<SmallVec<[ash::vk::Bool32; 8]>>();
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device
+                .extended_dynamic_state3_loader()
+                .cmd_set_color_blend_enable(
+                    self.vk_command_buffer,
+                    first_attachment,
+                    vk_color_blend_enables.as_slice(),
+                );
+        }
+    }
+
+    // DONE VUID-vkCmdSetColorWriteMaskEXT-commandBuffer-recording
+    // DONE VUID-vkCmdSetColorWriteMaskEXT-extendedDynamicState3ColorWriteMask-07364
+    pub fn cmd_set_color_write_mask(
+        &mut self,
+        first_attachment: u32,
+        color_write_masks: &[ash::vk::ColorComponentFlags],
+        _extension: &DeviceExtension<{ ExtExtendedDynamicState3 }>,
+        _feature: &Feature<{ ExtendedDynamicState3ColorWriteMask.into() }>,
+    ) {
+        // MUST VUID-vkCmdSetColorWriteMaskEXT-attachmentCount-arraylength
+        if color_write_masks.is_empty() {
+            panic!("VUID-vkCmdSetColorWriteMaskEXT-attachmentCount-arraylength");
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device
+                .extended_dynamic_state3_loader()
+                .cmd_set_color_write_mask(
+                    self.vk_command_buffer,
+                    first_attachment,
+                    color_write_masks,
+                );
+        }
+    }
+}
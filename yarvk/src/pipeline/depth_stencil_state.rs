@@ -1,8 +1,11 @@
+use crate::command::command_buffer::State::RECORDING;
+use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
 use crate::device_features::Feature;
 use crate::device_features::PhysicalDeviceFeatures::DepthBounds;
 use crate::device_features::PhysicalDeviceRasterizationOrderAttachmentAccessFeaturesARM::{
     RasterizationOrderDepthAttachmentAccess, RasterizationOrderStencilAttachmentAccess,
 };
+use crate::extensions::PhysicalDeviceExtensionType::ExtDepthRangeUnrestricted;
 
 pub enum PipelineDepthStencilStateCreateFlags {
     RasterizationOrderAttachmentDepthAccessArm(
@@ -108,3 +111,99 @@ impl PipelineDepthStencilStateCreateInfoBuilder {
         self.inner
     }
 }
+
+impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RECORDING }, SCOPE> {
+    // DONE VUID-vkCmdSetDepthBounds-commandBuffer-recording
+    pub fn cmd_set_depth_bounds(
+        &mut self,
+        min_depth_bounds: f32,
+        max_depth_bounds: f32,
+        _feature: &Feature<{ DepthBounds.into() }>,
+    ) {
+        if !self
+            .device
+            .enabled_extensions
+            .contains(&ExtDepthRangeUnrestricted)
+        {
+            // MUST VUID-vkCmdSetDepthBounds-minDepthBounds-00600
+            if !(0.0..=1.0).contains(&min_depth_bounds) {
+                panic!("VUID-vkCmdSetDepthBounds-minDepthBounds-00600");
+            }
+            // MUST VUID-vkCmdSetDepthBounds-maxDepthBounds-00601
+            if !(0.0..=1.0).contains(&max_depth_bounds) {
+                panic!("VUID-vkCmdSetDepthBounds-maxDepthBounds-00601");
+            }
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ash_device.cmd_set_depth_bounds(
+                self.vk_command_buffer,
+                min_depth_bounds,
+                max_depth_bounds,
+            );
+        }
+    }
+    // DONE VUID-vkCmdSetStencilReference-commandBuffer-recording
+    pub fn cmd_set_stencil_reference(
+        &mut self,
+        face_mask: ash::vk::StencilFaceFlags,
+        reference: u32,
+    ) {
+        // MUST VUID-vkCmdSetStencilReference-faceMask-requiredbitmask
+        if face_mask.is_empty() {
+            panic!("VUID-vkCmdSetStencilReference-faceMask-requiredbitmask");
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ash_device.cmd_set_stencil_reference(
+                self.vk_command_buffer,
+                face_mask,
+                reference,
+            );
+        }
+    }
+
+    // DONE VUID-vkCmdSetStencilCompareMask-commandBuffer-recording
+    pub fn cmd_set_stencil_compare_mask(
+        &mut self,
+        face_mask: ash::vk::StencilFaceFlags,
+        compare_mask: u32,
+    ) {
+        // MUST VUID-vkCmdSetStencilCompareMask-faceMask-requiredbitmask
+        if face_mask.is_empty() {
+            panic!("VUID-vkCmdSetStencilCompareMask-faceMask-requiredbitmask");
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ash_device.cmd_set_stencil_compare_mask(
+                self.vk_command_buffer,
+                face_mask,
+                compare_mask,
+            );
+        }
+    }
+
+    // DONE VUID-vkCmdSetStencilWriteMask-commandBuffer-recording
+    pub fn cmd_set_stencil_write_mask(
+        &mut self,
+        face_mask: ash::vk::StencilFaceFlags,
+        write_mask: u32,
+    ) {
+        // MUST VUID-vkCmdSetStencilWriteMask-faceMask-requiredbitmask
+        if face_mask.is_empty() {
+            panic!("VUID-vkCmdSetStencilWriteMask-faceMask-requiredbitmask");
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ash_device.cmd_set_stencil_write_mask(
+                self.vk_command_buffer,
+                face_mask,
+                write_mask,
+            );
+        }
+    }
+}
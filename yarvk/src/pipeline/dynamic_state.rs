@@ -0,0 +1,66 @@
+use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceExtendedDynamicState3FeaturesEXT::{
+    ExtendedDynamicState3ColorBlendEnable, ExtendedDynamicState3ColorWriteMask,
+    ExtendedDynamicState3PolygonMode, ExtendedDynamicState3RasterizationSamples,
+};
+use crate::device_features::PhysicalDeviceExtendedDynamicStateFeaturesEXT::ExtendedDynamicState;
+use crate::device_features::PhysicalDeviceFeatures::DepthBounds;
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::{
+    ExtLineRasterization, ExtSampleLocations, KhrFragmentShadingRate,
+};
+
+pub enum DynamicState {
+    Viewport,
+    Scissor,
+    DepthBias,
+    LineWidth,
+    StencilCompareMask,
+    StencilWriteMask,
+    StencilReference,
+    BlendConstants,
+    DepthBounds(Feature<{ DepthBounds.into() }>),
+    LineStippleExt(DeviceExtension<{ ExtLineRasterization }>),
+    FragmentShadingRateKhr(DeviceExtension<{ KhrFragmentShadingRate }>),
+    SampleLocationsExt(DeviceExtension<{ ExtSampleLocations }>),
+    // strides are set by `cmd_bind_vertex_buffers2`
+    VertexInputBindingStride(Feature<{ ExtendedDynamicState.into() }>),
+    // set by `cmd_set_polygon_mode`
+    PolygonModeExt(Feature<{ ExtendedDynamicState3PolygonMode.into() }>),
+    // set by `cmd_set_rasterization_samples`
+    RasterizationSamplesExt(Feature<{ ExtendedDynamicState3RasterizationSamples.into() }>),
+    // set by `cmd_set_color_blend_enable`
+    ColorBlendEnableExt(Feature<{ ExtendedDynamicState3ColorBlendEnable.into() }>),
+    // set by `cmd_set_color_write_mask`
+    ColorWriteMaskExt(Feature<{ ExtendedDynamicState3ColorWriteMask.into() }>),
+}
+
+impl DynamicState {
+    pub(crate) fn to_ash(&self) -> ash::vk::DynamicState {
+        match self {
+            DynamicState::Viewport => ash::vk::DynamicState::VIEWPORT,
+            DynamicState::Scissor => ash::vk::DynamicState::SCISSOR,
+            DynamicState::DepthBias => ash::vk::DynamicState::DEPTH_BIAS,
+            DynamicState::LineWidth => ash::vk::DynamicState::LINE_WIDTH,
+            DynamicState::StencilCompareMask => ash::vk::DynamicState::STENCIL_COMPARE_MASK,
+            DynamicState::StencilWriteMask => ash::vk::DynamicState::STENCIL_WRITE_MASK,
+            DynamicState::StencilReference => ash::vk::DynamicState::STENCIL_REFERENCE,
+            DynamicState::BlendConstants => ash::vk::DynamicState::BLEND_CONSTANTS,
+            DynamicState::DepthBounds(_) => ash::vk::DynamicState::DEPTH_BOUNDS,
+            DynamicState::LineStippleExt(_) => ash::vk::DynamicState::LINE_STIPPLE_EXT,
+            DynamicState::FragmentShadingRateKhr(_) => {
+                ash::vk::DynamicState::FRAGMENT_SHADING_RATE_KHR
+            }
+            DynamicState::SampleLocationsExt(_) => ash::vk::DynamicState::SAMPLE_LOCATIONS_EXT,
+            DynamicState::VertexInputBindingStride(_) => {
+                ash::vk::DynamicState::VERTEX_INPUT_BINDING_STRIDE
+            }
+            DynamicState::PolygonModeExt(_) => ash::vk::DynamicState::POLYGON_MODE_EXT,
+            DynamicState::RasterizationSamplesExt(_) => {
+                ash::vk::DynamicState::RASTERIZATION_SAMPLES_EXT
+            }
+            DynamicState::ColorBlendEnableExt(_) => ash::vk::DynamicState::COLOR_BLEND_ENABLE_EXT,
+            DynamicState::ColorWriteMaskExt(_) => ash::vk::DynamicState::COLOR_WRITE_MASK_EXT,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::command::command_buffer::State::RECORDING;
+use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
+use crate::device_features::PhysicalDeviceFragmentShadingRateFeaturesKHR;
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::KhrFragmentShadingRate;
+use crate::physical_device::PhysicalDevice;
+
+impl PhysicalDevice {
+    pub fn get_physical_device_fragment_shading_rates(
+        &self,
+    ) -> Result<Vec<ash::vk::PhysicalDeviceFragmentShadingRateKHR>, ash::vk::Result> {
+        let mut cached_rates = self.fragment_shading_rates.lock();
+        if let Some(rates) = cached_rates.as_ref() {
+            return Ok(rates
+                .iter()
+                .map(|(sample_counts, fragment_size)| {
+                    ash::vk::PhysicalDeviceFragmentShadingRateKHR::builder()
+                        .sample_counts(*sample_counts)
+                        .fragment_size(*fragment_size)
+                        .build()
+                })
+                .collect());
+        }
+        let fp = self.instance.fragment_shading_rate_fn();
+        unsafe {
+            // Host Synchronization: none
+            let mut count = 0;
+            (fp.get_physical_device_fragment_shading_rates_khr)(
+                self.vk_physical_device,
+                &mut count,
+                std::ptr::null_mut(),
+            )
+            .result()?;
+            let mut rates = vec![Default::default(); count as usize];
+            (fp.get_physical_device_fragment_shading_rates_khr)(
+                self.vk_physical_device,
+                &mut count,
+                rates.as_mut_ptr(),
+            )
+            .result()?;
+            rates.truncate(count as usize);
+            *cached_rates = Some(
+                rates
+                    .iter()
+                    .map(|rate| (rate.sample_counts, rate.fragment_size))
+                    .collect(),
+            );
+            Ok(rates)
+        }
+    }
+}
+
+impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RECORDING }, SCOPE> {
+    // DONE VUID-vkCmdSetFragmentShadingRateKHR-commandBuffer-recording
+    pub fn cmd_set_fragment_shading_rate(
+        &mut self,
+        _extension: &DeviceExtension<{ KhrFragmentShadingRate }>,
+        fragment_size: &ash::vk::Extent2D,
+        combiner_ops: &[ash::vk::FragmentShadingRateCombinerOpKHR; 2],
+    ) -> Result<(), ash::vk::Result> {
+        let enabled_features = &self.device.enabled_features;
+        // MUST VUID-vkCmdSetFragmentShadingRateKHR-pipelineFragmentShadingRate-04507
+        // MUST VUID-vkCmdSetFragmentShadingRateKHR-pipelineFragmentShadingRate-04508
+        if !enabled_features.contains(
+            &PhysicalDeviceFragmentShadingRateFeaturesKHR::PipelineFragmentShadingRate.into(),
+        ) && (fragment_size.width != 1 || fragment_size.height != 1)
+        {
+            panic!("VUID-vkCmdSetFragmentShadingRateKHR-pipelineFragmentShadingRate-04507");
+        }
+        // MUST VUID-vkCmdSetFragmentShadingRateKHR-primitiveFragmentShadingRate-04510
+        if !enabled_features.contains(
+            &PhysicalDeviceFragmentShadingRateFeaturesKHR::PrimitiveFragmentShadingRate.into(),
+        ) && combiner_ops[0] != ash::vk::FragmentShadingRateCombinerOpKHR::KEEP
+        {
+            panic!("VUID-vkCmdSetFragmentShadingRateKHR-primitiveFragmentShadingRate-04510");
+        }
+        // MUST VUID-vkCmdSetFragmentShadingRateKHR-attachmentFragmentShadingRate-04511
+        if !enabled_features.contains(
+            &PhysicalDeviceFragmentShadingRateFeaturesKHR::AttachmentFragmentShadingRate.into(),
+        ) && combiner_ops[1] != ash::vk::FragmentShadingRateCombinerOpKHR::KEEP
+        {
+            panic!("VUID-vkCmdSetFragmentShadingRateKHR-attachmentFragmentShadingRate-04511");
+        }
+        // 1x1 is always supported
+        if (fragment_size.width != 1 || fragment_size.height != 1)
+            && !self
+                .device
+                .physical_device
+                .get_physical_device_fragment_shading_rates()?
+                .iter()
+                .any(|rate| rate.fragment_size == *fragment_size)
+        {
+            panic!(
+                "fragment size {}x{} is not a supported fragment shading rate",
+                fragment_size.width, fragment_size.height
+            );
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            (self
+                .device
+                .fragment_shading_rate_fn()
+                .cmd_set_fragment_shading_rate_khr)(
+                self.vk_command_buffer,
+                fragment_size,
+                combiner_ops,
+            );
+        }
+        Ok(())
+    }
+}
@@ -178,6 +178,8 @@ fn_restart_enable!(crate::device_features::PhysicalDevicePrimitiveTopologyListRe
 fn_restart_enable!(crate::device_features::PhysicalDevicePrimitiveTopologyListRestartFeaturesEXT::PrimitiveTopologyPatchListRestart,
     PrimitiveTopology::PatchList
 );
+// primitive restart is enabled by `topology::<T>().restart_enable()`, an index of 0xFFFF (UINT16)
+// or 0xFFFFFFFF (UINT32) then restarts the strip, strips and fans need no feature
 macro_rules! fn_restart_enable_no_feature {
     ($($topology: expr),*) => {$(
         impl RestartBuilder<{$topology}> {
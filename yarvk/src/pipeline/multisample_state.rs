@@ -1,13 +1,112 @@
+use crate::command::command_buffer::State::RECORDING;
+use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
 use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceExtendedDynamicState3FeaturesEXT::ExtendedDynamicState3RasterizationSamples;
 use crate::device_features::PhysicalDeviceFeatures::{AlphaToOne, SampleRateShading};
+use crate::error::{Error, Result};
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::{
+    ExtExtendedDynamicState3, ExtSampleLocations,
+};
+use crate::physical_device::PhysicalDevice;
+use rustc_hash::FxHashSet;
+
+impl PhysicalDevice {
+    pub fn get_physical_device_multisample_properties(
+        &self,
+        samples: ash::vk::SampleCountFlags,
+    ) -> ash::vk::MultisamplePropertiesEXT {
+        let fp = self.instance.sample_locations_fn();
+        let mut properties = ash::vk::MultisamplePropertiesEXT::default();
+        unsafe {
+            // Host Synchronization: none
+            (fp.get_physical_device_multisample_properties_ext)(
+                self.vk_physical_device,
+                samples,
+                &mut properties,
+            );
+        }
+        properties
+    }
+}
+
+// Programmable sample positions, `locations` are ordered by pixel in the grid (row major) and
+// then by sample index, each in [0, 1) of the pixel
+#[derive(Clone)]
+pub struct SampleLocationsInfo {
+    sample_locations_per_pixel: ash::vk::SampleCountFlags,
+    sample_location_grid_size: ash::vk::Extent2D,
+    sample_locations: Vec<ash::vk::SampleLocationEXT>,
+}
+
+impl SampleLocationsInfo {
+    pub fn new(
+        extension: &DeviceExtension<{ ExtSampleLocations }>,
+        sample_locations_per_pixel: ash::vk::SampleCountFlags,
+        sample_location_grid_size: ash::vk::Extent2D,
+        sample_locations: &[[f32; 2]],
+    ) -> Self {
+        let physical_device = &extension.device.physical_device;
+        let properties = physical_device
+            .get_physical_device_properties2::<ash::vk::PhysicalDeviceSampleLocationsPropertiesEXT>(
+            );
+        // MUST VUID-VkSampleLocationsInfoEXT-sampleLocationsPerPixel-01526
+        if !properties
+            .sample_location_sample_counts
+            .contains(sample_locations_per_pixel)
+            || sample_locations_per_pixel.as_raw().count_ones() != 1
+        {
+            panic!("VUID-VkSampleLocationsInfoEXT-sampleLocationsPerPixel-01526");
+        }
+        // MUST VUID-VkSampleLocationsInfoEXT-sampleLocationsCount-01527
+        if sample_locations.len() as u32
+            != sample_locations_per_pixel.as_raw()
+                * sample_location_grid_size.width
+                * sample_location_grid_size.height
+        {
+            panic!("VUID-VkSampleLocationsInfoEXT-sampleLocationsCount-01527");
+        }
+        let max_grid_size = physical_device
+            .get_physical_device_multisample_properties(sample_locations_per_pixel)
+            .max_sample_location_grid_size;
+        // MUST VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-01522
+        if sample_location_grid_size.width == 0
+            || max_grid_size.width % sample_location_grid_size.width != 0
+        {
+            panic!("VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-01522");
+        }
+        // MUST VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-01523
+        if sample_location_grid_size.height == 0
+            || max_grid_size.height % sample_location_grid_size.height != 0
+        {
+            panic!("VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-01523");
+        }
+        SampleLocationsInfo {
+            sample_locations_per_pixel,
+            sample_location_grid_size,
+            sample_locations: sample_locations
+                .iter()
+                .map(|[x, y]| ash::vk::SampleLocationEXT { x: *x, y: *y })
+                .collect(),
+        }
+    }
+    pub(crate) fn ash_builder(&self) -> ash::vk::SampleLocationsInfoEXTBuilder {
+        ash::vk::SampleLocationsInfoEXT::builder()
+            .sample_locations_per_pixel(self.sample_locations_per_pixel)
+            .sample_location_grid_size(self.sample_location_grid_size)
+            .sample_locations(self.sample_locations.as_slice())
+    }
+}
 
 pub struct PipelineMultisampleStateCreateInfo {
     rasterization_samples: ash::vk::SampleCountFlags,
     sample_shading_enable: bool,
     min_sample_shading: f32,
-    sample_mask: [u32; 2],
+    // `None` enables all samples
+    sample_mask: Option<Vec<u32>>,
     alpha_to_coverage_enable: bool,
     alpha_to_one_enable: bool,
+    sample_locations: Option<SampleLocationsInfo>,
 }
 
 impl Default for PipelineMultisampleStateCreateInfo {
@@ -16,22 +115,54 @@ impl Default for PipelineMultisampleStateCreateInfo {
             rasterization_samples: ash::vk::SampleCountFlags::TYPE_1,
             sample_shading_enable: false,
             min_sample_shading: 0.0,
-            sample_mask: [0xFFFF, 0xFFFF],
+            sample_mask: None,
             alpha_to_coverage_enable: false,
             alpha_to_one_enable: false,
+            sample_locations: None,
         }
     }
 }
 
 impl PipelineMultisampleStateCreateInfo {
     pub(crate) fn ash_builder(&self) -> ash::vk::PipelineMultisampleStateCreateInfoBuilder {
-        ash::vk::PipelineMultisampleStateCreateInfo::builder()
+        let mut builder = ash::vk::PipelineMultisampleStateCreateInfo::builder()
             .rasterization_samples(self.rasterization_samples)
             .sample_shading_enable(self.sample_shading_enable)
             .min_sample_shading(self.min_sample_shading)
-            .sample_mask(self.sample_mask.as_slice())
             .alpha_to_coverage_enable(self.alpha_to_coverage_enable)
-            .alpha_to_one_enable(self.alpha_to_one_enable)
+            .alpha_to_one_enable(self.alpha_to_one_enable);
+        if let Some(sample_mask) = &self.sample_mask {
+            builder = builder.sample_mask(sample_mask.as_slice());
+        }
+        builder
+    }
+
+    pub(crate) fn ash_sample_locations_state(
+        &self,
+    ) -> Option<ash::vk::PipelineSampleLocationsStateCreateInfoEXT> {
+        self.sample_locations.as_ref().map(|sample_locations| {
+            ash::vk::PipelineSampleLocationsStateCreateInfoEXT::builder()
+                .sample_locations_enable(true)
+                .sample_locations_info(sample_locations.ash_builder().build())
+                .build()
+        })
+    }
+
+    pub(crate) fn check_sample_locations(
+        &self,
+        dynamic_states: &FxHashSet<ash::vk::DynamicState>,
+    ) -> Result<()> {
+        if let Some(sample_locations) = &self.sample_locations {
+            // MUST VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-01521
+            if !dynamic_states.contains(&ash::vk::DynamicState::SAMPLE_LOCATIONS_EXT)
+                && sample_locations.sample_locations_per_pixel != self.rasterization_samples
+            {
+                return Err(Error::Validation {
+                    vuid: "VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-01521",
+                });
+            }
+        }
+        Ok(())
     }
 
     pub fn builder() -> PipelineMultisampleStateCreateInfoBuilder {
@@ -66,9 +197,9 @@ impl PipelineMultisampleStateCreateInfoBuilder {
         self
     }
 
-    pub fn sample_mask(mut self, sample_mask: u64) -> Self {
-        let sample_mask = sample_mask;
-        self.inner.sample_mask = unsafe { std::mem::transmute(sample_mask.to_le_bytes()) };
+    // one word for every 32 samples of `rasterization_samples`, checked by `build`
+    pub fn sample_mask(mut self, sample_mask: &[u32]) -> Self {
+        self.inner.sample_mask = Some(sample_mask.to_vec());
         self
     }
     pub fn alpha_to_coverage_enable(mut self) -> Self {
@@ -80,7 +211,57 @@ impl PipelineMultisampleStateCreateInfoBuilder {
         self.inner.alpha_to_one_enable = true;
         self
     }
+    // with `DynamicState::SampleLocationsExt` the locations are set by `cmd_set_sample_locations`
+    // instead and `sample_locations` is ignored
+    pub fn sample_locations(mut self, sample_locations: SampleLocationsInfo) -> Self {
+        self.inner.sample_locations = Some(sample_locations);
+        self
+    }
     pub fn build(self) -> PipelineMultisampleStateCreateInfo {
+        // MUST VUID-VkPipelineMultisampleStateCreateInfo-pSampleMask-parameter
+        if let Some(sample_mask) = &self.inner.sample_mask {
+            let word_count = (self.inner.rasterization_samples.as_raw() + 31) / 32;
+            if sample_mask.len() != word_count as usize {
+                panic!("VUID-VkPipelineMultisampleStateCreateInfo-pSampleMask-parameter");
+            }
+        }
         self.inner
     }
 }
+
+impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RECORDING }, SCOPE> {
+    // DONE VUID-vkCmdSetSampleLocationsEXT-commandBuffer-recording
+    // TODO VUID-vkCmdSetSampleLocationsEXT-variableSampleLocations-01530
+    pub fn cmd_set_sample_locations(&mut self, sample_locations: &SampleLocationsInfo) {
+        let fp = self.device.sample_locations_fn();
+        let sample_locations_info = sample_locations.ash_builder().build();
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            (fp.cmd_set_sample_locations_ext)(self.vk_command_buffer, &sample_locations_info);
+        }
+    }
+
+    // DONE VUID-vkCmdSetRasterizationSamplesEXT-commandBuffer-recording
+    // DONE VUID-vkCmdSetRasterizationSamplesEXT-extendedDynamicState3RasterizationSamples-07414
+    pub fn cmd_set_rasterization_samples(
+        &mut self,
+        rasterization_samples: ash::vk::SampleCountFlags,
+        _extension: &DeviceExtension<{ ExtExtendedDynamicState3 }>,
+        _feature: &Feature<{ ExtendedDynamicState3RasterizationSamples.into() }>,
+    ) {
+        // MUST VUID-vkCmdSetRasterizationSamplesEXT-rasterizationSamples-parameter
+        if rasterization_samples.as_raw().count_ones() != 1
+            || !ash::vk::SampleCountFlags::from_raw(0x7f).contains(rasterization_samples)
+        {
+            panic!("VUID-vkCmdSetRasterizationSamplesEXT-rasterizationSamples-parameter");
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device
+                .extended_dynamic_state3_loader()
+                .cmd_set_rasterization_samples(self.vk_command_buffer, rasterization_samples);
+        }
+    }
+}
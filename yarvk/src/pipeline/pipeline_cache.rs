@@ -0,0 +1,142 @@
+use crate::device::Device;
+use std::path::Path;
+use std::sync::Arc;
+
+// file layout: MAGIC, FILE_VERSION, the VkPipelineCacheHeaderVersionOne fields of the device
+// that produced the data, data size, then the data returned by vkGetPipelineCacheData
+const MAGIC: &[u8; 4] = b"YPCH";
+const FILE_VERSION: u32 = 1;
+const HEADER_SIZE: usize = 4 + 4 + 32 + 8;
+
+#[derive(Debug)]
+pub enum PipelineCacheError {
+    Io(std::io::Error),
+    Vulkan(ash::vk::Result),
+}
+
+impl From<std::io::Error> for PipelineCacheError {
+    fn from(error: std::io::Error) -> Self {
+        PipelineCacheError::Io(error)
+    }
+}
+
+impl From<ash::vk::Result> for PipelineCacheError {
+    fn from(error: ash::vk::Result) -> Self {
+        PipelineCacheError::Vulkan(error)
+    }
+}
+
+pub struct PipelineCache {
+    pub device: Arc<Device>,
+    pub(crate) ash_vk_pipeline_cache: ash::vk::PipelineCache,
+}
+
+impl PipelineCache {
+    pub fn builder(device: Arc<Device>) -> PipelineCacheBuilder {
+        PipelineCacheBuilder {
+            device,
+            initial_data: Vec::new(),
+        }
+    }
+    pub fn get_data(&self) -> Result<Vec<u8>, ash::vk::Result> {
+        unsafe {
+            // Host Synchronization: none
+            self.device
+                .ash_device
+                .get_pipeline_cache_data(self.ash_vk_pipeline_cache)
+        }
+    }
+    pub fn save_to_path(&self, path: &Path) -> Result<(), PipelineCacheError> {
+        let data = self.get_data()?;
+        let mut file = Vec::with_capacity(HEADER_SIZE + data.len());
+        file.extend_from_slice(MAGIC);
+        file.extend_from_slice(&FILE_VERSION.to_le_bytes());
+        file.extend_from_slice(&device_header(&self.device));
+        file.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        file.extend_from_slice(&data);
+        std::fs::write(path, file)?;
+        Ok(())
+    }
+    // a cache saved by another driver or device is discarded and an empty cache is created,
+    // since initial data not retrieved from a compatible device is invalid
+    pub fn load_from_path(
+        device: Arc<Device>,
+        path: &Path,
+    ) -> Result<Arc<PipelineCache>, PipelineCacheError> {
+        let file = std::fs::read(path)?;
+        let mut builder = PipelineCache::builder(device);
+        if let Some(data) = compatible_data(&builder.device, &file) {
+            builder.initial_data = data.to_vec();
+        }
+        Ok(builder.build()?)
+    }
+}
+
+// the fields of VkPipelineCacheHeaderVersionOne for `device`
+fn device_header(device: &Device) -> [u8; 32] {
+    let properties = device.physical_device.get_physical_device_properties();
+    let mut header = [0u8; 32];
+    header[0..4].copy_from_slice(&32u32.to_le_bytes());
+    header[4..8]
+        .copy_from_slice(&(ash::vk::PipelineCacheHeaderVersion::ONE.as_raw() as u32).to_le_bytes());
+    header[8..12].copy_from_slice(&properties.vendor_id.to_le_bytes());
+    header[12..16].copy_from_slice(&properties.device_id.to_le_bytes());
+    header[16..32].copy_from_slice(&properties.pipeline_cache_uuid);
+    header
+}
+
+fn compatible_data<'a>(device: &Device, file: &'a [u8]) -> Option<&'a [u8]> {
+    if file.len() < HEADER_SIZE || &file[0..4] != MAGIC {
+        return None;
+    }
+    if file[4..8] != FILE_VERSION.to_le_bytes() {
+        return None;
+    }
+    let header = device_header(device);
+    if file[8..40] != header {
+        return None;
+    }
+    let size = u64::from_le_bytes(file[40..48].try_into().ok()?) as usize;
+    let data = &file[HEADER_SIZE..];
+    // the data written by the driver starts with the same header
+    if data.len() != size || data.len() < header.len() || data[8..32] != header[8..32] {
+        return None;
+    }
+    Some(data)
+}
+
+impl Drop for PipelineCache {
+    fn drop(&mut self) {
+        unsafe {
+            // Host Synchronization: pipelineCache
+            self.device
+                .ash_device
+                .destroy_pipeline_cache(self.ash_vk_pipeline_cache, None);
+        }
+    }
+}
+
+pub struct PipelineCacheBuilder {
+    device: Arc<Device>,
+    // only set from data validated by `load_from_path`
+    initial_data: Vec<u8>,
+}
+
+impl PipelineCacheBuilder {
+    pub fn build(self) -> Result<Arc<PipelineCache>, ash::vk::Result> {
+        // DONE VUID-VkPipelineCacheCreateInfo-initialDataSize-00768
+        // DONE VUID-VkPipelineCacheCreateInfo-initialDataSize-00769
+        let create_info =
+            ash::vk::PipelineCacheCreateInfo::builder().initial_data(self.initial_data.as_slice());
+        let ash_vk_pipeline_cache = unsafe {
+            // Host Synchronization: none
+            self.device
+                .ash_device
+                .create_pipeline_cache(&create_info, None)?
+        };
+        Ok(Arc::new(PipelineCache {
+            device: self.device,
+            ash_vk_pipeline_cache,
+        }))
+    }
+}
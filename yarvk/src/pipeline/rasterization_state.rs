@@ -1,7 +1,19 @@
+use crate::command::command_buffer::State::RECORDING;
+use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
 use crate::device_features::Feature;
-use crate::device_features::PhysicalDeviceFeatures::{DepthClamp, FillModeNonSolid};
+use crate::device::Device;
+use crate::error::{Error, Result};
+use crate::device_features::PhysicalDeviceDepthClipEnableFeaturesEXT::DepthClipEnable;
+use crate::device_features::PhysicalDeviceExtendedDynamicState3FeaturesEXT::ExtendedDynamicState3PolygonMode;
+use crate::device_features::PhysicalDeviceFeatures::WideLines;
+use crate::device_features::PhysicalDeviceLineRasterizationFeaturesEXT::{
+    self, BresenhamLines, RectangularLines, SmoothLines,
+};
+use crate::device_features::PhysicalDeviceFeatures::{DepthBiasClamp, DepthClamp, FillModeNonSolid};
 use crate::extensions::DeviceExtension;
-use crate::extensions::PhysicalDeviceExtensionType::NvFillRectangle;
+use crate::extensions::PhysicalDeviceExtensionType::{
+    ExtConservativeRasterization, ExtExtendedDynamicState3, ExtLineRasterization, NvFillRectangle,
+};
 
 
 pub enum PolygonMode {
@@ -24,10 +36,35 @@ impl PolygonMode {
     }
 }
 
+pub enum LineRasterizationMode {
+    Default,
+    // DONE VUID-VkPipelineRasterizationLineStateCreateInfoEXT-lineRasterizationMode-02768
+    Rectangular(Feature<{ RectangularLines.into() }>),
+    // DONE VUID-VkPipelineRasterizationLineStateCreateInfoEXT-lineRasterizationMode-02769
+    Bresenham(Feature<{ BresenhamLines.into() }>),
+    // DONE VUID-VkPipelineRasterizationLineStateCreateInfoEXT-lineRasterizationMode-02770
+    RectangularSmooth(Feature<{ SmoothLines.into() }>),
+}
+
+impl LineRasterizationMode {
+    pub(crate) fn to_ash(&self) -> ash::vk::LineRasterizationModeEXT {
+        match self {
+            LineRasterizationMode::Default => ash::vk::LineRasterizationModeEXT::DEFAULT,
+            LineRasterizationMode::Rectangular(_) => {
+                ash::vk::LineRasterizationModeEXT::RECTANGULAR
+            }
+            LineRasterizationMode::Bresenham(_) => ash::vk::LineRasterizationModeEXT::BRESENHAM,
+            LineRasterizationMode::RectangularSmooth(_) => {
+                ash::vk::LineRasterizationModeEXT::RECTANGULAR_SMOOTH
+            }
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct PipelineRasterizationStateCreateInfo {
     depth_clamp_enable: bool,
-    rasterizer_discard_enable: bool,
+    pub(crate) rasterizer_discard_enable: bool,
     polygon_mode: ash::vk::PolygonMode,
     cull_mode: ash::vk::CullModeFlags,
     front_face: ash::vk::FrontFace,
@@ -36,6 +73,12 @@ pub struct PipelineRasterizationStateCreateInfo {
     depth_bias_clamp: Option<f32>,
     depth_bias_slope_factor: Option<f32>,
     line_width: Option<f32>,
+    depth_clip_enable: Option<bool>,
+    line_rasterization_mode: Option<ash::vk::LineRasterizationModeEXT>,
+    // factor and pattern
+    line_stipple: Option<(u32, u16)>,
+    // mode and extra primitive overestimation size
+    conservative_rasterization: Option<(ash::vk::ConservativeRasterizationModeEXT, f32)>,
 }
 
 impl PipelineRasterizationStateCreateInfo {
@@ -66,6 +109,86 @@ impl PipelineRasterizationStateCreateInfo {
         }
         builder
     }
+    pub(crate) fn check_line_width(
+        &self,
+        device: &Device,
+        dynamic_states: &rustc_hash::FxHashSet<ash::vk::DynamicState>,
+    ) -> Result<()> {
+        // MUST VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-00749
+        if let Some(line_width) = self.line_width {
+            if line_width != 1.0
+                && !dynamic_states.contains(&ash::vk::DynamicState::LINE_WIDTH)
+                && !device.enabled_features.contains(&WideLines.into())
+            {
+                return Err(Error::Validation {
+                    vuid: "VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-00749",
+                });
+            }
+        }
+        Ok(())
+    }
+    pub(crate) fn ash_line_state(
+        &self,
+        device: &Device,
+    ) -> Option<ash::vk::PipelineRasterizationLineStateCreateInfoEXT> {
+        if self.line_rasterization_mode.is_none() && self.line_stipple.is_none() {
+            return None;
+        }
+        let line_rasterization_mode = self
+            .line_rasterization_mode
+            .unwrap_or(ash::vk::LineRasterizationModeEXT::DEFAULT);
+        let mut builder = ash::vk::PipelineRasterizationLineStateCreateInfoEXT::builder()
+            .line_rasterization_mode(line_rasterization_mode);
+        if let Some((factor, pattern)) = self.line_stipple {
+            let (feature, vuid) = match line_rasterization_mode {
+                ash::vk::LineRasterizationModeEXT::BRESENHAM => (
+                    PhysicalDeviceLineRasterizationFeaturesEXT::StippledBresenhamLines,
+                    "VUID-VkPipelineRasterizationLineStateCreateInfoEXT-stippledLineEnable-02773",
+                ),
+                ash::vk::LineRasterizationModeEXT::RECTANGULAR_SMOOTH => (
+                    PhysicalDeviceLineRasterizationFeaturesEXT::StippledSmoothLines,
+                    "VUID-VkPipelineRasterizationLineStateCreateInfoEXT-stippledLineEnable-02774",
+                ),
+                ash::vk::LineRasterizationModeEXT::RECTANGULAR => (
+                    PhysicalDeviceLineRasterizationFeaturesEXT::StippledRectangularLines,
+                    "VUID-VkPipelineRasterizationLineStateCreateInfoEXT-stippledLineEnable-02772",
+                ),
+                // TODO strictLines limit of the default mode
+                _ => (
+                    PhysicalDeviceLineRasterizationFeaturesEXT::StippledRectangularLines,
+                    "VUID-VkPipelineRasterizationLineStateCreateInfoEXT-stippledLineEnable-02775",
+                ),
+            };
+            // MUST the stipple feature of the line rasterization mode
+            if !device.enabled_features.contains(&feature.into()) {
+                panic!("{}", vuid);
+            }
+            builder = builder
+                .stippled_line_enable(true)
+                .line_stipple_factor(factor)
+                .line_stipple_pattern(pattern);
+        }
+        Some(builder.build())
+    }
+    pub(crate) fn ash_conservative_state(
+        &self,
+    ) -> Option<ash::vk::PipelineRasterizationConservativeStateCreateInfoEXT> {
+        self.conservative_rasterization.map(|(mode, extra_overestimation_size)| {
+            ash::vk::PipelineRasterizationConservativeStateCreateInfoEXT::builder()
+                .conservative_rasterization_mode(mode)
+                .extra_primitive_overestimation_size(extra_overestimation_size)
+                .build()
+        })
+    }
+    pub(crate) fn ash_depth_clip_state(
+        &self,
+    ) -> Option<ash::vk::PipelineRasterizationDepthClipStateCreateInfoEXT> {
+        self.depth_clip_enable.map(|depth_clip_enable| {
+            ash::vk::PipelineRasterizationDepthClipStateCreateInfoEXT::builder()
+                .depth_clip_enable(depth_clip_enable)
+                .build()
+        })
+    }
 }
 
 pub struct PipelineRasterizationStateCreateInfoBuilder {
@@ -82,6 +205,15 @@ impl PipelineRasterizationStateCreateInfoBuilder {
         self.inner.depth_clamp_enable = depth_clamp_enable.into();
         self
     }
+    // clipping against the near and far planes is controlled independently from depth clamp
+    pub fn depth_clip_enable(
+        mut self,
+        depth_clip_enable: bool,
+        _feature: Feature<{ DepthClipEnable.into() }>,
+    ) -> Self {
+        self.inner.depth_clip_enable = Some(depth_clip_enable);
+        self
+    }
     pub fn rasterizer_discard_enable(mut self) -> Self {
         self.inner.rasterizer_discard_enable = true;
         self
@@ -98,6 +230,7 @@ impl PipelineRasterizationStateCreateInfoBuilder {
         self.inner.front_face = front_face;
         self
     }
+    // TODO VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-00754
     pub fn depth_bias(
         mut self,
         constant_factor: Option<f32>,
@@ -114,7 +247,141 @@ impl PipelineRasterizationStateCreateInfoBuilder {
         self.inner.line_width = Some(line_width);
         self
     }
+    pub fn line_rasterization_mode(
+        mut self,
+        line_rasterization_mode: LineRasterizationMode,
+        _extension: &DeviceExtension<{ ExtLineRasterization }>,
+    ) -> Self {
+        self.inner.line_rasterization_mode = Some(line_rasterization_mode.to_ash());
+        self
+    }
+    // each bit of `pattern` is repeated `factor` times
+    pub fn line_stipple(
+        mut self,
+        factor: u32,
+        pattern: u16,
+        _extension: &DeviceExtension<{ ExtLineRasterization }>,
+    ) -> Self {
+        // MUST VUID-VkGraphicsPipelineCreateInfo-stippledLineEnable-02767
+        if !(1..=256).contains(&factor) {
+            panic!("VUID-VkGraphicsPipelineCreateInfo-stippledLineEnable-02767");
+        }
+        self.inner.line_stipple = Some((factor, pattern));
+        self
+    }
+    // `extra_overestimation` only takes effect in OVERESTIMATE mode
+    pub fn conservative_rasterization(
+        mut self,
+        mode: ash::vk::ConservativeRasterizationModeEXT,
+        extra_overestimation: f32,
+        extension: &DeviceExtension<{ ExtConservativeRasterization }>,
+    ) -> Self {
+        let properties = extension
+            .device
+            .physical_device
+            .get_physical_device_properties2::<ash::vk::PhysicalDeviceConservativeRasterizationPropertiesEXT>();
+        // MUST VUID-VkPipelineRasterizationConservativeStateCreateInfoEXT-extraPrimitiveOverestimationSize-01769
+        if !(0.0..=properties.max_extra_primitive_overestimation_size)
+            .contains(&extra_overestimation)
+        {
+            panic!("VUID-VkPipelineRasterizationConservativeStateCreateInfoEXT-extraPrimitiveOverestimationSize-01769");
+        }
+        self.inner.conservative_rasterization = Some((mode, extra_overestimation));
+        self
+    }
     pub fn build(self) -> PipelineRasterizationStateCreateInfo {
         self.inner
     }
 }
+
+impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RECORDING }, SCOPE> {
+    // DONE VUID-vkCmdSetDepthBias-commandBuffer-recording
+    // DONE VUID-vkCmdSetDepthBias-depthBiasClamp-00790
+    pub fn cmd_set_depth_bias(&mut self, constant_factor: f32, slope_factor: f32) {
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ash_device.cmd_set_depth_bias(
+                self.vk_command_buffer,
+                constant_factor,
+                0.0,
+                slope_factor,
+            );
+        }
+    }
+
+    pub fn cmd_set_depth_bias_clamp(
+        &mut self,
+        constant_factor: f32,
+        clamp: f32,
+        slope_factor: f32,
+        _feature: &Feature<{ DepthBiasClamp.into() }>,
+    ) {
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ash_device.cmd_set_depth_bias(
+                self.vk_command_buffer,
+                constant_factor,
+                clamp,
+                slope_factor,
+            );
+        }
+    }
+
+    // DONE VUID-vkCmdSetLineWidth-commandBuffer-recording
+    pub fn cmd_set_line_width(&mut self, line_width: f32) {
+        // MUST VUID-vkCmdSetLineWidth-lineWidth-00788
+        if line_width != 1.0 && !self.device.enabled_features.contains(&WideLines.into()) {
+            panic!("VUID-vkCmdSetLineWidth-lineWidth-00788");
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device
+                .ash_device
+                .cmd_set_line_width(self.vk_command_buffer, line_width);
+        }
+    }
+
+    // DONE VUID-vkCmdSetLineStippleEXT-commandBuffer-recording
+    pub fn cmd_set_line_stipple(
+        &mut self,
+        factor: u32,
+        pattern: u16,
+        _extension: &DeviceExtension<{ ExtLineRasterization }>,
+    ) {
+        // MUST VUID-vkCmdSetLineStippleEXT-lineStippleFactor-02776
+        if !(1..=256).contains(&factor) {
+            panic!("VUID-vkCmdSetLineStippleEXT-lineStippleFactor-02776");
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            (self.device.line_rasterization_fn().cmd_set_line_stipple_ext)(
+                self.vk_command_buffer,
+                factor,
+                pattern,
+            );
+        }
+    }
+
+    // DONE VUID-vkCmdSetPolygonModeEXT-commandBuffer-recording
+    // DONE VUID-vkCmdSetPolygonModeEXT-extendedDynamicState3PolygonMode-07422
+    // DONE VUID-vkCmdSetPolygonModeEXT-fillModeNonSolid-07424
+    // DONE VUID-vkCmdSetPolygonModeEXT-polygonMode-07425
+    pub fn cmd_set_polygon_mode(
+        &mut self,
+        polygon_mode: PolygonMode,
+        _extension: &DeviceExtension<{ ExtExtendedDynamicState3 }>,
+        _feature: &Feature<{ ExtendedDynamicState3PolygonMode.into() }>,
+    ) {
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device
+                .extended_dynamic_state3_loader()
+                .cmd_set_polygon_mode(self.vk_command_buffer, polygon_mode.to_ash());
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use crate::buffer::Buffer;
+use crate::command::command_buffer::RenderPassScope::OUTSIDE;
+use crate::command::command_buffer::State::RECORDING;
+use crate::command::command_buffer::{CommandBuffer, Level};
+use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceRayTracingPipelineFeaturesKHR::RayTracingPipeline as RayTracingPipelineFeature;
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::KhrRayTracingPipeline;
+use crate::pipeline::shader_stage::PipelineShaderStageCreateInfo;
+use crate::pipeline::{Pipeline, PipelineLayout};
+use ash::vk::Handle;
+use std::sync::Arc;
+
+// stages are referred by the order they are added to the builder
+pub enum RayTracingShaderGroup {
+    // a raygen, miss or callable stage
+    General(u32),
+    TrianglesHit {
+        closest_hit: Option<u32>,
+        any_hit: Option<u32>,
+    },
+    ProceduralHit {
+        intersection: u32,
+        closest_hit: Option<u32>,
+        any_hit: Option<u32>,
+    },
+}
+
+pub struct RayTracingPipeline {
+    pub pipeline: Pipeline,
+    group_count: u32,
+}
+
+impl RayTracingPipeline {
+    pub fn builder<'a>(
+        _extension: DeviceExtension<{ KhrRayTracingPipeline }>,
+        _feature: Feature<{ RayTracingPipelineFeature.into() }>,
+        layout: Arc<PipelineLayout>,
+    ) -> RayTracingPipelineBuilder<'a> {
+        RayTracingPipelineBuilder {
+            flags: Default::default(),
+            stages: vec![],
+            groups: vec![],
+            max_pipeline_ray_recursion_depth: 1,
+            layout,
+        }
+    }
+    pub fn group_count(&self) -> u32 {
+        self.group_count
+    }
+    // shader_group_handle_size bytes for each group, to be copied into shader binding tables
+    pub fn get_shader_group_handles(
+        &self,
+        first_group: u32,
+        group_count: u32,
+    ) -> Result<Vec<u8>, ash::vk::Result> {
+        // MUST VUID-vkGetRayTracingShaderGroupHandlesKHR-firstGroup-04050
+        if first_group >= self.group_count {
+            panic!("VUID-vkGetRayTracingShaderGroupHandlesKHR-firstGroup-04050");
+        }
+        // MUST VUID-vkGetRayTracingShaderGroupHandlesKHR-firstGroup-02419
+        if first_group + group_count > self.group_count {
+            panic!("VUID-vkGetRayTracingShaderGroupHandlesKHR-firstGroup-02419");
+        }
+        // DONE VUID-vkGetRayTracingShaderGroupHandlesKHR-dataSize-02420
+        let handle_size = self
+            .pipeline
+            .device
+            .physical_device
+            .get_physical_device_properties2::<ash::vk::PhysicalDeviceRayTracingPipelinePropertiesKHR>()
+            .shader_group_handle_size;
+        unsafe {
+            // Host Synchronization: none
+            self.pipeline
+                .device
+                .ray_tracing_pipeline_loader()
+                .get_ray_tracing_shader_group_handles(
+                    self.pipeline.ash_vk_pipeline,
+                    first_group,
+                    group_count,
+                    (group_count * handle_size) as usize,
+                )
+        }
+    }
+}
+
+pub struct RayTracingPipelineBuilder<'a> {
+    flags: ash::vk::PipelineCreateFlags,
+    stages: Vec<PipelineShaderStageCreateInfo<'a>>,
+    groups: Vec<RayTracingShaderGroup>,
+    max_pipeline_ray_recursion_depth: u32,
+    layout: Arc<PipelineLayout>,
+}
+
+impl<'a> RayTracingPipelineBuilder<'a> {
+    pub fn flags(mut self, flags: ash::vk::PipelineCreateFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+    pub fn add_stage(mut self, stage: PipelineShaderStageCreateInfo<'a>) -> Self {
+        self.stages.push(stage);
+        self
+    }
+    pub fn add_group(mut self, group: RayTracingShaderGroup) -> Self {
+        self.groups.push(group);
+        self
+    }
+    pub fn max_pipeline_ray_recursion_depth(mut self, depth: u32) -> Self {
+        self.max_pipeline_ray_recursion_depth = depth;
+        self
+    }
+    fn check_stage(&self, index: u32, stages: ash::vk::ShaderStageFlags, vuid: &str) {
+        match self.stages.get(index as usize) {
+            Some(stage) if stages.contains(stage.stage) => {}
+            _ => panic!("{}", vuid),
+        }
+    }
+    fn group_to_ash(
+        &self,
+        group: &RayTracingShaderGroup,
+    ) -> ash::vk::RayTracingShaderGroupCreateInfoKHR {
+        let builder = ash::vk::RayTracingShaderGroupCreateInfoKHR::builder()
+            .general_shader(ash::vk::SHADER_UNUSED_KHR)
+            .closest_hit_shader(ash::vk::SHADER_UNUSED_KHR)
+            .any_hit_shader(ash::vk::SHADER_UNUSED_KHR)
+            .intersection_shader(ash::vk::SHADER_UNUSED_KHR);
+        // DONE VUID-VkRayTracingShaderGroupCreateInfoKHR-type-03475
+        // DONE VUID-VkRayTracingShaderGroupCreateInfoKHR-type-03477
+        let (builder, closest_hit, any_hit) = match group {
+            RayTracingShaderGroup::General(general) => {
+                // MUST VUID-VkRayTracingShaderGroupCreateInfoKHR-type-03474
+                self.check_stage(
+                    *general,
+                    ash::vk::ShaderStageFlags::RAYGEN_KHR
+                        | ash::vk::ShaderStageFlags::MISS_KHR
+                        | ash::vk::ShaderStageFlags::CALLABLE_KHR,
+                    "VUID-VkRayTracingShaderGroupCreateInfoKHR-type-03474",
+                );
+                let builder = builder
+                    .ty(ash::vk::RayTracingShaderGroupTypeKHR::GENERAL)
+                    .general_shader(*general);
+                (builder, None, None)
+            }
+            RayTracingShaderGroup::TrianglesHit {
+                closest_hit,
+                any_hit,
+            } => {
+                let builder =
+                    builder.ty(ash::vk::RayTracingShaderGroupTypeKHR::TRIANGLES_HIT_GROUP);
+                (builder, *closest_hit, *any_hit)
+            }
+            RayTracingShaderGroup::ProceduralHit {
+                intersection,
+                closest_hit,
+                any_hit,
+            } => {
+                // MUST VUID-VkRayTracingShaderGroupCreateInfoKHR-type-03476
+                self.check_stage(
+                    *intersection,
+                    ash::vk::ShaderStageFlags::INTERSECTION_KHR,
+                    "VUID-VkRayTracingShaderGroupCreateInfoKHR-type-03476",
+                );
+                let builder = builder
+                    .ty(ash::vk::RayTracingShaderGroupTypeKHR::PROCEDURAL_HIT_GROUP)
+                    .intersection_shader(*intersection);
+                (builder, *closest_hit, *any_hit)
+            }
+        };
+        let mut builder = builder;
+        if let Some(closest_hit) = closest_hit {
+            // MUST VUID-VkRayTracingShaderGroupCreateInfoKHR-closestHitShader-03478
+            self.check_stage(
+                closest_hit,
+                ash::vk::ShaderStageFlags::CLOSEST_HIT_KHR,
+                "VUID-VkRayTracingShaderGroupCreateInfoKHR-closestHitShader-03478",
+            );
+            builder = builder.closest_hit_shader(closest_hit);
+        }
+        if let Some(any_hit) = any_hit {
+            // MUST VUID-VkRayTracingShaderGroupCreateInfoKHR-anyHitShader-03479
+            self.check_stage(
+                any_hit,
+                ash::vk::ShaderStageFlags::ANY_HIT_KHR,
+                "VUID-VkRayTracingShaderGroupCreateInfoKHR-anyHitShader-03479",
+            );
+            builder = builder.any_hit_shader(any_hit);
+        }
+        builder.build()
+    }
+    pub fn build(self) -> Result<RayTracingPipeline, ash::vk::Result> {
+        // MUST VUID-VkRayTracingPipelineCreateInfoKHR-stage-03425
+        if !self
+            .flags
+            .contains(ash::vk::PipelineCreateFlags::LIBRARY_KHR)
+            && !self
+                .stages
+                .iter()
+                .any(|stage| stage.stage == ash::vk::ShaderStageFlags::RAYGEN_KHR)
+        {
+            panic!("VUID-VkRayTracingPipelineCreateInfoKHR-stage-03425");
+        }
+        let device = self.layout.device.clone();
+        // MUST VUID-VkRayTracingPipelineCreateInfoKHR-maxPipelineRayRecursionDepth-03589
+        if self.max_pipeline_ray_recursion_depth
+            > device
+                .physical_device
+                .get_physical_device_properties2::<ash::vk::PhysicalDeviceRayTracingPipelinePropertiesKHR>()
+                .max_ray_recursion_depth
+        {
+            panic!("VUID-VkRayTracingPipelineCreateInfoKHR-maxPipelineRayRecursionDepth-03589");
+        }
+        let ash_vk_groups = self
+            .groups
+            .iter()
+            .map(|group| self.group_to_ash(group))
+            .collect::<Vec<_>>();
+        let ash_vk_stages = self
+            .stages
+            .iter()
+            .map(|stage| stage.ash_builder())
+            .collect::<Vec<_>>();
+        let create_info = ash::vk::RayTracingPipelineCreateInfoKHR::builder()
+            .flags(self.flags)
+            .stages(ash_vk_stages.as_slice())
+            .groups(ash_vk_groups.as_slice())
+            .max_pipeline_ray_recursion_depth(self.max_pipeline_ray_recursion_depth)
+            .layout(self.layout.ash_vk_pipeline_layout)
+            .build();
+        // TODO pipeline caching
+        let ash_vk_pipeline = unsafe {
+            // Host Synchronization: none
+            device
+                .ray_tracing_pipeline_loader()
+                .create_ray_tracing_pipelines(
+                    ash::vk::DeferredOperationKHR::null(),
+                    ash::vk::PipelineCache::null(),
+                    &[create_info],
+                    None,
+                )?[0]
+        };
+        Ok(RayTracingPipeline {
+            pipeline: Pipeline {
+                device,
+                _render_pass_holder: None,
+                _shader_modules_holder: self.stages.into_iter().map(|stage| stage.module).collect(),
+                layout: self.layout,
+                ash_vk_pipeline,
+            },
+            group_count: ash_vk_groups.len() as u32,
+        })
+    }
+}
+
+// a region of a buffer holding shader group handles, each record is `stride` bytes
+pub struct ShaderBindingTable {
+    pub buffer: Arc<Buffer>,
+    pub offset: ash::vk::DeviceSize,
+    pub stride: ash::vk::DeviceSize,
+    pub size: ash::vk::DeviceSize,
+}
+
+impl ShaderBindingTable {
+    fn to_ash(&self) -> ash::vk::StridedDeviceAddressRegionKHR {
+        ash::vk::StridedDeviceAddressRegionKHR {
+            device_address: self.buffer.device_address() + self.offset,
+            stride: self.stride,
+            size: self.size,
+        }
+    }
+}
+
+impl<const LEVEL: Level> CommandBuffer<LEVEL, { RECORDING }, { OUTSIDE }> {
+    // DONE VUID-vkCmdTraceRaysKHR-commandBuffer-recording
+    // DONE VUID-vkCmdTraceRaysKHR-renderpass
+    #[allow(clippy::too_many_arguments)]
+    pub fn cmd_trace_rays(
+        &mut self,
+        raygen_sbt: &ShaderBindingTable,
+        miss_sbt: Option<&ShaderBindingTable>,
+        hit_sbt: Option<&ShaderBindingTable>,
+        callable_sbt: Option<&ShaderBindingTable>,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) {
+        #[cfg(debug_assertions)]
+        self.check_bound_descriptor_sets(ash::vk::PipelineBindPoint::RAY_TRACING_KHR);
+        // MUST VUID-vkCmdTraceRaysKHR-size-04023
+        if raygen_sbt.size != raygen_sbt.stride {
+            panic!("VUID-vkCmdTraceRaysKHR-size-04023");
+        }
+        let mut regions = [ash::vk::StridedDeviceAddressRegionKHR::default(); 4];
+        for (region, sbt) in
+            regions
+                .iter_mut()
+                .zip([Some(raygen_sbt), miss_sbt, hit_sbt, callable_sbt])
+        {
+            if let Some(sbt) = sbt {
+                *region = sbt.to_ash();
+                self.holding_resources
+                    .read_buffers
+                    .insert(sbt.buffer.ash_vk_buffer.as_raw(), sbt.buffer.clone());
+            }
+        }
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ray_tracing_pipeline_loader().cmd_trace_rays(
+                self.vk_command_buffer,
+                &regions[0],
+                &regions[1],
+                &regions[2],
+                &regions[3],
+                width,
+                height,
+                depth,
+            );
+        }
+    }
+}
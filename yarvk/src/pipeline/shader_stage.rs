@@ -1,4 +1,5 @@
 use crate::device_features::PhysicalDeviceFeatures::{GeometryShader, TessellationShader};
+use crate::device_features::PhysicalDeviceMeshShaderFeaturesEXT;
 use crate::device_features::PhysicalDeviceMeshShaderFeaturesNV::{MeshShader, TaskShader};
 use crate::device_features::{Feature};
 use crate::shader_module::ShaderModule;
@@ -25,6 +26,9 @@ pub enum ShaderStageFlags {
     MissKhr,
     IntersectionKhr,
     CallableKhr,
+    // a mesh pipeline has no vertex input and input assembly, see `cmd_draw_mesh_tasks`
+    TaskExt(Feature<{ PhysicalDeviceMeshShaderFeaturesEXT::TaskShader.into() }>),
+    MeshExt(Feature<{ PhysicalDeviceMeshShaderFeaturesEXT::MeshShader.into() }>),
     TaskNv(Feature<{ TaskShader.into() }>),
     MeshNv(Feature<{ MeshShader.into() }>),
     SubpassShadingHuawei,
@@ -49,6 +53,8 @@ impl ShaderStageFlags {
             ShaderStageFlags::MissKhr => ash::vk::ShaderStageFlags::MISS_KHR,
             ShaderStageFlags::IntersectionKhr => ash::vk::ShaderStageFlags::INTERSECTION_KHR,
             ShaderStageFlags::CallableKhr => ash::vk::ShaderStageFlags::CALLABLE_KHR,
+            ShaderStageFlags::TaskExt(_) => ash::vk::ShaderStageFlags::TASK_EXT,
+            ShaderStageFlags::MeshExt(_) => ash::vk::ShaderStageFlags::MESH_EXT,
             ShaderStageFlags::TaskNv(_) => ash::vk::ShaderStageFlags::TASK_NV,
             ShaderStageFlags::MeshNv(_) => ash::vk::ShaderStageFlags::MESH_NV,
             ShaderStageFlags::SubpassShadingHuawei => {
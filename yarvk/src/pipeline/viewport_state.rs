@@ -3,6 +3,95 @@ use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
 use crate::device_features::Feature;
 use crate::device_features::PhysicalDeviceFeatures::MultiViewport;
 
+pub trait ViewportExt {
+    // depth range is reversed (near 1.0, far 0.0), pair it with compare op GREATER and clear
+    // depth to 0.0 for better depth precision
+    fn reverse_z(width: f32, height: f32) -> Self;
+    // cover the whole `extent`, with `flip_y` the y axis points up like OpenGL and GLM by using a
+    // negative height
+    fn from_extent(extent: ash::vk::Extent2D, min_depth: f32, max_depth: f32, flip_y: bool)
+        -> Self;
+}
+
+impl ViewportExt for ash::vk::Viewport {
+    fn reverse_z(width: f32, height: f32) -> Self {
+        // DONE VUID-VkViewport-minDepth-01234
+        // DONE VUID-VkViewport-maxDepth-01235
+        ash::vk::Viewport {
+            x: 0.0,
+            y: 0.0,
+            width,
+            height,
+            min_depth: 1.0,
+            max_depth: 0.0,
+        }
+    }
+    fn from_extent(
+        extent: ash::vk::Extent2D,
+        min_depth: f32,
+        max_depth: f32,
+        flip_y: bool,
+    ) -> Self {
+        // TODO VUID-VkViewport-minDepth-01234 depends on VK_EXT_depth_range_unrestricted
+        // TODO VUID-VkViewport-maxDepth-01235 depends on VK_EXT_depth_range_unrestricted
+        // SILENCE negative height is core since 1.1, which yarvk requires
+        let (y, height) = if flip_y {
+            (extent.height as f32, -(extent.height as f32))
+        } else {
+            (0.0, extent.height as f32)
+        };
+        ash::vk::Viewport {
+            x: 0.0,
+            y,
+            width: extent.width as f32,
+            height,
+            min_depth,
+            max_depth,
+        }
+    }
+}
+
+pub trait Rect2DExt {
+    // a rect at the origin covering `extent`, e.g. a scissor or render area
+    fn from_extent(extent: ash::vk::Extent2D) -> Self;
+}
+
+impl Rect2DExt for ash::vk::Rect2D {
+    fn from_extent(extent: ash::vk::Extent2D) -> Self {
+        ash::vk::Rect2D {
+            offset: ash::vk::Offset2D { x: 0, y: 0 },
+            extent,
+        }
+    }
+}
+
+pub trait Extent2DExt {
+    fn with_depth(self, depth: u32) -> ash::vk::Extent3D;
+}
+
+impl Extent2DExt for ash::vk::Extent2D {
+    fn with_depth(self, depth: u32) -> ash::vk::Extent3D {
+        ash::vk::Extent3D {
+            width: self.width,
+            height: self.height,
+            depth,
+        }
+    }
+}
+
+pub trait Extent3DExt {
+    // drop the depth
+    fn to_extent_2d(self) -> ash::vk::Extent2D;
+}
+
+impl Extent3DExt for ash::vk::Extent3D {
+    fn to_extent_2d(self) -> ash::vk::Extent2D {
+        ash::vk::Extent2D {
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct PipelineViewportStateCreateInfo {
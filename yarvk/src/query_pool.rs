@@ -0,0 +1,467 @@
+use crate::command::command_buffer::RenderPassScope::OUTSIDE;
+use crate::command::command_buffer::State::RECORDING;
+use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
+use crate::device::Device;
+use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceVulkan12Features::HostQueryReset;
+use ash::vk::Handle;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct QueryPool {
+    pub device: Arc<Device>,
+    pub(crate) vk_query_pool: ash::vk::QueryPool,
+    pub query_type: ash::vk::QueryType,
+    pub query_count: u32,
+    pub pipeline_statistics: ash::vk::QueryPipelineStatisticFlags,
+}
+
+impl Drop for QueryPool {
+    fn drop(&mut self) {
+        unsafe {
+            // TODO VUID-vkDestroyQueryPool-queryPool-00793
+            // Host Synchronization: queryPool
+            self.device
+                .ash_device
+                .destroy_query_pool(self.vk_query_pool, None);
+        }
+    }
+}
+
+impl QueryPool {
+    pub fn builder(
+        device: Arc<Device>,
+        query_type: ash::vk::QueryType,
+        query_count: u32,
+    ) -> QueryPoolBuilder {
+        QueryPoolBuilder {
+            device,
+            query_type,
+            query_count,
+            pipeline_statistics: Default::default(),
+        }
+    }
+
+    // u64 words written per query, one per enabled statistic for pipeline statistics queries,
+    // plus one for the availability word
+    fn result_stride(&self, flags: ash::vk::QueryResultFlags) -> usize {
+        let values = match self.query_type {
+            ash::vk::QueryType::PIPELINE_STATISTICS => {
+                self.pipeline_statistics.as_raw().count_ones() as usize
+            }
+            ash::vk::QueryType::TRANSFORM_FEEDBACK_STREAM_EXT => 2,
+            _ => 1,
+        };
+        if flags.contains(ash::vk::QueryResultFlags::WITH_AVAILABILITY) {
+            values + 1
+        } else {
+            values
+        }
+    }
+
+    // `data` holds the results of `data.len() / stride` queries, see `result_stride`
+    pub fn get_results(
+        &self,
+        first_query: u32,
+        data: &mut [u64],
+        flags: ash::vk::QueryResultFlags,
+    ) -> Result<(), ash::vk::Result> {
+        let stride = self.result_stride(flags);
+        // MUST VUID-vkGetQueryPoolResults-dataSize-00817
+        if data.len() % stride != 0 {
+            panic!("VUID-vkGetQueryPoolResults-dataSize-00817");
+        }
+        let query_count = (data.len() / stride) as u32;
+        // MUST VUID-vkGetQueryPoolResults-firstQuery-00813
+        if first_query >= self.query_count {
+            panic!("VUID-vkGetQueryPoolResults-firstQuery-00813");
+        }
+        // MUST VUID-vkGetQueryPoolResults-firstQuery-00816
+        if first_query
+            .checked_add(query_count)
+            .map_or(true, |end| end > self.query_count)
+        {
+            panic!("VUID-vkGetQueryPoolResults-firstQuery-00816");
+        }
+        // DONE VUID-vkGetQueryPoolResults-flags-02827
+        // DONE VUID-vkGetQueryPoolResults-flags-00815
+        unsafe {
+            // Host Synchronization: none
+            (self.device.ash_device.fp_v1_0().get_query_pool_results)(
+                self.device.ash_device.handle(),
+                self.vk_query_pool,
+                first_query,
+                query_count,
+                std::mem::size_of_val(data),
+                data.as_mut_ptr().cast(),
+                (stride * std::mem::size_of::<u64>()) as _,
+                flags | ash::vk::QueryResultFlags::TYPE_64,
+            )
+            .result()
+        }
+    }
+
+    // Reset queries from the host, without recording `cmd_reset_query_pool`
+    // DONE VUID-vkResetQueryPool-None-02665
+    pub fn reset(
+        &self,
+        first_query: u32,
+        query_count: u32,
+        _feature: &Feature<{ HostQueryReset.into() }>,
+    ) {
+        // MUST VUID-vkResetQueryPool-firstQuery-02666
+        if first_query >= self.query_count {
+            panic!("VUID-vkResetQueryPool-firstQuery-02666");
+        }
+        // MUST VUID-vkResetQueryPool-firstQuery-02667
+        if first_query + query_count > self.query_count {
+            panic!("VUID-vkResetQueryPool-firstQuery-02667");
+        }
+        // TODO VUID-vkResetQueryPool-firstQuery-02741
+        // TODO VUID-vkResetQueryPool-firstQuery-02742
+        unsafe {
+            // Host Synchronization: none
+            self.device
+                .ash_device
+                .reset_query_pool(self.vk_query_pool, first_query, query_count);
+        }
+    }
+}
+
+pub struct QueryPoolBuilder {
+    device: Arc<Device>,
+    query_type: ash::vk::QueryType,
+    query_count: u32,
+    pipeline_statistics: ash::vk::QueryPipelineStatisticFlags,
+}
+
+impl QueryPoolBuilder {
+    pub fn pipeline_statistics(
+        mut self,
+        pipeline_statistics: ash::vk::QueryPipelineStatisticFlags,
+    ) -> Self {
+        self.pipeline_statistics = pipeline_statistics;
+        self
+    }
+
+    pub fn build(self) -> Result<Arc<QueryPool>, ash::vk::Result> {
+        // TODO VUID-VkQueryPoolCreateInfo-queryType-00791
+        // MUST VUID-VkQueryPoolCreateInfo-queryCount-02763
+        if self.query_count == 0 {
+            panic!("VUID-VkQueryPoolCreateInfo-queryCount-02763");
+        }
+        let create_info = ash::vk::QueryPoolCreateInfo::builder()
+            .query_type(self.query_type)
+            .query_count(self.query_count)
+            .pipeline_statistics(self.pipeline_statistics)
+            .build();
+        unsafe {
+            // Host Synchronization: none
+            let vk_query_pool = self
+                .device
+                .ash_device
+                .create_query_pool(&create_info, None)?;
+            Ok(Arc::new(QueryPool {
+                device: self.device,
+                vk_query_pool,
+                query_type: self.query_type,
+                query_count: self.query_count,
+                pipeline_statistics: self.pipeline_statistics,
+            }))
+        }
+    }
+}
+
+// a timestamp scope of a `GpuProfiler`, read by `GpuProfiler::get_elapsed`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TimestampId {
+    // number of `begin_frame` calls when the scope began
+    frame_count: u64,
+    query_pool: usize,
+    first_query: u32,
+}
+
+// Returned by `GpuProfiler::scope`, the end timestamp is written when it's dropped. Commands of
+// the scope are recorded through it, and nested scopes are opened by its `scope`.
+pub struct ScopedTimestamp<'a, const LEVEL: Level, const SCOPE: RenderPassScope> {
+    profiler: &'a mut GpuProfiler,
+    command_buffer: &'a mut CommandBuffer<LEVEL, { RECORDING }, SCOPE>,
+    id: TimestampId,
+}
+
+impl<'a, const LEVEL: Level, const SCOPE: RenderPassScope> ScopedTimestamp<'a, LEVEL, SCOPE> {
+    pub fn id(&self) -> TimestampId {
+        self.id
+    }
+    pub fn scope(&mut self, name: &str) -> ScopedTimestamp<LEVEL, SCOPE> {
+        self.profiler.scope(self.command_buffer, name)
+    }
+}
+
+impl<'a, const LEVEL: Level, const SCOPE: RenderPassScope> Deref
+    for ScopedTimestamp<'a, LEVEL, SCOPE>
+{
+    type Target = CommandBuffer<LEVEL, { RECORDING }, SCOPE>;
+
+    fn deref(&self) -> &Self::Target {
+        self.command_buffer
+    }
+}
+
+impl<'a, const LEVEL: Level, const SCOPE: RenderPassScope> DerefMut
+    for ScopedTimestamp<'a, LEVEL, SCOPE>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.command_buffer
+    }
+}
+
+impl<'a, const LEVEL: Level, const SCOPE: RenderPassScope> Drop
+    for ScopedTimestamp<'a, LEVEL, SCOPE>
+{
+    fn drop(&mut self) {
+        self.profiler.end_scope(self.command_buffer);
+    }
+}
+
+impl<const LEVEL: Level> CommandBuffer<LEVEL, { RECORDING }, { OUTSIDE }> {
+    // DONE VUID-vkCmdResetQueryPool-commandBuffer-recording
+    // DONE VUID-vkCmdResetQueryPool-renderpass
+    pub fn cmd_reset_query_pool(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        first_query: u32,
+        query_count: u32,
+    ) {
+        // MUST VUID-vkCmdResetQueryPool-firstQuery-00796
+        if first_query >= query_pool.query_count {
+            panic!("VUID-vkCmdResetQueryPool-firstQuery-00796");
+        }
+        // MUST VUID-vkCmdResetQueryPool-firstQuery-00797
+        if first_query + query_count > query_pool.query_count {
+            panic!("VUID-vkCmdResetQueryPool-firstQuery-00797");
+        }
+        // TODO VUID-vkCmdResetQueryPool-None-02841
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ash_device.cmd_reset_query_pool(
+                self.vk_command_buffer,
+                query_pool.vk_query_pool,
+                first_query,
+                query_count,
+            );
+        }
+        self.holding_resources
+            .query_pools
+            .insert(query_pool.vk_query_pool.as_raw(), query_pool);
+    }
+}
+
+impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RECORDING }, SCOPE> {
+    // DONE VUID-vkCmdWriteTimestamp-commandBuffer-recording
+    pub fn cmd_write_timestamp(
+        &mut self,
+        pipeline_stage: ash::vk::PipelineStageFlags,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+    ) {
+        // MUST VUID-vkCmdWriteTimestamp-queryPool-01416
+        if query_pool.query_type != ash::vk::QueryType::TIMESTAMP {
+            panic!("VUID-vkCmdWriteTimestamp-queryPool-01416");
+        }
+        // MUST VUID-vkCmdWriteTimestamp-query-04904
+        if query >= query_pool.query_count {
+            panic!("VUID-vkCmdWriteTimestamp-query-04904");
+        }
+        // TODO VUID-vkCmdWriteTimestamp-None-00830
+        // TODO VUID-vkCmdWriteTimestamp-timestampValidBits-00829
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            self.device.ash_device.cmd_write_timestamp(
+                self.vk_command_buffer,
+                pipeline_stage,
+                query_pool.vk_query_pool,
+                query,
+            );
+        }
+        self.holding_resources
+            .query_pools
+            .insert(query_pool.vk_query_pool.as_raw(), query_pool);
+    }
+}
+
+pub struct ScopeTiming {
+    pub name: String,
+    // nesting level, 0 for scopes not inside another scope
+    pub depth: u32,
+    pub milliseconds: f64,
+}
+
+struct ProfilerScope {
+    name: String,
+    depth: u32,
+    first_query: u32,
+}
+
+// Timestamp scopes over a ring of query pools, one per frame in flight. Call `begin_frame` first
+// in each frame after waiting the fence of the frame recorded `frames_in_flight` frames ago, it
+// returns the timings of that frame and resets its queries.
+pub struct GpuProfiler {
+    query_pools: Vec<Arc<QueryPool>>,
+    scopes: Vec<Vec<ProfilerScope>>,
+    open_scopes: Vec<usize>,
+    frame: usize,
+    frame_count: u64,
+    next_query: u32,
+    timestamp_period: f32,
+}
+
+impl GpuProfiler {
+    pub fn new(
+        device: Arc<Device>,
+        frames_in_flight: u32,
+        max_scopes: u32,
+    ) -> Result<GpuProfiler, ash::vk::Result> {
+        if frames_in_flight == 0 {
+            panic!("frames_in_flight must be greater than 0");
+        }
+        let timestamp_period = device
+            .physical_device
+            .get_physical_device_properties()
+            .limits
+            .timestamp_period;
+        let query_pools = (0..frames_in_flight)
+            .map(|_| {
+                QueryPool::builder(
+                    device.clone(),
+                    ash::vk::QueryType::TIMESTAMP,
+                    max_scopes * 2,
+                )
+                .build()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(GpuProfiler {
+            scopes: query_pools.iter().map(|_| Vec::new()).collect(),
+            query_pools,
+            open_scopes: Vec::new(),
+            // the first `begin_frame` moves to slot 0
+            frame: frames_in_flight as usize - 1,
+            frame_count: 0,
+            next_query: 0,
+            timestamp_period,
+        })
+    }
+
+    pub fn begin_frame<const LEVEL: Level>(
+        &mut self,
+        command_buffer: &mut CommandBuffer<LEVEL, { RECORDING }, { OUTSIDE }>,
+    ) -> Result<Vec<ScopeTiming>, ash::vk::Result> {
+        if !self.open_scopes.is_empty() {
+            panic!("scope of the last frame is not ended");
+        }
+        self.frame = (self.frame + 1) % self.query_pools.len();
+        self.frame_count += 1;
+        let query_pool = self.query_pools[self.frame].clone();
+        let scopes = std::mem::take(&mut self.scopes[self.frame]);
+        let mut timings = Vec::with_capacity(scopes.len());
+        if !scopes.is_empty() {
+            let mut timestamps = vec![0u64; scopes.len() * 2];
+            query_pool.get_results(0, &mut timestamps, ash::vk::QueryResultFlags::WAIT)?;
+            for scope in scopes {
+                let begin = timestamps[scope.first_query as usize];
+                let end = timestamps[scope.first_query as usize + 1];
+                let ticks = end.wrapping_sub(begin);
+                timings.push(ScopeTiming {
+                    name: scope.name,
+                    depth: scope.depth,
+                    milliseconds: ticks as f64 * self.timestamp_period as f64 / 1_000_000.0,
+                });
+            }
+        }
+        let query_count = query_pool.query_count;
+        command_buffer.cmd_reset_query_pool(query_pool, 0, query_count);
+        self.next_query = 0;
+        Ok(timings)
+    }
+
+    pub fn begin_scope<const LEVEL: Level, const SCOPE: RenderPassScope>(
+        &mut self,
+        command_buffer: &mut CommandBuffer<LEVEL, { RECORDING }, SCOPE>,
+        name: &str,
+    ) -> TimestampId {
+        let query_pool = self.query_pools[self.frame].clone();
+        // MUST VUID-vkCmdWriteTimestamp-query-04904
+        if self.next_query + 1 >= query_pool.query_count {
+            panic!("VUID-vkCmdWriteTimestamp-query-04904");
+        }
+        command_buffer.cmd_write_timestamp(
+            ash::vk::PipelineStageFlags::TOP_OF_PIPE,
+            query_pool,
+            self.next_query,
+        );
+        let scopes = &mut self.scopes[self.frame];
+        self.open_scopes.push(scopes.len());
+        scopes.push(ProfilerScope {
+            name: name.to_string(),
+            depth: self.open_scopes.len() as u32 - 1,
+            first_query: self.next_query,
+        });
+        let id = TimestampId {
+            frame_count: self.frame_count,
+            query_pool: self.frame,
+            first_query: self.next_query,
+        };
+        self.next_query += 2;
+        id
+    }
+
+    // `begin_scope` with the `end_scope` done when the returned scope is dropped, so the
+    // queries of a scope can't be mixed up or left without an end timestamp
+    pub fn scope<'a, const LEVEL: Level, const SCOPE: RenderPassScope>(
+        &'a mut self,
+        command_buffer: &'a mut CommandBuffer<LEVEL, { RECORDING }, SCOPE>,
+        name: &str,
+    ) -> ScopedTimestamp<'a, LEVEL, SCOPE> {
+        let id = self.begin_scope(command_buffer, name);
+        ScopedTimestamp {
+            profiler: self,
+            command_buffer,
+            id,
+        }
+    }
+
+    // Wait for both timestamps of the scope and return the time elapsed between them, the
+    // queries are reset by the `begin_frame` which reuses the query pool of the scope
+    pub fn get_elapsed(&self, id: TimestampId) -> Result<Duration, ash::vk::Result> {
+        if id.frame_count + self.query_pools.len() as u64 <= self.frame_count {
+            panic!("queries of the timestamp scope are already reset");
+        }
+        let mut timestamps = [0u64; 2];
+        self.query_pools[id.query_pool].get_results(
+            id.first_query,
+            &mut timestamps,
+            ash::vk::QueryResultFlags::WAIT,
+        )?;
+        let ticks = timestamps[1].wrapping_sub(timestamps[0]);
+        Ok(Duration::from_nanos(
+            (ticks as f64 * self.timestamp_period as f64) as u64,
+        ))
+    }
+
+    // end the innermost open scope
+    pub fn end_scope<const LEVEL: Level, const SCOPE: RenderPassScope>(
+        &mut self,
+        command_buffer: &mut CommandBuffer<LEVEL, { RECORDING }, SCOPE>,
+    ) {
+        let index = self.open_scopes.pop().expect("no open scope to end");
+        let first_query = self.scopes[self.frame][index].first_query;
+        command_buffer.cmd_write_timestamp(
+            ash::vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+            self.query_pools[self.frame].clone(),
+            first_query + 1,
+        );
+    }
+}
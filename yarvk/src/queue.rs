@@ -3,12 +3,16 @@ use crate::command::command_buffer::Level::PRIMARY;
 use crate::command::command_buffer::RenderPassScope::OUTSIDE;
 use crate::command::command_buffer::State::{EXECUTABLE, INVALID};
 use crate::device::Device;
+use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceVulkan11Features::ProtectedMemory;
 use crate::fence::{SignalingFence, UnsignaledFence};
+use crate::physical_device::queue_falmily_properties::QueueFamilyProperties;
 
 use crate::pipeline::pipeline_stage_flags::PipelineStageFlags;
 use crate::semaphore::Semaphore;
 use parking_lot::RwLockReadGuard;
 use std::cell::Cell;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 #[derive(Default)]
@@ -18,6 +22,7 @@ pub struct SubmitInfo {
     command_buffers: Vec<CommandBuffer<{ PRIMARY }, { EXECUTABLE }, { OUTSIDE }>>,
     onetime_submit_command_buffers: Vec<CommandBuffer<{ PRIMARY }, { EXECUTABLE }, { OUTSIDE }>>,
     invalid_buffers: Vec<CommandBuffer<{ PRIMARY }, { INVALID }, { OUTSIDE }>>,
+    protected: bool,
 }
 
 impl SubmitInfo {
@@ -65,6 +70,12 @@ impl SubmitInfo {
     pub fn add_signal_semaphore(&mut self, signal_semaphore: Arc<Semaphore>) {
         self.signal_semaphores.push(signal_semaphore);
     }
+    // submit as a protected submission, all command buffers must be allocated from protected
+    // command pools and the queue must be protected-capable
+    // DONE VUID-VkProtectedSubmitInfo-protectedSubmit-01816
+    pub fn protected(&mut self, _feature: &Feature<{ ProtectedMemory.into() }>) {
+        self.protected = true;
+    }
 }
 
 // fn is_stage_supported(stage_flags: ash::vk::PipelineStageFlags, queue_flags: ash::vk::QueueFlags) -> bool {
@@ -107,7 +118,9 @@ impl SubmitInfo {
 pub struct Queue {
     pub(crate) device: Arc<Device>,
     pub(crate) vk_queue: ash::vk::Queue,
-    // pub(crate) queue_family_property: ash::vk::QueueFamilyProperties,
+    pub queue_family: QueueFamilyProperties,
+    // created with ash::vk::DeviceQueueCreateFlags::PROTECTED
+    pub protected: bool,
 }
 
 impl Queue {
@@ -124,7 +137,7 @@ impl Queue {
 
     pub fn submit(
         &mut self,
-        fence: UnsignaledFence,
+        mut fence: UnsignaledFence,
         mut submit_infos: Vec<SubmitInfo>,
     ) -> Result<SignalingFence<Vec<SubmitInfo>>, ash::vk::Result> {
         // Host Synchronization: queue fence
@@ -141,7 +154,43 @@ impl Queue {
                 mut ash_vk_signal_semaphores,
                 mut ash_vk_command_buffers,
             ) = local.take();
+            let protected_submit_info = ash::vk::ProtectedSubmitInfo::builder()
+                .protected_submit(true)
+                .build();
             for submit_info in &submit_infos {
+                // MUST VUID-vkQueueSubmit-queue-06448
+                if submit_info.protected && !self.protected {
+                    panic!("VUID-vkQueueSubmit-queue-06448");
+                }
+                for buffer in submit_info
+                    .onetime_submit_command_buffers
+                    .iter()
+                    .chain(&submit_info.command_buffers)
+                {
+                    // MUST VUID-VkSubmitInfo-pNext-04148
+                    if submit_info.protected && !buffer.command_pool.protected {
+                        panic!("VUID-VkSubmitInfo-pNext-04148");
+                    }
+                    // MUST VUID-VkSubmitInfo-pNext-04120
+                    if !submit_info.protected && buffer.command_pool.protected {
+                        panic!("VUID-VkSubmitInfo-pNext-04120");
+                    }
+                    // the pool lock keeps the pool from being reset between the check and
+                    // counting the command buffer as pending
+                    let _pool = buffer.command_pool.vk_command_pool.read();
+                    // MUST VUID-vkQueueSubmit-pCommandBuffers-00072
+                    if buffer.is_reset_by_pool() {
+                        panic!("VUID-vkQueueSubmit-pCommandBuffers-00072");
+                    }
+                    buffer
+                        .command_pool
+                        .pending_count
+                        .fetch_add(1, Ordering::Acquire);
+                    fence
+                        .0
+                        .pending_command_pools
+                        .push(buffer.command_pool.clone());
+                }
                 for (semaphore, masks) in &submit_info.wait_semaphores {
                     let lock = semaphore.ash_vk_semaphore.read();
                     ash_vk_wait_semaphores.push(*lock);
@@ -159,23 +208,34 @@ impl Queue {
                     ash_vk_signal_semaphores.push(*lock);
                     semaphore_locks.push(lock);
                 }
-                let submit_info = ash::vk::SubmitInfo::builder()
+                let submit_info_ash = ash::vk::SubmitInfo::builder()
                     .wait_semaphores(ash_vk_wait_semaphores.as_slice())
                     .wait_dst_stage_mask(ash_vk_wait_dst_stage_masks.as_slice())
                     .command_buffers(ash_vk_command_buffers.as_slice())
-                    .signal_semaphores(ash_vk_signal_semaphores.as_slice())
-                    .build();
-                vk_submit_infos.push(submit_info);
+                    .signal_semaphores(ash_vk_signal_semaphores.as_slice());
+                let mut submit_info_ash = submit_info_ash.build();
+                if submit_info.protected {
+                    submit_info_ash.p_next =
+                        &protected_submit_info as *const _ as *const std::ffi::c_void;
+                }
+                vk_submit_infos.push(submit_info_ash);
             }
 
-            unsafe {
+            let _queues = self.device.queues_lock.read();
+            let result = unsafe {
                 // Host Synchronization: queue fence
                 self.device.ash_device.queue_submit(
                     self.vk_queue,
                     vk_submit_infos.as_slice(),
                     fence.vk_fence,
-                )?
+                )
             };
+            if let Err(error) = result {
+                for command_pool in fence.0.pending_command_pools.drain(..) {
+                    command_pool.pending_count.fetch_sub(1, Ordering::Release);
+                }
+                return Err(error);
+            }
             semaphore_locks.clear();
             vk_submit_infos.clear();
             ash_vk_wait_semaphores.clear();
@@ -208,6 +268,7 @@ impl Queue {
     }
 
     pub fn wait_idle(&mut self) -> Result<(), ash::vk::Result> {
+        let _queues = self.device.queues_lock.read();
         unsafe {
             // Host Synchronization: queue
             self.device.ash_device.queue_wait_idle(self.vk_queue)?;
@@ -46,9 +46,21 @@ impl RenderPassBuilder {
         // DONE VUID-VkRenderPassCreateInfo-dstSubpass-02518
         let vk_attachments = self
             .attachments
-            .into_iter()
+            .iter()
             .map(|attachment| attachment.0)
             .collect::<Vec<_>>();
+        let color_attachment_counts = self
+            .subpasses
+            .iter()
+            .map(|subpass| subpass.color_attachments.len() as u32)
+            .collect::<Vec<_>>();
+        if self
+            .subpasses
+            .iter()
+            .any(|subpass| subpass.fragment_shading_rate_attachment.is_some())
+        {
+            return self.build2(vk_attachments, color_attachment_counts);
+        }
         let vk_subpasses: Vec<_> = self
             .subpasses
             .iter()
@@ -74,6 +86,97 @@ impl RenderPassBuilder {
             Ok(Arc::new(RenderPass {
                 device: self.device,
                 vk_attachments,
+                color_attachment_counts,
+                ash_vk_renderpass: vk_renderpass,
+            }))
+        }
+    }
+    // structures only available in vkCreateRenderPass2 (e.g. fragment shading rate attachments)
+    fn build2(
+        self,
+        vk_attachments: Vec<ash::vk::AttachmentDescription>,
+        color_attachment_counts: Vec<u32>,
+    ) -> Result<Arc<RenderPass>, ash::vk::Result> {
+        let vk_attachments2 = vk_attachments
+            .iter()
+            .map(|attachment| {
+                ash::vk::AttachmentDescription2::builder()
+                    .flags(attachment.flags)
+                    .format(attachment.format)
+                    .samples(attachment.samples)
+                    .load_op(attachment.load_op)
+                    .store_op(attachment.store_op)
+                    .stencil_load_op(attachment.stencil_load_op)
+                    .stencil_store_op(attachment.stencil_store_op)
+                    .initial_layout(attachment.initial_layout)
+                    .final_layout(attachment.final_layout)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        let subpass_attachments = self
+            .subpasses
+            .iter()
+            .map(|subpass| subpass.attachments2(vk_attachments.as_slice()))
+            .collect::<Vec<_>>();
+        let mut fragment_shading_rate_infos = self
+            .subpasses
+            .iter()
+            .zip(&subpass_attachments)
+            .map(|(subpass, attachments)| {
+                match (
+                    &subpass.fragment_shading_rate_attachment,
+                    &attachments.fragment_shading_rate_attachment,
+                ) {
+                    (Some((_, texel_size)), Some(attachment)) => Some(
+                        ash::vk::FragmentShadingRateAttachmentInfoKHR::builder()
+                            .fragment_shading_rate_attachment(attachment)
+                            .shading_rate_attachment_texel_size(*texel_size)
+                            .build(),
+                    ),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        let vk_subpasses = self
+            .subpasses
+            .iter()
+            .zip(&subpass_attachments)
+            .zip(fragment_shading_rate_infos.iter_mut())
+            .map(|((subpass, attachments), fragment_shading_rate_info)| {
+                let mut builder = subpass.to_ash2(attachments);
+                if let Some(fragment_shading_rate_info) = fragment_shading_rate_info {
+                    builder = builder.push_next(fragment_shading_rate_info);
+                }
+                builder.build()
+            })
+            .collect::<Vec<_>>();
+        let vk_subpass_dependencies = self
+            .subpass_dependencies
+            .iter()
+            .map(|subpass_dependency| subpass_dependency.to_ash2())
+            .collect::<Vec<_>>();
+        let create_info = ash::vk::RenderPassCreateInfo2::builder()
+            .flags(self.flags)
+            .attachments(vk_attachments2.as_slice())
+            .subpasses(vk_subpasses.as_slice())
+            .dependencies(vk_subpass_dependencies.as_slice());
+        unsafe {
+            // Host Synchronization: none
+            // the core command is only loaded for 1.2 devices
+            let vk_renderpass =
+                if self.device.physical_device.instance.api_version >= ash::vk::API_VERSION_1_2 {
+                    self.device
+                        .ash_device
+                        .create_render_pass2(&create_info, None)?
+                } else {
+                    self.device
+                        .create_render_pass2_loader()
+                        .create_render_pass2(&create_info, None)?
+                };
+            Ok(Arc::new(RenderPass {
+                device: self.device,
+                vk_attachments,
+                color_attachment_counts,
                 ash_vk_renderpass: vk_renderpass,
             }))
         }
@@ -83,6 +186,7 @@ impl RenderPassBuilder {
 pub struct RenderPass {
     pub device: Arc<Device>,
     pub(crate) vk_attachments: Vec<ash::vk::AttachmentDescription>,
+    pub(crate) color_attachment_counts: Vec<u32>,
     pub(crate) ash_vk_renderpass: ash::vk::RenderPass,
 }
 
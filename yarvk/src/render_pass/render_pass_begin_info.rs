@@ -3,10 +3,44 @@ use crate::command::command_buffer::Level::PRIMARY;
 use crate::command::command_buffer::RenderPassScope::{INSIDE, OUTSIDE};
 use crate::command::command_buffer::State::RECORDING;
 use crate::frame_buffer::Framebuffer;
+use crate::image::image_view::ImageView;
 use crate::render_pass::RenderPass;
-use ash::vk::{ClearValue, Rect2D};
+use ash::vk::{ClearValue, Handle, Rect2D};
 use std::sync::Arc;
 
+// pick the constructor matching the numeric format of the attachment, float for UNORM, SNORM,
+// SRGB and SFLOAT formats, u32 for UINT and i32 for SINT formats
+pub trait ClearValueExt {
+    fn color_f32(color: [f32; 4]) -> Self;
+    fn color_u32(color: [u32; 4]) -> Self;
+    fn color_i32(color: [i32; 4]) -> Self;
+    fn depth_stencil(depth: f32, stencil: u32) -> Self;
+}
+
+impl ClearValueExt for ClearValue {
+    fn color_f32(color: [f32; 4]) -> Self {
+        ClearValue {
+            color: ash::vk::ClearColorValue { float32: color },
+        }
+    }
+    fn color_u32(color: [u32; 4]) -> Self {
+        ClearValue {
+            color: ash::vk::ClearColorValue { uint32: color },
+        }
+    }
+    fn color_i32(color: [i32; 4]) -> Self {
+        ClearValue {
+            color: ash::vk::ClearColorValue { int32: color },
+        }
+    }
+    fn depth_stencil(depth: f32, stencil: u32) -> Self {
+        // TODO VUID-VkClearDepthStencilValue-depth-00022 depends on VK_EXT_depth_range_unrestricted
+        ClearValue {
+            depth_stencil: ash::vk::ClearDepthStencilValue { depth, stencil },
+        }
+    }
+}
+
 pub struct RenderPassBeginInfoBuilder {
     inner: RenderPassBeginInfo,
 }
@@ -20,7 +54,46 @@ impl RenderPassBeginInfoBuilder {
         self.inner.clear_values.push(clear_value);
         self
     }
+    // the image views of an imageless framebuffer, in attachment order
+    pub fn attachments(mut self, attachments: &[Arc<ImageView>]) -> Self {
+        let image_infos = &self.inner.framebuffer.attachment_image_infos;
+        // MUST VUID-VkRenderPassBeginInfo-framebuffer-03207
+        if image_infos.is_empty() {
+            panic!("VUID-VkRenderPassBeginInfo-framebuffer-03207");
+        }
+        // MUST VUID-VkRenderPassBeginInfo-framebuffer-03208
+        if attachments.len() != image_infos.len() {
+            panic!("VUID-VkRenderPassBeginInfo-framebuffer-03208");
+        }
+        for (image_view, image_info) in attachments.iter().zip(image_infos) {
+            let image_create_info = &image_view.image.image_create_info;
+            // MUST VUID-VkRenderPassBeginInfo-framebuffer-03210
+            if image_create_info.flags != image_info.flags {
+                panic!("VUID-VkRenderPassBeginInfo-framebuffer-03210");
+            }
+            // MUST VUID-VkRenderPassBeginInfo-framebuffer-04627
+            if !image_create_info.usage.contains(image_info.usage) {
+                panic!("VUID-VkRenderPassBeginInfo-framebuffer-04627");
+            }
+            // TODO VUID-VkRenderPassBeginInfo-framebuffer-03211
+            // TODO VUID-VkRenderPassBeginInfo-framebuffer-03212
+            // TODO VUID-VkRenderPassBeginInfo-framebuffer-03213
+            // TODO VUID-VkRenderPassBeginInfo-framebuffer-03214
+        }
+        self.inner.ash_vk_attachments = attachments
+            .iter()
+            .map(|image_view| image_view.ash_vk_image_view)
+            .collect();
+        self.inner.attachments = attachments.to_vec();
+        self
+    }
     pub fn build(self) -> RenderPassBeginInfo {
+        // MUST VUID-VkRenderPassBeginInfo-framebuffer-03207
+        if !self.inner.framebuffer.attachment_image_infos.is_empty()
+            && self.inner.attachments.is_empty()
+        {
+            panic!("VUID-VkRenderPassBeginInfo-framebuffer-03207");
+        }
         self.inner
     }
 }
@@ -31,6 +104,8 @@ pub struct RenderPassBeginInfo {
     render_area: Rect2D,
     // DONE VUID-VkRenderPassBeginInfo-clearValueCount-04962
     clear_values: Vec<ClearValue>,
+    attachments: Vec<Arc<ImageView>>,
+    ash_vk_attachments: Vec<ash::vk::ImageView>,
 }
 
 impl RenderPassBeginInfo {
@@ -44,6 +119,8 @@ impl RenderPassBeginInfo {
                 framebuffer,
                 render_area: Default::default(),
                 clear_values: vec![],
+                attachments: vec![],
+                ash_vk_attachments: vec![],
             },
         }
     }
@@ -54,6 +131,18 @@ impl RenderPassBeginInfo {
             .render_area(self.render_area)
             .clear_values(self.clear_values.as_slice())
     }
+    pub(crate) fn ash_attachment_begin_info(
+        &self,
+    ) -> Option<ash::vk::RenderPassAttachmentBeginInfo> {
+        if self.ash_vk_attachments.is_empty() {
+            return None;
+        }
+        Some(
+            ash::vk::RenderPassAttachmentBeginInfo::builder()
+                .attachments(self.ash_vk_attachments.as_slice())
+                .build(),
+        )
+    }
 }
 
 impl CommandBuffer<{ PRIMARY }, { RECORDING }, { OUTSIDE }> {
@@ -67,15 +156,25 @@ impl CommandBuffer<{ PRIMARY }, { RECORDING }, { OUTSIDE }> {
     ) where
         F: FnOnce(&mut CommandBuffer<{ PRIMARY }, { RECORDING }, { INSIDE }>),
     {
+        let attachment_begin_info = create_info.ash_attachment_begin_info();
+        let mut begin_info = create_info.ash_builder().build();
+        if let Some(attachment_begin_info) = &attachment_begin_info {
+            begin_info.p_next = attachment_begin_info as *const _ as *const std::ffi::c_void;
+        }
         unsafe {
             // Host Synchronization: commandBuffer, VkCommandPool
             let _pool = self.command_pool.vk_command_pool.write();
             self.device.ash_device.cmd_begin_render_pass(
                 self.vk_command_buffer,
-                &create_info.ash_builder().build(),
+                &begin_info,
                 contents,
             );
         }
+        for image_view in &create_info.attachments {
+            self.holding_resources
+                .image_views
+                .insert(image_view.ash_vk_image_view.as_raw(), image_view.clone());
+        }
         f(unsafe {
             &mut *(self as *mut Self as *mut CommandBuffer<{ PRIMARY }, { RECORDING }, { INSIDE }>)
         });
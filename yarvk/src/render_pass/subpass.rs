@@ -1,3 +1,6 @@
+use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceFragmentShadingRateFeaturesKHR::AttachmentFragmentShadingRate;
+use crate::image::format_aspect_mask;
 use crate::pipeline::pipeline_stage_flags::PipelineStageFlags;
 use crate::render_pass::attachment::{AttachmentIndex, AttachmentReference};
 
@@ -56,6 +59,17 @@ impl SubpassDependency {
     pub fn builder() -> SubpassDependencyBuilder {
         SubpassDependencyBuilder::default()
     }
+    pub(crate) fn to_ash2(&self) -> ash::vk::SubpassDependency2 {
+        ash::vk::SubpassDependency2::builder()
+            .src_subpass(self.src_subpass)
+            .dst_subpass(self.dst_subpass)
+            .src_access_mask(self.src_access_mask)
+            .dst_access_mask(self.dst_access_mask)
+            .src_stage_mask(self.src_stage_mask)
+            .dst_stage_mask(self.dst_stage_mask)
+            .dependency_flags(self.dependency_flags)
+            .build()
+    }
     pub fn ash_builder(&self) -> ash::vk::SubpassDependencyBuilder {
         ash::vk::SubpassDependency::builder()
             .src_subpass(self.src_subpass)
@@ -72,13 +86,89 @@ pub struct SubpassDescription {
     flags: ash::vk::SubpassDescriptionFlags,
     pipeline_bind_point: ash::vk::PipelineBindPoint,
     input_attachments: Vec<ash::vk::AttachmentReference>,
-    color_attachments: Vec<ash::vk::AttachmentReference>,
+    pub(crate) color_attachments: Vec<ash::vk::AttachmentReference>,
     resolve_attachments: Vec<ash::vk::AttachmentReference>,
     depth_stencil_attachment: Option<ash::vk::AttachmentReference>,
     preserve_attachments: Vec<u32>,
+    pub(crate) fragment_shading_rate_attachment:
+        Option<(ash::vk::AttachmentReference, ash::vk::Extent2D)>,
+}
+
+// attachment references of a subpass for vkCreateRenderPass2
+pub(crate) struct SubpassAttachments2 {
+    pub input_attachments: Vec<ash::vk::AttachmentReference2>,
+    pub color_attachments: Vec<ash::vk::AttachmentReference2>,
+    pub resolve_attachments: Vec<ash::vk::AttachmentReference2>,
+    pub depth_stencil_attachment: Option<ash::vk::AttachmentReference2>,
+    pub fragment_shading_rate_attachment: Option<ash::vk::AttachmentReference2>,
+}
+
+fn to_ash2(
+    reference: &ash::vk::AttachmentReference,
+    aspect_mask: ash::vk::ImageAspectFlags,
+) -> ash::vk::AttachmentReference2 {
+    ash::vk::AttachmentReference2::builder()
+        .attachment(reference.attachment)
+        .layout(reference.layout)
+        .aspect_mask(aspect_mask)
+        .build()
 }
 
 impl SubpassDescription {
+    pub(crate) fn attachments2(
+        &self,
+        attachments: &[ash::vk::AttachmentDescription],
+    ) -> SubpassAttachments2 {
+        SubpassAttachments2 {
+            // only the aspect mask of input attachments is used
+            input_attachments: self
+                .input_attachments
+                .iter()
+                .map(|reference| {
+                    let aspect_mask = attachments
+                        .get(reference.attachment as usize)
+                        .map_or(ash::vk::ImageAspectFlags::COLOR, |attachment| {
+                            format_aspect_mask(attachment.format)
+                        });
+                    to_ash2(reference, aspect_mask)
+                })
+                .collect(),
+            color_attachments: self
+                .color_attachments
+                .iter()
+                .map(|reference| to_ash2(reference, Default::default()))
+                .collect(),
+            resolve_attachments: self
+                .resolve_attachments
+                .iter()
+                .map(|reference| to_ash2(reference, Default::default()))
+                .collect(),
+            depth_stencil_attachment: self
+                .depth_stencil_attachment
+                .as_ref()
+                .map(|reference| to_ash2(reference, Default::default())),
+            fragment_shading_rate_attachment: self
+                .fragment_shading_rate_attachment
+                .as_ref()
+                .map(|(reference, _)| to_ash2(reference, Default::default())),
+        }
+    }
+    pub(crate) fn to_ash2<'a>(
+        &'a self,
+        attachments: &'a SubpassAttachments2,
+    ) -> ash::vk::SubpassDescription2Builder<'a> {
+        let mut builder = ash::vk::SubpassDescription2::builder()
+            .flags(self.flags)
+            .pipeline_bind_point(self.pipeline_bind_point)
+            .input_attachments(attachments.input_attachments.as_slice())
+            .color_attachments(attachments.color_attachments.as_slice())
+            .resolve_attachments(attachments.resolve_attachments.as_slice())
+            .preserve_attachments(self.preserve_attachments.as_slice());
+        if let Some(depth_stencil_attachment) = &attachments.depth_stencil_attachment {
+            builder = builder.depth_stencil_attachment(depth_stencil_attachment);
+        }
+        builder
+    }
     pub fn builder() -> SubpassDescriptionBuilder {
         SubpassDescriptionBuilder::default()
     }
@@ -105,6 +195,7 @@ pub struct SubpassDescriptionBuilder {
     color_with_resolve_attachments: Vec<(AttachmentReference, AttachmentReference)>,
     depth_stencil_attachment: Option<AttachmentReference>,
     preserve_attachments: Vec<AttachmentIndex>,
+    fragment_shading_rate_attachment: Option<(AttachmentReference, ash::vk::Extent2D)>,
 }
 
 impl SubpassDescriptionBuilder {
@@ -151,6 +242,32 @@ impl SubpassDescriptionBuilder {
         self
     }
 
+    // the render pass is created by vkCreateRenderPass2 if any subpass has this attachment
+    pub fn fragment_shading_rate_attachment(
+        mut self,
+        _feature: Feature<{ AttachmentFragmentShadingRate.into() }>,
+        attachment: AttachmentReference,
+        shading_rate_attachment_texel_size: ash::vk::Extent2D,
+    ) -> Self {
+        // MUST VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04525
+        if !shading_rate_attachment_texel_size.width.is_power_of_two() {
+            panic!(
+                "VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04525"
+            );
+        }
+        // MUST VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04528
+        if !shading_rate_attachment_texel_size.height.is_power_of_two() {
+            panic!(
+                "VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04528"
+            );
+        }
+        // TODO VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04526
+        // TODO VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04529
+        self.fragment_shading_rate_attachment =
+            Some((attachment, shading_rate_attachment_texel_size));
+        self
+    }
+
     pub fn build(self) -> SubpassDescription {
         let input_attachments = self
             .input_attachments
@@ -189,8 +306,12 @@ impl SubpassDescriptionBuilder {
             resolve_attachments,
             depth_stencil_attachment,
             preserve_attachments,
+            fragment_shading_rate_attachment: self
+                .fragment_shading_rate_attachment
+                .map(|(attachment, texel_size)| (attachment.to_ash(), texel_size)),
         }
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct SubpassIndex(pub(crate) u32);
@@ -1,9 +1,19 @@
 use crate::device::Device;
+use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceFeatures::SamplerAnisotropy;
+use crate::device_features::PhysicalDeviceCustomBorderColorFeaturesEXT::{
+    CustomBorderColorWithoutFormat, CustomBorderColors,
+};
+use crate::device_features::PhysicalDeviceVulkan11Features;
+use crate::device_features::PhysicalDeviceVulkan12Features::SamplerFilterMinmax;
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::ExtCustomBorderColor;
 use std::sync::Arc;
 
 pub struct Sampler {
     pub device: Arc<Device>,
     pub(crate) ash_vk_sampler: ash::vk::Sampler,
+    pub ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
 }
 
 impl Drop for Sampler {
@@ -34,7 +44,10 @@ impl Sampler {
             min_lod: 0.0,
             max_lod: 0.0,
             border_color: Default::default(),
+            custom_border_color: None,
+            reduction_mode: None,
             unnormalized_coordinates: false,
+            ycbcr_conversion: None,
         }
     }
 }
@@ -54,7 +67,10 @@ pub struct SamplerBuilder {
     min_lod: f32,
     max_lod: f32,
     border_color: ash::vk::BorderColor,
+    custom_border_color: Option<ash::vk::SamplerCustomBorderColorCreateInfoEXT>,
+    reduction_mode: Option<ash::vk::SamplerReductionModeCreateInfo>,
     unnormalized_coordinates: bool,
+    ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
 }
 
 impl SamplerBuilder {
@@ -90,7 +106,12 @@ impl SamplerBuilder {
         self.mip_lod_bias = mip_lod_bias;
         self
     }
-    pub fn max_anisotropy(mut self, max_anisotropy: f32) -> Self {
+    // DONE VUID-VkSamplerCreateInfo-anisotropyEnable-01070
+    pub fn max_anisotropy(
+        mut self,
+        max_anisotropy: f32,
+        _feature: Feature<{ SamplerAnisotropy.into() }>,
+    ) -> Self {
         self.max_anisotropy = Some(max_anisotropy);
         self
     }
@@ -110,11 +131,131 @@ impl SamplerBuilder {
         self.border_color = border_color;
         self
     }
+    // `format` can be UNDEFINED if CustomBorderColorWithoutFormat is enabled
+    pub fn custom_border_color(
+        mut self,
+        _extension: DeviceExtension<{ ExtCustomBorderColor }>,
+        _feature: Feature<{ CustomBorderColors.into() }>,
+        color: [f32; 4],
+        format: ash::vk::Format,
+    ) -> Self {
+        // MUST VUID-VkSamplerCustomBorderColorCreateInfoEXT-format-04014
+        if format == ash::vk::Format::UNDEFINED
+            && !self
+                .device
+                .enabled_features
+                .contains(&CustomBorderColorWithoutFormat.into())
+        {
+            panic!("VUID-VkSamplerCustomBorderColorCreateInfoEXT-format-04014");
+        }
+        // DONE VUID-VkSamplerCreateInfo-borderColor-04011
+        self.border_color = ash::vk::BorderColor::FLOAT_CUSTOM_EXT;
+        self.custom_border_color = Some(
+            ash::vk::SamplerCustomBorderColorCreateInfoEXT::builder()
+                .custom_border_color(ash::vk::ClearColorValue { float32: color })
+                .format(format)
+                .build(),
+        );
+        self
+    }
+    // DONE VUID-VkSamplerCreateInfo-pNext-06726
+    pub fn reduction_mode(
+        mut self,
+        _feature: Feature<{ SamplerFilterMinmax.into() }>,
+        reduction_mode: ash::vk::SamplerReductionMode,
+    ) -> Self {
+        self.reduction_mode = Some(
+            ash::vk::SamplerReductionModeCreateInfo::builder()
+                .reduction_mode(reduction_mode)
+                .build(),
+        );
+        self
+    }
     pub fn unnormalized_coordinates(mut self) -> Self {
         self.unnormalized_coordinates = true;
         self
     }
-    pub fn build(self) -> Result<Arc<Sampler>, ash::vk::Result> {
+    pub fn ycbcr_conversion(mut self, ycbcr_conversion: Arc<SamplerYcbcrConversion>) -> Self {
+        self.ycbcr_conversion = Some(ycbcr_conversion);
+        self
+    }
+    pub fn build(mut self) -> Result<Arc<Sampler>, ash::vk::Result> {
+        let limits = self
+            .device
+            .physical_device
+            .get_physical_device_properties()
+            .limits;
+        // MUST VUID-VkSamplerCreateInfo-mipLodBias-01069
+        if self.mip_lod_bias.abs() > limits.max_sampler_lod_bias {
+            panic!("VUID-VkSamplerCreateInfo-mipLodBias-01069");
+        }
+        // MUST VUID-VkSamplerCreateInfo-maxLod-01973
+        if self.max_lod < self.min_lod {
+            panic!("VUID-VkSamplerCreateInfo-maxLod-01973");
+        }
+        // MUST VUID-VkSamplerCreateInfo-anisotropyEnable-01071
+        if let Some(max_anisotropy) = self.max_anisotropy {
+            if !(1.0..=limits.max_sampler_anisotropy).contains(&max_anisotropy) {
+                panic!("VUID-VkSamplerCreateInfo-anisotropyEnable-01071");
+            }
+        }
+        if self.unnormalized_coordinates {
+            // MUST VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01072
+            if self.min_filter != self.mag_filter {
+                panic!("VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01072");
+            }
+            // MUST VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01073
+            if self.mipmap_mode != ash::vk::SamplerMipmapMode::NEAREST {
+                panic!("VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01073");
+            }
+            // MUST VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01074
+            if self.min_lod != 0.0 || self.max_lod != 0.0 {
+                panic!("VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01074");
+            }
+            // MUST VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01075
+            let clamped = |mode: ash::vk::SamplerAddressMode| {
+                mode == ash::vk::SamplerAddressMode::CLAMP_TO_EDGE
+                    || mode == ash::vk::SamplerAddressMode::CLAMP_TO_BORDER
+            };
+            if !clamped(self.address_mode_u) || !clamped(self.address_mode_v) {
+                panic!("VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01075");
+            }
+            // MUST VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01076
+            if self.max_anisotropy.is_some() {
+                panic!("VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01076");
+            }
+            // MUST VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01077
+            if matches!(self.compare_op, Some(op) if op != ash::vk::CompareOp::NEVER) {
+                panic!("VUID-VkSamplerCreateInfo-unnormalizedCoordinates-01077");
+            }
+            // TODO image views sampled with unnormalized coordinates must be non-array 1D or 2D
+            // views with a single mip level, validate it when the sampler is used in a draw
+        }
+        if let Some(ycbcr_conversion) = &self.ycbcr_conversion {
+            // MUST VUID-VkSamplerCreateInfo-minFilter-01645
+            if !ycbcr_conversion.format_features.contains(
+                ash::vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_SEPARATE_RECONSTRUCTION_FILTER,
+            ) && (self.min_filter != ycbcr_conversion.chroma_filter
+                || self.mag_filter != ycbcr_conversion.chroma_filter)
+            {
+                panic!("VUID-VkSamplerCreateInfo-minFilter-01645");
+            }
+            // MUST VUID-VkSamplerCreateInfo-addressModeU-01646
+            if self.address_mode_u != ash::vk::SamplerAddressMode::CLAMP_TO_EDGE
+                || self.address_mode_v != ash::vk::SamplerAddressMode::CLAMP_TO_EDGE
+                || self.address_mode_w != ash::vk::SamplerAddressMode::CLAMP_TO_EDGE
+                || self.max_anisotropy.is_some()
+                || self.unnormalized_coordinates
+            {
+                panic!("VUID-VkSamplerCreateInfo-addressModeU-01646");
+            }
+            // MUST VUID-VkSamplerCreateInfo-None-01647
+            if matches!(&self.reduction_mode, Some(reduction_mode)
+                if reduction_mode.reduction_mode != ash::vk::SamplerReductionMode::WEIGHTED_AVERAGE)
+            {
+                panic!("VUID-VkSamplerCreateInfo-None-01647");
+            }
+        }
         let mut create_info_builder = ash::vk::SamplerCreateInfo::builder()
             .flags(self.flags)
             .mag_filter(self.mag_filter)
@@ -142,6 +283,20 @@ impl SamplerBuilder {
                     .compare_enable(true);
             }
         }
+        // TODO VUID-VkSamplerCreateInfo-None-04012
+        if let Some(custom_border_color) = &mut self.custom_border_color {
+            create_info_builder = create_info_builder.push_next(custom_border_color);
+        }
+        if let Some(reduction_mode) = &mut self.reduction_mode {
+            create_info_builder = create_info_builder.push_next(reduction_mode);
+        }
+        let mut ycbcr_conversion_info;
+        if let Some(ycbcr_conversion) = &self.ycbcr_conversion {
+            ycbcr_conversion_info = ash::vk::SamplerYcbcrConversionInfo::builder()
+                .conversion(ycbcr_conversion.ash_vk_sampler_ycbcr_conversion)
+                .build();
+            create_info_builder = create_info_builder.push_next(&mut ycbcr_conversion_info);
+        }
         let create_info = create_info_builder.build();
         unsafe {
             // Host Synchronization: none
@@ -149,6 +304,159 @@ impl SamplerBuilder {
             Ok(Arc::new(Sampler {
                 device: self.device,
                 ash_vk_sampler,
+                ycbcr_conversion: self.ycbcr_conversion,
+            }))
+        }
+    }
+}
+
+pub struct SamplerYcbcrConversion {
+    pub device: Arc<Device>,
+    pub format: ash::vk::Format,
+    pub(crate) ash_vk_sampler_ycbcr_conversion: ash::vk::SamplerYcbcrConversion,
+    chroma_filter: ash::vk::Filter,
+    format_features: ash::vk::FormatFeatureFlags,
+}
+
+impl Drop for SamplerYcbcrConversion {
+    fn drop(&mut self) {
+        unsafe {
+            // Host Synchronization: ycbcrConversion
+            self.device
+                .ash_device
+                .destroy_sampler_ycbcr_conversion(self.ash_vk_sampler_ycbcr_conversion, None);
+        }
+    }
+}
+
+impl SamplerYcbcrConversion {
+    pub fn builder(
+        device: Arc<Device>,
+        format: ash::vk::Format,
+        _feature: Feature<{ PhysicalDeviceVulkan11Features::SamplerYcbcrConversion.into() }>,
+    ) -> SamplerYcbcrConversionBuilder {
+        SamplerYcbcrConversionBuilder {
+            device,
+            format,
+            ycbcr_model: Default::default(),
+            ycbcr_range: Default::default(),
+            components: Default::default(),
+            x_chroma_offset: Default::default(),
+            y_chroma_offset: Default::default(),
+            chroma_filter: Default::default(),
+            force_explicit_reconstruction: false,
+        }
+    }
+}
+
+pub struct SamplerYcbcrConversionBuilder {
+    device: Arc<Device>,
+    format: ash::vk::Format,
+    ycbcr_model: ash::vk::SamplerYcbcrModelConversion,
+    ycbcr_range: ash::vk::SamplerYcbcrRange,
+    components: ash::vk::ComponentMapping,
+    x_chroma_offset: ash::vk::ChromaLocation,
+    y_chroma_offset: ash::vk::ChromaLocation,
+    chroma_filter: ash::vk::Filter,
+    force_explicit_reconstruction: bool,
+}
+
+impl SamplerYcbcrConversionBuilder {
+    pub fn ycbcr_model(mut self, ycbcr_model: ash::vk::SamplerYcbcrModelConversion) -> Self {
+        self.ycbcr_model = ycbcr_model;
+        self
+    }
+    pub fn ycbcr_range(mut self, ycbcr_range: ash::vk::SamplerYcbcrRange) -> Self {
+        self.ycbcr_range = ycbcr_range;
+        self
+    }
+    pub fn components(mut self, components: ash::vk::ComponentMapping) -> Self {
+        self.components = components;
+        self
+    }
+    pub fn x_chroma_offset(mut self, x_chroma_offset: ash::vk::ChromaLocation) -> Self {
+        self.x_chroma_offset = x_chroma_offset;
+        self
+    }
+    pub fn y_chroma_offset(mut self, y_chroma_offset: ash::vk::ChromaLocation) -> Self {
+        self.y_chroma_offset = y_chroma_offset;
+        self
+    }
+    pub fn chroma_filter(mut self, chroma_filter: ash::vk::Filter) -> Self {
+        self.chroma_filter = chroma_filter;
+        self
+    }
+    pub fn force_explicit_reconstruction(mut self) -> Self {
+        self.force_explicit_reconstruction = true;
+        self
+    }
+    pub fn build(self) -> Result<Arc<SamplerYcbcrConversion>, ash::vk::Result> {
+        let format_properties = self
+            .device
+            .physical_device
+            .get_format_properties(self.format);
+        let format_features =
+            format_properties.linear_tiling_features | format_properties.optimal_tiling_features;
+        // MUST VUID-VkSamplerYcbcrConversionCreateInfo-format-01650
+        if !format_features.intersects(
+            ash::vk::FormatFeatureFlags::MIDPOINT_CHROMA_SAMPLES
+                | ash::vk::FormatFeatureFlags::COSITED_CHROMA_SAMPLES,
+        ) {
+            panic!("VUID-VkSamplerYcbcrConversionCreateInfo-format-01650");
+        }
+        let chroma_offsets = [self.x_chroma_offset, self.y_chroma_offset];
+        // MUST VUID-VkSamplerYcbcrConversionCreateInfo-xChromaOffset-01651
+        if chroma_offsets.contains(&ash::vk::ChromaLocation::COSITED_EVEN)
+            && !format_features.contains(ash::vk::FormatFeatureFlags::COSITED_CHROMA_SAMPLES)
+        {
+            panic!("VUID-VkSamplerYcbcrConversionCreateInfo-xChromaOffset-01651");
+        }
+        // MUST VUID-VkSamplerYcbcrConversionCreateInfo-xChromaOffset-01652
+        if chroma_offsets.contains(&ash::vk::ChromaLocation::MIDPOINT)
+            && !format_features.contains(ash::vk::FormatFeatureFlags::MIDPOINT_CHROMA_SAMPLES)
+        {
+            panic!("VUID-VkSamplerYcbcrConversionCreateInfo-xChromaOffset-01652");
+        }
+        // MUST VUID-VkSamplerYcbcrConversionCreateInfo-forceExplicitReconstruction-01656
+        if self.force_explicit_reconstruction
+            && !format_features.contains(
+                ash::vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_CHROMA_RECONSTRUCTION_EXPLICIT_FORCEABLE,
+            )
+        {
+            panic!("VUID-VkSamplerYcbcrConversionCreateInfo-forceExplicitReconstruction-01656");
+        }
+        // MUST VUID-VkSamplerYcbcrConversionCreateInfo-chromaFilter-01657
+        if self.chroma_filter == ash::vk::Filter::LINEAR
+            && !format_features.contains(
+                ash::vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_LINEAR_FILTER,
+            )
+        {
+            panic!("VUID-VkSamplerYcbcrConversionCreateInfo-chromaFilter-01657");
+        }
+        // TODO VUID-VkSamplerYcbcrConversionCreateInfo-components-02581
+        // TODO VUID-VkSamplerYcbcrConversionCreateInfo-ycbcrModel-01655
+        let create_info = ash::vk::SamplerYcbcrConversionCreateInfo::builder()
+            .format(self.format)
+            .ycbcr_model(self.ycbcr_model)
+            .ycbcr_range(self.ycbcr_range)
+            .components(self.components)
+            .x_chroma_offset(self.x_chroma_offset)
+            .y_chroma_offset(self.y_chroma_offset)
+            .chroma_filter(self.chroma_filter)
+            .force_explicit_reconstruction(self.force_explicit_reconstruction)
+            .build();
+        unsafe {
+            // Host Synchronization: none
+            let ash_vk_sampler_ycbcr_conversion = self
+                .device
+                .ash_device
+                .create_sampler_ycbcr_conversion(&create_info, None)?;
+            Ok(Arc::new(SamplerYcbcrConversion {
+                device: self.device,
+                format: self.format,
+                ash_vk_sampler_ycbcr_conversion,
+                chroma_filter: self.chroma_filter,
+                format_features,
             }))
         }
     }
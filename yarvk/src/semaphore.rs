@@ -1,36 +1,306 @@
 use crate::device::Device;
+use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceVulkan12Features::TimelineSemaphore as TimelineSemaphoreFeature;
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::KhrExternalSemaphoreFd;
+use crate::physical_device::PhysicalDevice;
 
-
+use std::os::raw::c_int;
 use std::sync::Arc;
 use parking_lot::RwLock;
 
+impl PhysicalDevice {
+    pub fn get_external_semaphore_properties(
+        &self,
+        handle_type: ash::vk::ExternalSemaphoreHandleTypeFlags,
+    ) -> ash::vk::ExternalSemaphoreProperties {
+        let info = ash::vk::PhysicalDeviceExternalSemaphoreInfo::builder()
+            .handle_type(handle_type)
+            .build();
+        let mut properties = ash::vk::ExternalSemaphoreProperties::default();
+        unsafe {
+            // Host Synchronization: none
+            self.instance
+                .ash_instance
+                .get_physical_device_external_semaphore_properties(
+                    self.vk_physical_device,
+                    &info,
+                    &mut properties,
+                );
+        }
+        properties
+    }
+}
+
+fn check_fd_handle_type(handle_type: ash::vk::ExternalSemaphoreHandleTypeFlags, vuid: &str) {
+    if handle_type != ash::vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD
+        && handle_type != ash::vk::ExternalSemaphoreHandleTypeFlags::SYNC_FD
+    {
+        panic!("{}", vuid);
+    }
+}
+
+pub struct SemaphoreBuilder {
+    device: Arc<Device>,
+    export_handle_types: ash::vk::ExternalSemaphoreHandleTypeFlags,
+}
+
+impl SemaphoreBuilder {
+    // the semaphore payload can be exported as any of `handle_types` by `Semaphore::get_fd`
+    pub fn exportable(
+        mut self,
+        handle_types: ash::vk::ExternalSemaphoreHandleTypeFlags,
+        _extension: &DeviceExtension<{ KhrExternalSemaphoreFd }>,
+    ) -> Self {
+        // MUST VUID-VkExportSemaphoreCreateInfo-handleTypes-01124
+        for bit in 0..u32::BITS {
+            let handle_type = ash::vk::ExternalSemaphoreHandleTypeFlags::from_raw(1 << bit);
+            if !handle_types.contains(handle_type) {
+                continue;
+            }
+            let properties = self
+                .device
+                .physical_device
+                .get_external_semaphore_properties(handle_type);
+            if !properties
+                .external_semaphore_features
+                .contains(ash::vk::ExternalSemaphoreFeatureFlags::EXPORTABLE)
+                || !properties.compatible_handle_types.contains(handle_types)
+            {
+                panic!("VUID-VkExportSemaphoreCreateInfo-handleTypes-01124");
+            }
+        }
+        self.export_handle_types = handle_types;
+        self
+    }
+
+    pub fn build(self) -> Result<Arc<Semaphore>, ash::vk::Result> {
+        let mut export_create_info = ash::vk::ExportSemaphoreCreateInfo::builder()
+            .handle_types(self.export_handle_types)
+            .build();
+        let mut create_info = ash::vk::SemaphoreCreateInfo::builder();
+        if !self.export_handle_types.is_empty() {
+            create_info = create_info.push_next(&mut export_create_info);
+        }
+        let ash_vk_semaphore = unsafe {
+            // Host Synchronization: none
+            self.device
+                .ash_device
+                .create_semaphore(&create_info, None)?
+        };
+        Ok(Arc::new(Semaphore {
+            device: self.device,
+            ash_vk_semaphore: RwLock::new(ash_vk_semaphore),
+            export_handle_types: self.export_handle_types,
+        }))
+    }
+}
+
 pub struct Semaphore {
     pub device: Arc<Device>,
     pub(crate) ash_vk_semaphore: RwLock<ash::vk::Semaphore>,
+    export_handle_types: ash::vk::ExternalSemaphoreHandleTypeFlags,
 }
 
 impl Semaphore {
+    pub fn builder(device: Arc<Device>) -> SemaphoreBuilder {
+        SemaphoreBuilder {
+            device,
+            export_handle_types: Default::default(),
+        }
+    }
+
     pub fn new(device: Arc<Device>) -> Result<Arc<Semaphore>, ash::vk::Result> {
-        let create_info = ash::vk::SemaphoreCreateInfo::builder().build();
+        Self::builder(device).build()
+    }
+
+    // export the payload as a POSIX file descriptor, the caller owns the returned fd
+    pub fn get_fd(
+        &self,
+        handle_type: ash::vk::ExternalSemaphoreHandleTypeFlags,
+        _extension: &DeviceExtension<{ KhrExternalSemaphoreFd }>,
+    ) -> Result<c_int, ash::vk::Result> {
+        // MUST VUID-VkSemaphoreGetFdInfoKHR-handleType-01132
+        if !self.export_handle_types.contains(handle_type) {
+            panic!("VUID-VkSemaphoreGetFdInfoKHR-handleType-01132");
+        }
+        // MUST VUID-VkSemaphoreGetFdInfoKHR-handleType-01136
+        check_fd_handle_type(handle_type, "VUID-VkSemaphoreGetFdInfoKHR-handleType-01136");
+        // TODO VUID-VkSemaphoreGetFdInfoKHR-handleType-01133
+        // TODO VUID-VkSemaphoreGetFdInfoKHR-handleType-03253
+        // TODO VUID-VkSemaphoreGetFdInfoKHR-handleType-03254
+        let ash_vk_semaphore = self.ash_vk_semaphore.read();
+        let get_info = ash::vk::SemaphoreGetFdInfoKHR::builder()
+            .semaphore(*ash_vk_semaphore)
+            .handle_type(handle_type)
+            .build();
+        unsafe {
+            // Host Synchronization: none
+            self.device
+                .external_semaphore_fd_loader()
+                .get_semaphore_fd(&get_info)
+        }
+    }
+
+    // import a payload from a POSIX file descriptor, on success the ownership of `fd` is
+    // transferred to the implementation
+    pub fn import_fd(
+        &self,
+        handle_type: ash::vk::ExternalSemaphoreHandleTypeFlags,
+        fd: c_int,
+        temporary: bool,
+        _extension: &DeviceExtension<{ KhrExternalSemaphoreFd }>,
+    ) -> Result<(), ash::vk::Result> {
+        // MUST VUID-VkImportSemaphoreFdInfoKHR-handleType-01143
+        check_fd_handle_type(handle_type, "VUID-VkImportSemaphoreFdInfoKHR-handleType-01143");
+        // MUST VUID-VkImportSemaphoreFdInfoKHR-handleType-07307
+        if handle_type == ash::vk::ExternalSemaphoreHandleTypeFlags::SYNC_FD && !temporary {
+            panic!("VUID-VkImportSemaphoreFdInfoKHR-handleType-07307");
+        }
+        // TODO VUID-vkImportSemaphoreFdKHR-semaphore-01142
+        // TODO VUID-VkImportSemaphoreFdInfoKHR-fd-01544
+        let flags = if temporary {
+            ash::vk::SemaphoreImportFlags::TEMPORARY
+        } else {
+            ash::vk::SemaphoreImportFlags::empty()
+        };
+        // Host Synchronization: semaphore
+        let ash_vk_semaphore = self.ash_vk_semaphore.write();
+        let import_info = ash::vk::ImportSemaphoreFdInfoKHR::builder()
+            .semaphore(*ash_vk_semaphore)
+            .flags(flags)
+            .handle_type(handle_type)
+            .fd(fd)
+            .build();
+        unsafe {
+            self.device
+                .external_semaphore_fd_loader()
+                .import_semaphore_fd(&import_info)
+        }
+    }
+}
+
+impl Drop for Semaphore {
+    fn drop(&mut self) {
+        unsafe {
+            // TODO VUID-vkDestroySemaphore-semaphore-01137
+            // Host Synchronization: semaphore
+            self.device
+                .ash_device
+                .destroy_semaphore(*self.ash_vk_semaphore.get_mut(), None);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum WaitResult {
+    Signaled,
+    Timeout,
+}
+
+pub struct TimelineSemaphore {
+    pub device: Arc<Device>,
+    pub(crate) ash_vk_semaphore: ash::vk::Semaphore,
+}
+
+impl TimelineSemaphore {
+    pub fn new(
+        device: Arc<Device>,
+        initial_value: u64,
+        _feature: Feature<{ TimelineSemaphoreFeature.into() }>,
+    ) -> Result<Arc<TimelineSemaphore>, ash::vk::Result> {
+        let mut type_create_info = ash::vk::SemaphoreTypeCreateInfo::builder()
+            .semaphore_type(ash::vk::SemaphoreType::TIMELINE)
+            .initial_value(initial_value)
+            .build();
+        let create_info = ash::vk::SemaphoreCreateInfo::builder()
+            .push_next(&mut type_create_info)
+            .build();
         let ash_vk_semaphore = unsafe {
             // Host Synchronization: none
             device.ash_device.create_semaphore(&create_info, None)?
         };
-        Ok(Arc::new(Semaphore {
+        Ok(Arc::new(TimelineSemaphore {
             device,
-            ash_vk_semaphore: RwLock::new(ash_vk_semaphore),
+            ash_vk_semaphore,
         }))
     }
+
+    pub fn get_counter_value(&self) -> Result<u64, ash::vk::Result> {
+        // DONE VUID-vkGetSemaphoreCounterValue-semaphore-03255
+        unsafe {
+            // Host Synchronization: none
+            self.device
+                .ash_device
+                .get_semaphore_counter_value(self.ash_vk_semaphore)
+        }
+    }
+
+    pub fn signal(&self, value: u64) -> Result<(), ash::vk::Result> {
+        // DONE VUID-VkSemaphoreSignalInfo-semaphore-03257
+        // MUST VUID-VkSemaphoreSignalInfo-value-03258
+        if value <= self.get_counter_value()? {
+            panic!("VUID-VkSemaphoreSignalInfo-value-03258");
+        }
+        // TODO VUID-VkSemaphoreSignalInfo-value-03259
+        let signal_info = ash::vk::SemaphoreSignalInfo::builder()
+            .semaphore(self.ash_vk_semaphore)
+            .value(value)
+            .build();
+        unsafe {
+            // Host Synchronization: none
+            self.device.ash_device.signal_semaphore(&signal_info)
+        }
+    }
+
+    pub fn wait(&self, value: u64, timeout: u64) -> Result<WaitResult, ash::vk::Result> {
+        self.device.wait_semaphores(&[(self, value)], true, timeout)
+    }
 }
 
-impl Drop for Semaphore {
+impl Drop for TimelineSemaphore {
     fn drop(&mut self) {
         unsafe {
             // TODO VUID-vkDestroySemaphore-semaphore-01137
             // Host Synchronization: semaphore
             self.device
                 .ash_device
-                .destroy_semaphore(*self.ash_vk_semaphore.get_mut(), None);
+                .destroy_semaphore(self.ash_vk_semaphore, None);
+        }
+    }
+}
+
+impl Device {
+    // wait until all (or any if `wait_all` is false) semaphores reach their values
+    pub fn wait_semaphores(
+        &self,
+        semaphores: &[(&TimelineSemaphore, u64)],
+        wait_all: bool,
+        timeout: u64,
+    ) -> Result<WaitResult, ash::vk::Result> {
+        // DONE VUID-VkSemaphoreWaitInfo-pSemaphores-03256
+        let (vk_semaphores, values): (Vec<_>, Vec<_>) = semaphores
+            .iter()
+            .map(|(semaphore, value)| (semaphore.ash_vk_semaphore, *value))
+            .unzip();
+        let flags = if wait_all {
+            ash::vk::SemaphoreWaitFlags::empty()
+        } else {
+            ash::vk::SemaphoreWaitFlags::ANY
+        };
+        let wait_info = ash::vk::SemaphoreWaitInfo::builder()
+            .flags(flags)
+            .semaphores(vk_semaphores.as_slice())
+            .values(values.as_slice())
+            .build();
+        let result = unsafe {
+            // Host Synchronization: none
+            self.ash_device.wait_semaphores(&wait_info, timeout)
+        };
+        match result {
+            Ok(()) => Ok(WaitResult::Signaled),
+            Err(ash::vk::Result::TIMEOUT) => Ok(WaitResult::Timeout),
+            Err(e) => Err(e),
         }
     }
 }
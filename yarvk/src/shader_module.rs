@@ -1,15 +1,123 @@
 use crate::device::Device;
+#[cfg(feature = "shaderc")]
+use crate::error::Error;
+#[cfg(feature = "shaderc")]
+use crate::pipeline::shader_stage::ShaderStageFlags;
 use std::sync::Arc;
 
+#[cfg(feature = "shaderc")]
+#[derive(Debug)]
+pub enum ShaderCompileError {
+    // `shaderc::Error` carries the source name given to `from_glsl`/`from_hlsl` in its message
+    Compile(shaderc::Error),
+    Vulkan(Error),
+}
+
+#[cfg(feature = "shaderc")]
+impl From<shaderc::Error> for ShaderCompileError {
+    fn from(error: shaderc::Error) -> Self {
+        ShaderCompileError::Compile(error)
+    }
+}
+
+#[cfg(feature = "shaderc")]
+impl From<ash::vk::Result> for ShaderCompileError {
+    fn from(error: ash::vk::Result) -> Self {
+        ShaderCompileError::Vulkan(error.into())
+    }
+}
+
 pub struct ShaderModule {
     pub device: Arc<Device>,
     pub(crate) ash_vk_shader_module: ash::vk::ShaderModule,
+    // kept for `ShaderModule::reflect`
+    #[cfg(feature = "spirv-reflect")]
+    pub(crate) code: Vec<u32>,
 }
 
 impl ShaderModule {
     pub fn builder(device: Arc<Device>, code: &[u32]) -> ShaderModuleBuilder {
         ShaderModuleBuilder { device, code }
     }
+
+    // compile GLSL `source` at runtime, `name` is the source file name used in error messages
+    #[cfg(feature = "shaderc")]
+    pub fn from_glsl(
+        device: Arc<Device>,
+        source: &str,
+        stage: &ShaderStageFlags,
+        entry: &str,
+        name: &str,
+    ) -> Result<Arc<ShaderModule>, ShaderCompileError> {
+        Self::compile(
+            device,
+            source,
+            shaderc::SourceLanguage::GLSL,
+            stage,
+            entry,
+            name,
+        )
+    }
+
+    // compile HLSL `source` at runtime, `name` is the source file name used in error messages
+    #[cfg(feature = "shaderc")]
+    pub fn from_hlsl(
+        device: Arc<Device>,
+        source: &str,
+        stage: &ShaderStageFlags,
+        entry: &str,
+        name: &str,
+    ) -> Result<Arc<ShaderModule>, ShaderCompileError> {
+        Self::compile(
+            device,
+            source,
+            shaderc::SourceLanguage::HLSL,
+            stage,
+            entry,
+            name,
+        )
+    }
+
+    #[cfg(feature = "shaderc")]
+    fn compile(
+        device: Arc<Device>,
+        source: &str,
+        language: shaderc::SourceLanguage,
+        stage: &ShaderStageFlags,
+        entry: &str,
+        name: &str,
+    ) -> Result<Arc<ShaderModule>, ShaderCompileError> {
+        let kind = match stage.to_ash() {
+            ash::vk::ShaderStageFlags::VERTEX => shaderc::ShaderKind::Vertex,
+            ash::vk::ShaderStageFlags::TESSELLATION_CONTROL => shaderc::ShaderKind::TessControl,
+            ash::vk::ShaderStageFlags::TESSELLATION_EVALUATION => {
+                shaderc::ShaderKind::TessEvaluation
+            }
+            ash::vk::ShaderStageFlags::GEOMETRY => shaderc::ShaderKind::Geometry,
+            ash::vk::ShaderStageFlags::FRAGMENT => shaderc::ShaderKind::Fragment,
+            ash::vk::ShaderStageFlags::COMPUTE => shaderc::ShaderKind::Compute,
+            ash::vk::ShaderStageFlags::RAYGEN_KHR => shaderc::ShaderKind::RayGeneration,
+            ash::vk::ShaderStageFlags::ANY_HIT_KHR => shaderc::ShaderKind::AnyHit,
+            ash::vk::ShaderStageFlags::CLOSEST_HIT_KHR => shaderc::ShaderKind::ClosestHit,
+            ash::vk::ShaderStageFlags::MISS_KHR => shaderc::ShaderKind::Miss,
+            ash::vk::ShaderStageFlags::INTERSECTION_KHR => shaderc::ShaderKind::Intersection,
+            ash::vk::ShaderStageFlags::CALLABLE_KHR => shaderc::ShaderKind::Callable,
+            ash::vk::ShaderStageFlags::TASK_NV => shaderc::ShaderKind::Task,
+            ash::vk::ShaderStageFlags::MESH_NV => shaderc::ShaderKind::Mesh,
+            // no shaderc kind for the stage, the source must contain `#pragma shader_stage`
+            _ => shaderc::ShaderKind::InferFromSource,
+        };
+        let compiler = shaderc::Compiler::new().ok_or_else(|| {
+            shaderc::Error::InternalError("failed to create shaderc compiler".to_string())
+        })?;
+        let mut options = shaderc::CompileOptions::new().ok_or_else(|| {
+            shaderc::Error::InternalError("failed to create shaderc compile options".to_string())
+        })?;
+        options.set_source_language(language);
+        let artifact = compiler.compile_into_spirv(source, kind, name, entry, Some(&options))?;
+        let shader_module = ShaderModule::builder(device, artifact.as_binary()).build()?;
+        Ok(shader_module)
+    }
 }
 
 impl Drop for ShaderModule {
@@ -42,6 +150,8 @@ impl<'a> ShaderModuleBuilder<'a> {
         Ok(Arc::new(ShaderModule {
             device: self.device,
             ash_vk_shader_module,
+            #[cfg(feature = "spirv-reflect")]
+            code: self.code.to_vec(),
         }))
     }
 }
@@ -0,0 +1,620 @@
+use crate::descriptor_pool::{DescriptorSetLayout, DescriptorSetLayoutBinding};
+use crate::device::Device;
+use crate::pipeline::PipelineLayout;
+use crate::shader_module::ShaderModule;
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+const MAGIC_NUMBER: u32 = 0x0723_0203;
+const HEADER_LEN: usize = 5;
+
+// opcodes
+const OP_ENTRY_POINT: u32 = 15;
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_TYPE_MATRIX: u32 = 24;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_CONSTANT: u32 = 43;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+const OP_MEMBER_DECORATE: u32 = 72;
+const OP_TYPE_ACCELERATION_STRUCTURE_KHR: u32 = 5341;
+
+// decorations
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_ROW_MAJOR: u32 = 4;
+const DECORATION_ARRAY_STRIDE: u32 = 6;
+const DECORATION_MATRIX_STRIDE: u32 = 7;
+const DECORATION_BUILT_IN: u32 = 11;
+const DECORATION_LOCATION: u32 = 30;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+const DECORATION_OFFSET: u32 = 35;
+
+// storage classes
+const STORAGE_CLASS_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_CLASS_INPUT: u32 = 1;
+const STORAGE_CLASS_UNIFORM: u32 = 2;
+const STORAGE_CLASS_PUSH_CONSTANT: u32 = 9;
+const STORAGE_CLASS_STORAGE_BUFFER: u32 = 12;
+
+// image dimensions
+const DIM_BUFFER: u32 = 5;
+const DIM_SUBPASS_DATA: u32 = 6;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DescriptorBindingReflection {
+    pub set: u32,
+    pub binding: u32,
+    // dynamic buffer types can not be told from the shader, change them if needed
+    pub descriptor_type: ash::vk::DescriptorType,
+    // 0 for runtime arrays, which must be given a count before building a layout
+    pub descriptor_count: u32,
+    pub stage_flags: ash::vk::ShaderStageFlags,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VertexInputReflection {
+    pub location: u32,
+    // UNDEFINED if the input type has no matching format
+    pub format: ash::vk::Format,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ShaderReflection {
+    pub stage_flags: ash::vk::ShaderStageFlags,
+    pub descriptor_bindings: Vec<DescriptorBindingReflection>,
+    pub push_constant_ranges: Vec<ash::vk::PushConstantRange>,
+    pub vertex_inputs: Vec<VertexInputReflection>,
+}
+
+#[derive(Clone, Copy)]
+enum Type {
+    Bool,
+    Int { width: u32, signed: bool },
+    Float { width: u32 },
+    Vector { component: u32, count: u32 },
+    Matrix { column: u32, count: u32 },
+    Image { dim: u32, sampled: u32 },
+    Sampler,
+    SampledImage,
+    Array { element: u32, length: u32 },
+    RuntimeArray { element: u32 },
+    Struct,
+    Pointer,
+    AccelerationStructure,
+}
+
+struct Variable {
+    id: u32,
+    ty: u32,
+    storage_class: u32,
+}
+
+#[derive(Default)]
+struct Module {
+    stage_flags: ash::vk::ShaderStageFlags,
+    vertex_interface: Vec<u32>,
+    types: FxHashMap<u32, Type>,
+    pointers: FxHashMap<u32, u32>,
+    struct_members: FxHashMap<u32, Vec<u32>>,
+    constants: FxHashMap<u32, u32>,
+    variables: Vec<Variable>,
+    decorations: FxHashMap<(u32, u32), u32>,
+    member_decorations: FxHashMap<(u32, u32, u32), u32>,
+}
+
+fn execution_model_to_stage(execution_model: u32) -> ash::vk::ShaderStageFlags {
+    match execution_model {
+        0 => ash::vk::ShaderStageFlags::VERTEX,
+        1 => ash::vk::ShaderStageFlags::TESSELLATION_CONTROL,
+        2 => ash::vk::ShaderStageFlags::TESSELLATION_EVALUATION,
+        3 => ash::vk::ShaderStageFlags::GEOMETRY,
+        4 => ash::vk::ShaderStageFlags::FRAGMENT,
+        5 => ash::vk::ShaderStageFlags::COMPUTE,
+        5267 => ash::vk::ShaderStageFlags::TASK_NV,
+        5268 => ash::vk::ShaderStageFlags::MESH_NV,
+        5313 => ash::vk::ShaderStageFlags::RAYGEN_KHR,
+        5314 => ash::vk::ShaderStageFlags::INTERSECTION_KHR,
+        5315 => ash::vk::ShaderStageFlags::ANY_HIT_KHR,
+        5316 => ash::vk::ShaderStageFlags::CLOSEST_HIT_KHR,
+        5317 => ash::vk::ShaderStageFlags::MISS_KHR,
+        5318 => ash::vk::ShaderStageFlags::CALLABLE_KHR,
+        _ => ash::vk::ShaderStageFlags::empty(),
+    }
+}
+
+impl Module {
+    fn parse(code: &[u32]) -> Module {
+        let mut module = Module::default();
+        if code.len() < HEADER_LEN || code[0] != MAGIC_NUMBER {
+            return module;
+        }
+        let mut words = &code[HEADER_LEN..];
+        while let Some(first) = words.first() {
+            let word_count = (first >> 16) as usize;
+            // valid SPIR-V is required by VUID-VkShaderModuleCreateInfo-pCode-01379, stop on a
+            // malformed instruction instead of reading past it
+            if word_count == 0 || word_count > words.len() {
+                break;
+            }
+            module.parse_instruction(first & 0xffff, &words[1..word_count]);
+            words = &words[word_count..];
+        }
+        module
+    }
+
+    fn parse_instruction(&mut self, opcode: u32, operands: &[u32]) {
+        let operand = |index: usize| operands.get(index).copied().unwrap_or(0);
+        match opcode {
+            OP_ENTRY_POINT => {
+                let stage = execution_model_to_stage(operand(0));
+                self.stage_flags |= stage;
+                if stage == ash::vk::ShaderStageFlags::VERTEX {
+                    // the name is a nul terminated string packed in words after the entry id
+                    let name_len = operands[2..]
+                        .iter()
+                        .position(|word| word >> 24 == 0)
+                        .map_or(operands.len(), |index| index + 1);
+                    self.vertex_interface
+                        .extend_from_slice(operands.get(2 + name_len..).unwrap_or(&[]));
+                }
+            }
+            OP_TYPE_BOOL => {
+                self.types.insert(operand(0), Type::Bool);
+            }
+            OP_TYPE_INT => {
+                self.types.insert(
+                    operand(0),
+                    Type::Int {
+                        width: operand(1),
+                        signed: operand(2) != 0,
+                    },
+                );
+            }
+            OP_TYPE_FLOAT => {
+                self.types
+                    .insert(operand(0), Type::Float { width: operand(1) });
+            }
+            OP_TYPE_VECTOR => {
+                self.types.insert(
+                    operand(0),
+                    Type::Vector {
+                        component: operand(1),
+                        count: operand(2),
+                    },
+                );
+            }
+            OP_TYPE_MATRIX => {
+                self.types.insert(
+                    operand(0),
+                    Type::Matrix {
+                        column: operand(1),
+                        count: operand(2),
+                    },
+                );
+            }
+            OP_TYPE_IMAGE => {
+                self.types.insert(
+                    operand(0),
+                    Type::Image {
+                        dim: operand(2),
+                        sampled: operand(6),
+                    },
+                );
+            }
+            OP_TYPE_SAMPLER => {
+                self.types.insert(operand(0), Type::Sampler);
+            }
+            OP_TYPE_SAMPLED_IMAGE => {
+                self.types.insert(operand(0), Type::SampledImage);
+            }
+            OP_TYPE_ARRAY => {
+                let length = self.constants.get(&operand(2)).copied().unwrap_or(1);
+                self.types.insert(
+                    operand(0),
+                    Type::Array {
+                        element: operand(1),
+                        length,
+                    },
+                );
+            }
+            OP_TYPE_RUNTIME_ARRAY => {
+                self.types.insert(
+                    operand(0),
+                    Type::RuntimeArray {
+                        element: operand(1),
+                    },
+                );
+            }
+            OP_TYPE_STRUCT => {
+                self.types.insert(operand(0), Type::Struct);
+                self.struct_members
+                    .insert(operand(0), operands[1..].to_vec());
+            }
+            OP_TYPE_POINTER => {
+                self.types.insert(operand(0), Type::Pointer);
+                self.pointers.insert(operand(0), operand(2));
+            }
+            OP_TYPE_ACCELERATION_STRUCTURE_KHR => {
+                self.types.insert(operand(0), Type::AccelerationStructure);
+            }
+            OP_CONSTANT => {
+                // only the low word is needed for array lengths
+                self.constants.insert(operand(1), operand(2));
+            }
+            OP_VARIABLE => {
+                self.variables.push(Variable {
+                    id: operand(1),
+                    ty: operand(0),
+                    storage_class: operand(2),
+                });
+            }
+            OP_DECORATE => {
+                self.decorations
+                    .insert((operand(0), operand(1)), operand(2));
+            }
+            OP_MEMBER_DECORATE => {
+                self.member_decorations
+                    .insert((operand(0), operand(1), operand(2)), operand(3));
+            }
+            _ => {}
+        }
+    }
+
+    fn decoration(&self, id: u32, decoration: u32) -> Option<u32> {
+        self.decorations.get(&(id, decoration)).copied()
+    }
+
+    // size in bytes of `ty` as laid out in a block, `matrix_stride` comes from the member
+    fn size_of(&self, ty: u32, matrix_stride: Option<u32>, row_major: bool) -> u32 {
+        match self.types.get(&ty) {
+            Some(Type::Bool) => 4,
+            Some(Type::Int { width, .. }) | Some(Type::Float { width }) => width / 8,
+            Some(Type::Vector { component, count }) => {
+                count * self.size_of(*component, None, false)
+            }
+            Some(Type::Matrix { column, count }) => {
+                let rows = match self.types.get(column) {
+                    Some(Type::Vector { count, .. }) => *count,
+                    _ => 1,
+                };
+                match matrix_stride {
+                    Some(stride) if row_major => rows * stride,
+                    Some(stride) => count * stride,
+                    None => count * self.size_of(*column, None, false),
+                }
+            }
+            Some(Type::Array { element, length }) => {
+                let stride = self
+                    .decoration(ty, DECORATION_ARRAY_STRIDE)
+                    .unwrap_or_else(|| self.size_of(*element, matrix_stride, row_major));
+                length * stride
+            }
+            Some(Type::Struct) => self.struct_members.get(&ty).map_or(0, |members| {
+                members
+                    .iter()
+                    .enumerate()
+                    .map(|(index, member)| {
+                        let member_decoration = |decoration| {
+                            self.member_decorations
+                                .get(&(ty, index as u32, decoration))
+                                .copied()
+                        };
+                        member_decoration(DECORATION_OFFSET).unwrap_or(0)
+                            + self.size_of(
+                                *member,
+                                member_decoration(DECORATION_MATRIX_STRIDE),
+                                member_decoration(DECORATION_ROW_MAJOR).is_some(),
+                            )
+                    })
+                    .max()
+                    .unwrap_or(0)
+            }),
+            Some(Type::Pointer) => 8,
+            _ => 0,
+        }
+    }
+
+    fn descriptor_type(&self, variable: &Variable, ty: u32) -> Option<ash::vk::DescriptorType> {
+        let descriptor_type = match (variable.storage_class, self.types.get(&ty)?) {
+            (STORAGE_CLASS_UNIFORM_CONSTANT, Type::Sampler) => ash::vk::DescriptorType::SAMPLER,
+            (STORAGE_CLASS_UNIFORM_CONSTANT, Type::SampledImage) => {
+                ash::vk::DescriptorType::COMBINED_IMAGE_SAMPLER
+            }
+            (STORAGE_CLASS_UNIFORM_CONSTANT, Type::Image { dim, sampled }) => {
+                match (*dim, *sampled) {
+                    (DIM_SUBPASS_DATA, _) => ash::vk::DescriptorType::INPUT_ATTACHMENT,
+                    (DIM_BUFFER, 2) => ash::vk::DescriptorType::STORAGE_TEXEL_BUFFER,
+                    (DIM_BUFFER, _) => ash::vk::DescriptorType::UNIFORM_TEXEL_BUFFER,
+                    (_, 2) => ash::vk::DescriptorType::STORAGE_IMAGE,
+                    _ => ash::vk::DescriptorType::SAMPLED_IMAGE,
+                }
+            }
+            (STORAGE_CLASS_UNIFORM_CONSTANT, Type::AccelerationStructure) => {
+                ash::vk::DescriptorType::ACCELERATION_STRUCTURE_KHR
+            }
+            (STORAGE_CLASS_UNIFORM, Type::Struct) => {
+                if self.decoration(ty, DECORATION_BUFFER_BLOCK).is_some() {
+                    ash::vk::DescriptorType::STORAGE_BUFFER
+                } else {
+                    ash::vk::DescriptorType::UNIFORM_BUFFER
+                }
+            }
+            (STORAGE_CLASS_STORAGE_BUFFER, Type::Struct) => ash::vk::DescriptorType::STORAGE_BUFFER,
+            _ => return None,
+        };
+        Some(descriptor_type)
+    }
+
+    fn descriptor_bindings(&self) -> Vec<DescriptorBindingReflection> {
+        self.variables
+            .iter()
+            .filter_map(|variable| {
+                let set = self.decoration(variable.id, DECORATION_DESCRIPTOR_SET)?;
+                let binding = self.decoration(variable.id, DECORATION_BINDING)?;
+                let mut ty = *self.pointers.get(&variable.ty)?;
+                let mut descriptor_count = 1;
+                // arrays of descriptors
+                match self.types.get(&ty) {
+                    Some(Type::Array { element, length }) => {
+                        descriptor_count = *length;
+                        ty = *element;
+                    }
+                    Some(Type::RuntimeArray { element }) => {
+                        descriptor_count = 0;
+                        ty = *element;
+                    }
+                    _ => {}
+                }
+                let descriptor_type = self.descriptor_type(variable, ty)?;
+                Some(DescriptorBindingReflection {
+                    set,
+                    binding,
+                    descriptor_type,
+                    descriptor_count,
+                    stage_flags: self.stage_flags,
+                })
+            })
+            .collect()
+    }
+
+    fn push_constant_ranges(&self) -> Vec<ash::vk::PushConstantRange> {
+        self.variables
+            .iter()
+            .filter(|variable| variable.storage_class == STORAGE_CLASS_PUSH_CONSTANT)
+            .filter_map(|variable| {
+                let ty = *self.pointers.get(&variable.ty)?;
+                let members = self.struct_members.get(&ty)?;
+                let offset = (0..members.len() as u32)
+                    .filter_map(|index| {
+                        self.member_decorations
+                            .get(&(ty, index, DECORATION_OFFSET))
+                            .copied()
+                    })
+                    .min()
+                    .unwrap_or(0);
+                let end = self.size_of(ty, None, false);
+                // DONE VUID-VkPushConstantRange-size-00297
+                let size = (end - offset + 3) & !3;
+                Some(ash::vk::PushConstantRange {
+                    stage_flags: self.stage_flags,
+                    offset,
+                    size,
+                })
+            })
+            .collect()
+    }
+
+    fn format_of(&self, ty: u32) -> ash::vk::Format {
+        let (component, count) = match self.types.get(&ty) {
+            Some(Type::Vector { component, count }) => (*component, *count),
+            _ => (ty, 1),
+        };
+        use ash::vk::Format;
+        let formats = match self.types.get(&component) {
+            Some(Type::Float { width: 16 }) => [
+                Format::R16_SFLOAT,
+                Format::R16G16_SFLOAT,
+                Format::R16G16B16_SFLOAT,
+                Format::R16G16B16A16_SFLOAT,
+            ],
+            Some(Type::Float { width: 32 }) => [
+                Format::R32_SFLOAT,
+                Format::R32G32_SFLOAT,
+                Format::R32G32B32_SFLOAT,
+                Format::R32G32B32A32_SFLOAT,
+            ],
+            Some(Type::Float { width: 64 }) => [
+                Format::R64_SFLOAT,
+                Format::R64G64_SFLOAT,
+                Format::R64G64B64_SFLOAT,
+                Format::R64G64B64A64_SFLOAT,
+            ],
+            Some(Type::Int {
+                width: 32,
+                signed: true,
+            }) => [
+                Format::R32_SINT,
+                Format::R32G32_SINT,
+                Format::R32G32B32_SINT,
+                Format::R32G32B32A32_SINT,
+            ],
+            Some(Type::Int {
+                width: 32,
+                signed: false,
+            }) => [
+                Format::R32_UINT,
+                Format::R32G32_UINT,
+                Format::R32G32B32_UINT,
+                Format::R32G32B32A32_UINT,
+            ],
+            _ => return Format::UNDEFINED,
+        };
+        formats
+            .get(count.wrapping_sub(1) as usize)
+            .copied()
+            .unwrap_or(Format::UNDEFINED)
+    }
+
+    fn vertex_inputs(&self) -> Vec<VertexInputReflection> {
+        let mut vertex_inputs = Vec::new();
+        for variable in &self.variables {
+            if variable.storage_class != STORAGE_CLASS_INPUT
+                || !self.vertex_interface.contains(&variable.id)
+                || self.decoration(variable.id, DECORATION_BUILT_IN).is_some()
+            {
+                continue;
+            }
+            let (location, ty) = match (
+                self.decoration(variable.id, DECORATION_LOCATION),
+                self.pointers.get(&variable.ty),
+            ) {
+                (Some(location), Some(ty)) => (location, *ty),
+                _ => continue,
+            };
+            // a matrix takes one location per column
+            match self.types.get(&ty) {
+                Some(Type::Matrix { column, count }) => {
+                    let format = self.format_of(*column);
+                    vertex_inputs.extend((0..*count).map(|index| VertexInputReflection {
+                        location: location + index,
+                        format,
+                    }));
+                }
+                _ => vertex_inputs.push(VertexInputReflection {
+                    location,
+                    format: self.format_of(ty),
+                }),
+            }
+        }
+        vertex_inputs.sort_by_key(|input| input.location);
+        vertex_inputs
+    }
+}
+
+impl ShaderModule {
+    // reflect descriptor bindings, push constant ranges and vertex inputs of all entry points
+    pub fn reflect(&self) -> ShaderReflection {
+        let module = Module::parse(&self.code);
+        ShaderReflection {
+            stage_flags: module.stage_flags,
+            descriptor_bindings: module.descriptor_bindings(),
+            push_constant_ranges: module.push_constant_ranges(),
+            vertex_inputs: module.vertex_inputs(),
+        }
+    }
+}
+
+impl ShaderReflection {
+    // merge the reflections of the stages of one pipeline
+    pub fn merge(reflections: &[ShaderReflection]) -> ShaderReflection {
+        let mut merged = ShaderReflection::default();
+        for reflection in reflections {
+            merged.stage_flags |= reflection.stage_flags;
+            for binding in &reflection.descriptor_bindings {
+                match merged
+                    .descriptor_bindings
+                    .iter_mut()
+                    .find(|b| b.set == binding.set && b.binding == binding.binding)
+                {
+                    Some(merged_binding) => {
+                        if merged_binding.descriptor_type != binding.descriptor_type
+                            || merged_binding.descriptor_count != binding.descriptor_count
+                        {
+                            panic!(
+                                "binding {} of set {} is declared differently between stages",
+                                binding.binding, binding.set
+                            );
+                        }
+                        merged_binding.stage_flags |= binding.stage_flags;
+                    }
+                    None => merged.descriptor_bindings.push(binding.clone()),
+                }
+            }
+            // DONE VUID-VkPipelineLayoutCreateInfo-pPushConstantRanges-00292
+            for range in &reflection.push_constant_ranges {
+                match merged
+                    .push_constant_ranges
+                    .iter_mut()
+                    .find(|r| r.stage_flags == range.stage_flags)
+                {
+                    Some(merged_range) => {
+                        let end = (merged_range.offset + merged_range.size)
+                            .max(range.offset + range.size);
+                        merged_range.offset = merged_range.offset.min(range.offset);
+                        merged_range.size = end - merged_range.offset;
+                    }
+                    None => merged.push_constant_ranges.push(*range),
+                }
+            }
+            merged
+                .vertex_inputs
+                .extend_from_slice(&reflection.vertex_inputs);
+        }
+        merged
+            .descriptor_bindings
+            .sort_by_key(|binding| (binding.set, binding.binding));
+        merged
+    }
+
+    // one layout per set up to the highest set index, sets not used by the shaders are empty
+    pub fn build_descriptor_set_layouts(
+        &self,
+        device: Arc<Device>,
+    ) -> Result<Vec<Arc<DescriptorSetLayout>>, ash::vk::Result> {
+        let set_count = self
+            .descriptor_bindings
+            .iter()
+            .map(|binding| binding.set + 1)
+            .max()
+            .unwrap_or(0);
+        (0..set_count)
+            .map(|set| {
+                self.descriptor_bindings
+                    .iter()
+                    .filter(|binding| binding.set == set)
+                    .fold(
+                        DescriptorSetLayout::builder(device.clone()),
+                        |builder, binding| {
+                            builder.add_binding(
+                                DescriptorSetLayoutBinding::builder()
+                                    .binding(binding.binding)
+                                    .descriptor_type(binding.descriptor_type)
+                                    .descriptor_count(binding.descriptor_count)
+                                    .ash_stage_flags(binding.stage_flags)
+                                    .build(),
+                            )
+                        },
+                    )
+                    .build()
+            })
+            .collect()
+    }
+
+    pub fn build_pipeline_layout(
+        &self,
+        device: Arc<Device>,
+    ) -> Result<Arc<PipelineLayout>, ash::vk::Result> {
+        let builder = self
+            .build_descriptor_set_layouts(device.clone())?
+            .into_iter()
+            .fold(PipelineLayout::builder(device), |builder, set_layout| {
+                builder.add_set_layout(set_layout)
+            });
+        self.push_constant_ranges
+            .iter()
+            .fold(builder, |builder, range| {
+                builder.add_push_constant_range(*range)
+            })
+            .build()
+    }
+}
@@ -0,0 +1,250 @@
+use crate::buffer::Buffer;
+use crate::device_memory::DeviceMemory;
+use crate::fence::{SignalingFence, UnsignaledFence};
+use crate::image::Image;
+use crate::queue::Queue;
+use crate::semaphore::Semaphore;
+use std::sync::Arc;
+
+#[derive(Default)]
+pub struct BindSparseInfo {
+    wait_semaphores: Vec<Arc<Semaphore>>,
+    signal_semaphores: Vec<Arc<Semaphore>>,
+    buffer_binds: Vec<(Arc<Buffer>, Vec<ash::vk::SparseMemoryBind>)>,
+    image_opaque_binds: Vec<(Arc<Image>, Vec<ash::vk::SparseMemoryBind>)>,
+    image_binds: Vec<(Arc<Image>, Vec<ash::vk::SparseImageMemoryBind>)>,
+}
+
+impl BindSparseInfo {
+    pub fn new() -> BindSparseInfo {
+        BindSparseInfo::default()
+    }
+    pub fn add_wait_semaphore(&mut self, wait_semaphore: Arc<Semaphore>) {
+        self.wait_semaphores.push(wait_semaphore);
+    }
+    pub fn add_signal_semaphore(&mut self, signal_semaphore: Arc<Semaphore>) {
+        self.signal_semaphores.push(signal_semaphore);
+    }
+    // `memory` of None unbinds the range
+    pub fn add_buffer_bind(
+        &mut self,
+        buffer: Arc<Buffer>,
+        resource_offset: ash::vk::DeviceSize,
+        size: ash::vk::DeviceSize,
+        memory: Option<&DeviceMemory>,
+        memory_offset: ash::vk::DeviceSize,
+    ) {
+        if !buffer
+            .flags
+            .contains(ash::vk::BufferCreateFlags::SPARSE_BINDING)
+        {
+            panic!("buffer is not created with SPARSE_BINDING");
+        }
+        let bind = memory_bind(
+            resource_offset,
+            size,
+            memory,
+            memory_offset,
+            Default::default(),
+        );
+        push_bind(&mut self.buffer_binds, buffer, bind);
+    }
+    // binds the opaque mip tail region or a whole non-resident image
+    pub fn add_image_opaque_bind(
+        &mut self,
+        image: Arc<Image>,
+        resource_offset: ash::vk::DeviceSize,
+        size: ash::vk::DeviceSize,
+        memory: Option<&DeviceMemory>,
+        memory_offset: ash::vk::DeviceSize,
+        flags: ash::vk::SparseMemoryBindFlags,
+    ) {
+        if !image
+            .image_create_info
+            .flags
+            .contains(ash::vk::ImageCreateFlags::SPARSE_BINDING)
+        {
+            panic!("image is not created with SPARSE_BINDING");
+        }
+        let bind = memory_bind(resource_offset, size, memory, memory_offset, flags);
+        push_bind(&mut self.image_opaque_binds, image, bind);
+    }
+    pub fn add_image_bind(
+        &mut self,
+        image: Arc<Image>,
+        subresource: ash::vk::ImageSubresource,
+        offset: ash::vk::Offset3D,
+        extent: ash::vk::Extent3D,
+        memory: Option<&DeviceMemory>,
+        memory_offset: ash::vk::DeviceSize,
+    ) {
+        // MUST VUID-VkSparseImageMemoryBindInfo-image-02901
+        if !image
+            .image_create_info
+            .flags
+            .contains(ash::vk::ImageCreateFlags::SPARSE_RESIDENCY)
+        {
+            panic!("VUID-VkSparseImageMemoryBindInfo-image-02901");
+        }
+        // MUST VUID-VkSparseImageMemoryBindInfo-subresource-01722
+        if subresource.mip_level >= image.image_create_info.mip_levels {
+            panic!("VUID-VkSparseImageMemoryBindInfo-subresource-01722");
+        }
+        // MUST VUID-VkSparseImageMemoryBindInfo-subresource-01723
+        if subresource.array_layer >= image.image_create_info.array_layers {
+            panic!("VUID-VkSparseImageMemoryBindInfo-subresource-01723");
+        }
+        // TODO VUID-VkSparseImageMemoryBind-offset-01107
+        // TODO VUID-VkSparseImageMemoryBind-extent-01108
+        let mut bind = ash::vk::SparseImageMemoryBind::builder()
+            .subresource(subresource)
+            .offset(offset)
+            .extent(extent)
+            .memory_offset(memory_offset);
+        if let Some(memory) = memory {
+            bind = bind.memory(memory.vk_device_memory);
+        }
+        push_bind(&mut self.image_binds, image, bind.build());
+    }
+}
+
+fn memory_bind(
+    resource_offset: ash::vk::DeviceSize,
+    size: ash::vk::DeviceSize,
+    memory: Option<&DeviceMemory>,
+    memory_offset: ash::vk::DeviceSize,
+    flags: ash::vk::SparseMemoryBindFlags,
+) -> ash::vk::SparseMemoryBind {
+    // MUST VUID-VkSparseMemoryBind-size-01098
+    if size == 0 {
+        panic!("VUID-VkSparseMemoryBind-size-01098");
+    }
+    let mut bind = ash::vk::SparseMemoryBind::builder()
+        .resource_offset(resource_offset)
+        .size(size)
+        .memory_offset(memory_offset)
+        .flags(flags);
+    if let Some(memory) = memory {
+        // TODO VUID-VkSparseMemoryBind-memory-01096
+        // MUST VUID-VkSparseMemoryBind-memoryOffset-01101
+        if memory_offset >= memory.size {
+            panic!("VUID-VkSparseMemoryBind-memoryOffset-01101");
+        }
+        // MUST VUID-VkSparseMemoryBind-size-01102
+        if size > memory.size - memory_offset {
+            panic!("VUID-VkSparseMemoryBind-size-01102");
+        }
+        bind = bind.memory(memory.vk_device_memory);
+    }
+    bind.build()
+}
+
+fn push_bind<R, B>(binds: &mut Vec<(Arc<R>, Vec<B>)>, resource: Arc<R>, bind: B) {
+    match binds.iter_mut().find(|(r, _)| Arc::ptr_eq(r, &resource)) {
+        Some((_, resource_binds)) => resource_binds.push(bind),
+        None => binds.push((resource, vec![bind])),
+    }
+}
+
+impl Queue {
+    pub fn bind_sparse(
+        &mut self,
+        fence: UnsignaledFence,
+        bind_infos: Vec<BindSparseInfo>,
+    ) -> Result<SignalingFence<Vec<BindSparseInfo>>, ash::vk::Result> {
+        // MUST VUID-vkQueueBindSparse-queuetype
+        if !self
+            .queue_family
+            .property
+            .queue_flags
+            .contains(ash::vk::QueueFlags::SPARSE_BINDING)
+        {
+            panic!("VUID-vkQueueBindSparse-queuetype");
+        }
+        // DONE VUID-vkQueueBindSparse-fence-01113
+        // DONE VUID-vkQueueBindSparse-fence-01114
+        {
+            let mut semaphore_locks = Vec::new();
+            let mut wait_semaphores = Vec::with_capacity(bind_infos.len());
+            let mut signal_semaphores = Vec::with_capacity(bind_infos.len());
+            let mut buffer_bind_infos = Vec::with_capacity(bind_infos.len());
+            let mut image_opaque_bind_infos = Vec::with_capacity(bind_infos.len());
+            let mut image_bind_infos = Vec::with_capacity(bind_infos.len());
+            for bind_info in &bind_infos {
+                let mut vk_wait_semaphores = Vec::with_capacity(bind_info.wait_semaphores.len());
+                for semaphore in &bind_info.wait_semaphores {
+                    let lock = semaphore.ash_vk_semaphore.read();
+                    vk_wait_semaphores.push(*lock);
+                    semaphore_locks.push(lock);
+                }
+                wait_semaphores.push(vk_wait_semaphores);
+                let mut vk_signal_semaphores =
+                    Vec::with_capacity(bind_info.signal_semaphores.len());
+                for semaphore in &bind_info.signal_semaphores {
+                    let lock = semaphore.ash_vk_semaphore.read();
+                    vk_signal_semaphores.push(*lock);
+                    semaphore_locks.push(lock);
+                }
+                signal_semaphores.push(vk_signal_semaphores);
+                buffer_bind_infos.push(
+                    bind_info
+                        .buffer_binds
+                        .iter()
+                        .map(|(buffer, binds)| {
+                            ash::vk::SparseBufferMemoryBindInfo::builder()
+                                .buffer(buffer.ash_vk_buffer)
+                                .binds(binds)
+                                .build()
+                        })
+                        .collect::<Vec<_>>(),
+                );
+                image_opaque_bind_infos.push(
+                    bind_info
+                        .image_opaque_binds
+                        .iter()
+                        .map(|(image, binds)| {
+                            ash::vk::SparseImageOpaqueMemoryBindInfo::builder()
+                                .image(image.vk_image)
+                                .binds(binds)
+                                .build()
+                        })
+                        .collect::<Vec<_>>(),
+                );
+                image_bind_infos.push(
+                    bind_info
+                        .image_binds
+                        .iter()
+                        .map(|(image, binds)| {
+                            ash::vk::SparseImageMemoryBindInfo::builder()
+                                .image(image.vk_image)
+                                .binds(binds)
+                                .build()
+                        })
+                        .collect::<Vec<_>>(),
+                );
+            }
+            let vk_bind_infos = (0..bind_infos.len())
+                .map(|i| {
+                    ash::vk::BindSparseInfo::builder()
+                        .wait_semaphores(&wait_semaphores[i])
+                        .buffer_binds(&buffer_bind_infos[i])
+                        .image_opaque_binds(&image_opaque_bind_infos[i])
+                        .image_binds(&image_bind_infos[i])
+                        .signal_semaphores(&signal_semaphores[i])
+                        .build()
+                })
+                .collect::<Vec<_>>();
+            let _queues = self.device.queues_lock.read();
+            unsafe {
+                // Host Synchronization: queue, pBindInfo[].pBufferBinds[].buffer,
+                // pBindInfo[].pImageOpaqueBinds[].image, pBindInfo[].pImageBinds[].image, fence
+                self.device.ash_device.queue_bind_sparse(
+                    self.vk_queue,
+                    &vk_bind_infos,
+                    fence.vk_fence,
+                )?;
+            }
+        }
+        Ok(fence.to_executing_fence(bind_infos))
+    }
+}
@@ -2,6 +2,7 @@ use crate::extensions::{InstanceExtension, PhysicalInstanceExtensionType};
 use crate::physical_device::queue_falmily_properties::QueueFamilyProperties;
 use crate::physical_device::PhysicalDevice;
 
+use parking_lot::RwLock;
 use raw_window_handle::HasRawWindowHandle;
 use std::sync::Arc;
 
@@ -26,7 +27,7 @@ pub struct Surface {
     loader: ash::extensions::khr::Surface,
     pub(crate) vk_surface_khr: ash::vk::SurfaceKHR,
     pub(crate) supported_formats: Vec<ash::vk::SurfaceFormatKHR>,
-    pub(crate) capabilities: ash::vk::SurfaceCapabilitiesKHR,
+    pub(crate) capabilities: RwLock<ash::vk::SurfaceCapabilitiesKHR>,
     pub(crate) present_modes: Vec<ash::vk::PresentModeKHR>,
 }
 
@@ -96,7 +97,7 @@ impl Surface {
                     loader,
                     vk_surface_khr,
                     supported_formats,
-                    capabilities,
+                    capabilities: RwLock::new(capabilities),
                     present_modes,
                 })))
             } else {
@@ -105,11 +106,56 @@ impl Surface {
         }
     }
 
+    // formats, capabilities and present modes are queried once when the surface is created,
+    // the getters return the cached values, call `refresh_capabilities` after a resize
     pub fn get_physical_device_surface_formats(&self) -> &[ash::vk::SurfaceFormatKHR] {
         self.supported_formats.as_slice()
     }
-    pub fn get_physical_device_surface_capabilities(&self) -> &ash::vk::SurfaceCapabilitiesKHR {
-        &self.capabilities
+    // the first supported format in `preferred`, the first supported format otherwise
+    pub fn choose_format(
+        &self,
+        preferred: &[(ash::vk::Format, ash::vk::ColorSpaceKHR)],
+    ) -> ash::vk::SurfaceFormatKHR {
+        let mut preferred =
+            preferred
+                .iter()
+                .map(|(format, color_space)| ash::vk::SurfaceFormatKHR {
+                    format: *format,
+                    color_space: *color_space,
+                });
+        match self.supported_formats.as_slice() {
+            // a single UNDEFINED format means the surface has no preferred format
+            [only] if only.format == ash::vk::Format::UNDEFINED => {
+                preferred.next().unwrap_or(*only)
+            }
+            formats => preferred
+                .find(|format| formats.contains(format))
+                .unwrap_or(formats[0]),
+        }
+    }
+    // B8G8R8A8_SRGB with SRGB_NONLINEAR if supported, the first supported format otherwise
+    pub fn preferred_format(&self) -> ash::vk::SurfaceFormatKHR {
+        self.choose_format(&[(
+            ash::vk::Format::B8G8R8A8_SRGB,
+            ash::vk::ColorSpaceKHR::SRGB_NONLINEAR,
+        )])
+    }
+    pub fn get_physical_device_surface_capabilities(&self) -> ash::vk::SurfaceCapabilitiesKHR {
+        *self.capabilities.read()
+    }
+    // re-query the capabilities (e.g. current_extent after a resize) and cache the result
+    pub fn refresh_capabilities(&self) -> Result<ash::vk::SurfaceCapabilitiesKHR, ash::vk::Result> {
+        // Done VUID-vkGetPhysicalDeviceSurfaceCapabilitiesKHR-surface-06523
+        // Done VUID-vkGetPhysicalDeviceSurfaceCapabilitiesKHR-surface-06211
+        // Host Synchronization: none
+        let capabilities = unsafe {
+            self.loader.get_physical_device_surface_capabilities(
+                self.physical_device.vk_physical_device,
+                self.vk_surface_khr,
+            )?
+        };
+        *self.capabilities.write() = capabilities;
+        Ok(capabilities)
     }
     pub fn get_physical_device_capabilities2<
         T: ash::vk::ExtendsPhysicalDeviceSurfaceInfo2KHR + Default,
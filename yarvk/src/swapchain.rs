@@ -1,16 +1,20 @@
 use crate::device::Device;
+use crate::device_features::{
+    Feature, PhysicalDevicePresentIdFeaturesKHR, PhysicalDevicePresentWaitFeaturesKHR,
+};
 use crate::extensions::{DeviceExtension, PhysicalDeviceExtensionType};
-use crate::fence::{SignalingFence, UnsignaledFence};
+use crate::fence::{SignaledFence, SignalingFence, UnsignaledFence};
 use crate::image::State::Bound;
 use crate::image::{Image, ImageCreateInfo, ImageFormatListCreateInfo};
 use crate::physical_device::SharingMode;
 use crate::queue::Queue;
-use crate::semaphore::Semaphore;
+use crate::semaphore::{Semaphore, WaitResult};
 use crate::surface::Surface;
 use ash::vk::Handle;
 use parking_lot::{RwLock};
 
 use std::mem::ManuallyDrop;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 pub struct SwapchainBuilder {
@@ -144,11 +148,39 @@ impl SwapchainBuilder {
         self
     }
 
+    pub fn full_screen_exclusive(
+        mut self,
+        _extension: DeviceExtension<{ PhysicalDeviceExtensionType::ExtFullScreenExclusive }>,
+        full_screen_exclusive: ash::vk::FullScreenExclusiveEXT,
+    ) -> Self {
+        // TODO VUID-VkSwapchainCreateInfoKHR-pNext-02679
+        self.full_screen_exclusive = Some(full_screen_exclusive);
+        self
+    }
+
     pub fn build(self) -> Result<Arc<Swapchain>, ash::vk::Result> {
         let image_create_info = Arc::new(self.get_image_create_info());
         // Done VUID-VkSwapchainCreateInfoKHR-surface-01270
         // Done VUID-VkSwapchainCreateInfoKHR-imageUsage-parameter
         // Done VUID-VkSwapchainCreateInfoKHR-imageUsage-requiredbitmask
+        // MUST VUID-VkSwapchainCreateInfoKHR-presentMode-01427
+        match self.present_mode {
+            ash::vk::PresentModeKHR::IMMEDIATE
+            | ash::vk::PresentModeKHR::MAILBOX
+            | ash::vk::PresentModeKHR::FIFO
+            | ash::vk::PresentModeKHR::FIFO_RELAXED => {
+                if !self
+                    .surface
+                    .get_physical_device_surface_capabilities()
+                    .supported_usage_flags
+                    .contains(self.image_usage)
+                {
+                    panic!("VUID-VkSwapchainCreateInfoKHR-presentMode-01427");
+                }
+            }
+            // TODO VUID-VkSwapchainCreateInfoKHR-imageUsage-01384
+            _ => {}
+        }
         let mut create_info = ash::vk::SwapchainCreateInfoKHR::builder()
             .min_image_count(self.min_image_count)
             .image_format(self.image_format)
@@ -226,6 +258,8 @@ impl SwapchainBuilder {
             swapchain_loader,
             // image_create_info,
             images,
+            last_present_id: AtomicU64::new(0),
+            full_screen_exclusive: self.full_screen_exclusive,
         }))
     }
 }
@@ -234,13 +268,17 @@ pub struct PresentInfo {
     wait_semaphores: Vec<Arc<Semaphore>>,
     swapchains_and_image_indices: Vec<(Arc<Swapchain>, u32)>,
     results: Vec<ash::vk::Result>,
+    present_regions: Option<Vec<Vec<ash::vk::RectLayerKHR>>>,
+    present_ids: Option<Vec<u64>>,
 }
 
 impl PresentInfo {
     pub fn builder()-> PresentInfoBuilder {
         PresentInfoBuilder {
             wait_semaphores: vec![],
-            swapchains_and_image_indices: vec![]
+            swapchains_and_image_indices: vec![],
+            present_regions: None,
+            present_id: None,
         }
     }
 }
@@ -248,6 +286,8 @@ impl PresentInfo {
 pub struct PresentInfoBuilder {
     wait_semaphores: Vec<Arc<Semaphore>>,
     swapchains_and_image_indices: Vec<(Arc<Swapchain>, u32)>,
+    present_regions: Option<Vec<Vec<ash::vk::RectLayerKHR>>>,
+    present_id: Option<u64>,
 }
 
 impl PresentInfoBuilder {
@@ -259,24 +299,79 @@ impl PresentInfoBuilder {
     pub fn add_swapchain_and_image(mut self, swapchian: Arc<Swapchain>, image: &Image) -> Self {
         let image_index = swapchian.get_image_index(image)
             .expect("Each element of pImageIndices must be the index of a presentable image acquired from the swapchain specified by the corresponding element of the pSwapchains array");
+        // a swapchain can only present one image per present
+        if self
+            .swapchains_and_image_indices
+            .iter()
+            .any(|(added, _)| Arc::ptr_eq(added, &swapchian))
+        {
+            panic!("swapchain is already added to this present");
+        }
 
         self.swapchains_and_image_indices
             .push((swapchian, image_index));
         self
     }
 
+    // `swapchain_index` is the index of the swapchain added by `add_swapchain_and_image`, only
+    // the `rects` of the image are changed since the last present
+    pub fn add_present_region(
+        mut self,
+        _extension: DeviceExtension<{ PhysicalDeviceExtensionType::KhrIncrementalPresent }>,
+        swapchain_index: usize,
+        rects: &[ash::vk::Rect2D],
+    ) -> Self {
+        if swapchain_index >= self.swapchains_and_image_indices.len() {
+            panic!("swapchain is not added by add_swapchain_and_image");
+        }
+        let present_regions = self.present_regions.get_or_insert_with(Vec::new);
+        if present_regions.len() <= swapchain_index {
+            present_regions.resize(swapchain_index + 1, Vec::new());
+        }
+        for rect in rects {
+            // TODO VUID-VkRectLayerKHR-offset-04864
+            present_regions[swapchain_index].push(ash::vk::RectLayerKHR {
+                offset: rect.offset,
+                extent: rect.extent,
+                layer: 0,
+            });
+        }
+        self
+    }
+
+    // the id is used for all swapchains of this present, it must be greater than the ids of
+    // previous presents to these swapchains
+    pub fn present_id(
+        mut self,
+        _feature: Feature<{ PhysicalDevicePresentIdFeaturesKHR::PresentId.into() }>,
+        present_id: u64,
+    ) -> Self {
+        self.present_id = Some(present_id);
+        self
+    }
+
     pub fn build(self) -> PresentInfo {
         let image_counts = self.swapchains_and_image_indices.len();
         let _semaphore_counts = self.wait_semaphores.len();
+        // DONE VUID-VkPresentRegionsKHR-swapchainCount-01260
+        let present_regions = self.present_regions.map(|mut present_regions| {
+            present_regions.resize(image_counts, Vec::new());
+            present_regions
+        });
         PresentInfo {
             wait_semaphores: self.wait_semaphores,
             swapchains_and_image_indices: self.swapchains_and_image_indices,
             results: vec![ash::vk::Result::SUCCESS; image_counts],
+            present_regions,
+            present_ids: self
+                .present_id
+                .map(|present_id| vec![present_id; image_counts]),
         }
     }
 }
 
 impl Queue {
+    // the results are per swapchain, in the order they are added by `add_swapchain_and_image`
     pub fn queue_present<'a>(
         &mut self,
         present_info: &'a mut PresentInfo,
@@ -303,12 +398,42 @@ impl Queue {
             swapchain_locks.push(lock);
         }
 
-        let ash_vk_present_info = ash::vk::PresentInfoKHR::builder()
+        let mut ash_vk_present_info = ash::vk::PresentInfoKHR::builder()
             .wait_semaphores(ash_vk_semaphores.as_slice())
             .image_indices(image_indices.as_slice())
             .swapchains(ash_vk_swapchains.as_slice())
-            .results(present_info.results.as_mut_slice())
-            .build();
+            .results(present_info.results.as_mut_slice());
+        let ash_vk_present_regions;
+        let mut ash_vk_present_regions_info;
+        if let Some(present_regions) = &present_info.present_regions {
+            ash_vk_present_regions = present_regions
+                .iter()
+                .map(|rects| ash::vk::PresentRegionKHR::builder().rectangles(rects).build())
+                .collect::<Vec<_>>();
+            ash_vk_present_regions_info = ash::vk::PresentRegionsKHR::builder()
+                .regions(ash_vk_present_regions.as_slice())
+                .build();
+            ash_vk_present_info = ash_vk_present_info.push_next(&mut ash_vk_present_regions_info);
+        }
+        let mut ash_vk_present_id;
+        if let Some(present_ids) = &present_info.present_ids {
+            for ((swapchain, _), present_id) in
+                present_info.swapchains_and_image_indices.iter().zip(present_ids)
+            {
+                // MUST VUID-VkPresentIdKHR-presentIds-04999
+                if *present_id != 0
+                    && *present_id <= swapchain.last_present_id.load(Ordering::Relaxed)
+                {
+                    panic!("VUID-VkPresentIdKHR-presentIds-04999");
+                }
+            }
+            // DONE VUID-VkPresentIdKHR-swapchainCount-04998
+            ash_vk_present_id = ash::vk::PresentIdKHR::builder()
+                .present_ids(present_ids.as_slice())
+                .build();
+            ash_vk_present_info = ash_vk_present_info.push_next(&mut ash_vk_present_id);
+        }
+        let ash_vk_present_info = ash_vk_present_info.build();
 
             // MUST VUID-VkPresentInfoKHR-swapchainCount-arraylength
             let loader = &present_info
@@ -317,10 +442,33 @@ impl Queue {
                 .expect("swapchainCount must be greater than 0")
                 .0
                 .swapchain_loader;
+            let _queues = queue.device.queues_lock.read();
             unsafe {
                 // Host Synchronization: queue, semaphores, swapchains
-                // TODO suboptimal
-                let _suboptimal = loader.queue_present(queue.vk_queue, &ash_vk_present_info)?;
+                // errors of single swapchains (e.g. OUT_OF_DATE) are returned in the results, so
+                // one swapchain doesn't hide the results of the others, SUBOPTIMAL as well
+                match loader.queue_present(queue.vk_queue, &ash_vk_present_info) {
+                    Ok(_)
+                    | Err(ash::vk::Result::ERROR_OUT_OF_DATE_KHR)
+                    | Err(ash::vk::Result::ERROR_SURFACE_LOST_KHR)
+                    | Err(ash::vk::Result::ERROR_FULL_SCREEN_EXCLUSIVE_MODE_LOST_EXT) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+            // a failed present doesn't consume its id
+            if let Some(present_ids) = &present_info.present_ids {
+                for (((swapchain, _), present_id), result) in present_info
+                    .swapchains_and_image_indices
+                    .iter()
+                    .zip(present_ids)
+                    .zip(&present_info.results)
+                {
+                    if *result == ash::vk::Result::SUCCESS
+                        || *result == ash::vk::Result::SUBOPTIMAL_KHR
+                    {
+                        swapchain.last_present_id.store(*present_id, Ordering::Relaxed);
+                    }
+                }
             }
 
         Ok(present_info.results.as_slice())
@@ -335,6 +483,8 @@ pub struct Swapchain {
     swapchain_loader: ash::extensions::khr::Swapchain,
     // image_create_info: Arc<ImageCreateInfo>,
     images: Vec<Arc<Image<{ Bound }>>>,
+    last_present_id: AtomicU64,
+    full_screen_exclusive: Option<ash::vk::FullScreenExclusiveEXT>,
 }
 
 impl Swapchain {
@@ -365,6 +515,88 @@ impl Swapchain {
     pub fn get_swapchain_images<'a>(self: &'a Arc<Self>) -> &'a [Arc<Image<{ Bound }>>] {
         self.images.as_slice()
     }
+    // wait until the present with `present_id` is displayed
+    pub fn wait_for_present(
+        &self,
+        _feature: Feature<{ PhysicalDevicePresentWaitFeaturesKHR::PresentWait.into() }>,
+        present_id: u64,
+        timeout: u64,
+    ) -> Result<WaitResult, ash::vk::Result> {
+        // Host Synchronization: swapchain
+        // the handle is copied out instead of holding the lock for the whole wait, which would
+        // block `queue_present` of this swapchain on other threads until the wait returns
+        let vk_swapchain = *self.vk_swapchain.read();
+        let result = unsafe {
+            self.device
+                .present_wait_loader()
+                .wait_for_present(vk_swapchain, present_id, timeout)
+        };
+        match result {
+            Ok(()) => Ok(WaitResult::Signaled),
+            Err(ash::vk::Result::TIMEOUT) => Ok(WaitResult::Timeout),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn acquire_full_screen_exclusive_mode(
+        &self,
+        _extension: &DeviceExtension<{ PhysicalDeviceExtensionType::ExtFullScreenExclusive }>,
+    ) -> Result<(), ash::vk::Result> {
+        // DONE VUID-vkAcquireFullScreenExclusiveModeEXT-swapchain-02674
+        // MUST VUID-vkAcquireFullScreenExclusiveModeEXT-swapchain-02675
+        if self.full_screen_exclusive
+            != Some(ash::vk::FullScreenExclusiveEXT::APPLICATION_CONTROLLED)
+        {
+            panic!("VUID-vkAcquireFullScreenExclusiveModeEXT-swapchain-02675");
+        }
+        // TODO VUID-vkAcquireFullScreenExclusiveModeEXT-swapchain-02676
+        unsafe {
+            // Host Synchronization: swapchain
+            let vk_swapchain = self.vk_swapchain.write();
+            self.device
+                .full_screen_exclusive_loader()
+                .acquire_full_screen_exclusive_mode(*vk_swapchain)
+        }
+    }
+
+    pub fn release_full_screen_exclusive_mode(
+        &self,
+        _extension: &DeviceExtension<{ PhysicalDeviceExtensionType::ExtFullScreenExclusive }>,
+    ) -> Result<(), ash::vk::Result> {
+        // DONE VUID-vkReleaseFullScreenExclusiveModeEXT-swapchain-02677
+        // MUST VUID-vkReleaseFullScreenExclusiveModeEXT-swapchain-02678
+        if self.full_screen_exclusive
+            != Some(ash::vk::FullScreenExclusiveEXT::APPLICATION_CONTROLLED)
+        {
+            panic!("VUID-vkReleaseFullScreenExclusiveModeEXT-swapchain-02678");
+        }
+        unsafe {
+            // Host Synchronization: swapchain
+            let vk_swapchain = self.vk_swapchain.write();
+            self.device
+                .full_screen_exclusive_loader()
+                .release_full_screen_exclusive_mode(*vk_swapchain)
+        }
+    }
+
+    pub fn get_status(
+        &self,
+        _extension: &DeviceExtension<{ PhysicalDeviceExtensionType::KhrSharedPresentableImage }>,
+    ) -> Result<SwapchainStatus, ash::vk::Result> {
+        let fp = self.device.shared_presentable_image_fn();
+        let result = unsafe {
+            // Host Synchronization: swapchain
+            let vk_swapchain = self.vk_swapchain.write();
+            (fp.get_swapchain_status_khr)(self.device.ash_device.handle(), *vk_swapchain)
+        };
+        match result {
+            ash::vk::Result::SUCCESS => Ok(SwapchainStatus::Success),
+            ash::vk::Result::SUBOPTIMAL_KHR => Ok(SwapchainStatus::Suboptimal),
+            ash::vk::Result::ERROR_OUT_OF_DATE_KHR => Ok(SwapchainStatus::OutOfDate),
+            e => Err(e),
+        }
+    }
+
     pub(crate) fn get_image_index(&self, image: &Image) -> Option<u32> {
         let handle = image.vk_image.as_raw();
         for i in 0..self.images.len() {
@@ -383,61 +615,235 @@ impl Swapchain {
         timeout: u64,
         semaphore: &Semaphore,
         fence: UnsignaledFence,
-    ) -> Result<(Arc<Image<{ Bound }>>, SignalingFence<()>), ash::vk::Result> {
-        unsafe {
+    ) -> Result<AcquireResult<ImageAndFence, UnsignaledFence>, ash::vk::Result> {
+        let result = unsafe {
             // Host Synchronization: swapchain semaphore fence
             let vk_swapchain = self.vk_swapchain.write();
             let vk_semaphore = semaphore.ash_vk_semaphore.write();
-            let (index, _) = self.swapchain_loader.acquire_next_image(
+            self.swapchain_loader.acquire_next_image(
                 *vk_swapchain,
                 timeout,
                 *vk_semaphore,
                 fence.vk_fence,
-            )?;
-            let image = self.images[index as usize].clone();
-            let fence = fence.to_executing_fence(());
-            Ok((image, fence))
-        }
+            )
+        };
+        Ok(match AcquireResult::from_vk(result, fence)? {
+            AcquireResult::Acquired {
+                image: (index, fence),
+                suboptimal,
+            } => AcquireResult::Acquired {
+                image: (
+                    self.images[index as usize].clone(),
+                    fence.to_executing_fence(()),
+                ),
+                suboptimal,
+            },
+            AcquireResult::NotReady(fence) => AcquireResult::NotReady(fence),
+            AcquireResult::Timeout(fence) => AcquireResult::Timeout(fence),
+        })
     }
 
     pub fn acquire_next_image_semaphore_only(
         self: &Arc<Self>,
         timeout: u64,
         semaphore: &Semaphore,
-    ) -> Result<Arc<Image<{ Bound }>>, ash::vk::Result> {
-        unsafe {
+    ) -> Result<AcquireResult<Arc<Image<{ Bound }>>>, ash::vk::Result> {
+        let result = unsafe {
             // Host Synchronization: swapchain, semaphore, fence
             let vk_swapchain = self.vk_swapchain.write();
             let vk_semaphore = semaphore.ash_vk_semaphore.write();
-            let (index, _) = self.swapchain_loader.acquire_next_image(
+            self.swapchain_loader.acquire_next_image(
                 *vk_swapchain,
                 timeout,
                 *vk_semaphore,
                 ash::vk::Fence::null(),
-            )?;
-            let image = self.images[index as usize].clone();
-            Ok(image)
-        }
+            )
+        };
+        Ok(match AcquireResult::from_vk(result, ())? {
+            AcquireResult::Acquired {
+                image: (index, _),
+                suboptimal,
+            } => AcquireResult::Acquired {
+                image: self.images[index as usize].clone(),
+                suboptimal,
+            },
+            AcquireResult::NotReady(_) => AcquireResult::NotReady(()),
+            AcquireResult::Timeout(_) => AcquireResult::Timeout(()),
+        })
     }
 
     pub fn acquire_next_image_fence_only(
         self: &Arc<Self>,
         timeout: u64,
         fence: UnsignaledFence,
-    ) -> Result<(Arc<Image<{ Bound }>>, SignalingFence<()>), ash::vk::Result> {
+    ) -> Result<AcquireResult<ImageAndFence, UnsignaledFence>, ash::vk::Result> {
         let vk_swapchain = self.vk_swapchain.write();
-        let (index, _) = unsafe {
+        let result = unsafe {
             // Host Synchronization: swapchain, semaphore, fence
             self.swapchain_loader.acquire_next_image(
                 *vk_swapchain,
                 timeout,
                 ash::vk::Semaphore::null(),
                 fence.vk_fence,
-            )?
+            )
         };
-        let image = self.images[index as usize].clone();
-        let fence = fence.to_executing_fence(());
-        Ok((image, fence))
+        Ok(match AcquireResult::from_vk(result, fence)? {
+            AcquireResult::Acquired {
+                image: (index, fence),
+                suboptimal,
+            } => AcquireResult::Acquired {
+                image: (
+                    self.images[index as usize].clone(),
+                    fence.to_executing_fence(()),
+                ),
+                suboptimal,
+            },
+            AcquireResult::NotReady(fence) => AcquireResult::NotReady(fence),
+            AcquireResult::Timeout(fence) => AcquireResult::Timeout(fence),
+        })
+    }
+
+    // for callers that decide at runtime what to signal, the returned fence is Some if `fence`
+    // is Some
+    pub fn acquire_next_image(
+        self: &Arc<Self>,
+        timeout: u64,
+        semaphore: Option<&Semaphore>,
+        fence: Option<UnsignaledFence>,
+    ) -> Result<AcquireResult<ImageAndOptionalFence, Option<UnsignaledFence>>, ash::vk::Result> {
+        Ok(match (semaphore, fence) {
+            (Some(semaphore), Some(fence)) => {
+                match self.acquire_next_image_both(timeout, semaphore, fence)? {
+                    AcquireResult::Acquired {
+                        image: (image, fence),
+                        suboptimal,
+                    } => AcquireResult::Acquired {
+                        image: (image, Some(fence)),
+                        suboptimal,
+                    },
+                    AcquireResult::NotReady(fence) => AcquireResult::NotReady(Some(fence)),
+                    AcquireResult::Timeout(fence) => AcquireResult::Timeout(Some(fence)),
+                }
+            }
+            (Some(semaphore), None) => {
+                match self.acquire_next_image_semaphore_only(timeout, semaphore)? {
+                    AcquireResult::Acquired { image, suboptimal } => AcquireResult::Acquired {
+                        image: (image, None),
+                        suboptimal,
+                    },
+                    AcquireResult::NotReady(_) => AcquireResult::NotReady(None),
+                    AcquireResult::Timeout(_) => AcquireResult::Timeout(None),
+                }
+            }
+            (None, Some(fence)) => match self.acquire_next_image_fence_only(timeout, fence)? {
+                AcquireResult::Acquired {
+                    image: (image, fence),
+                    suboptimal,
+                } => AcquireResult::Acquired {
+                    image: (image, Some(fence)),
+                    suboptimal,
+                },
+                AcquireResult::NotReady(fence) => AcquireResult::NotReady(Some(fence)),
+                AcquireResult::Timeout(fence) => AcquireResult::Timeout(Some(fence)),
+            },
+            // MUST VUID-vkAcquireNextImageKHR-semaphore-01780
+            (None, None) => panic!("VUID-vkAcquireNextImageKHR-semaphore-01780"),
+        })
+    }
+}
+
+pub enum SwapchainStatus {
+    Success,
+    // the swapchain still presents but no longer matches the surface properties exactly
+    Suboptimal,
+    // the swapchain must be recreated
+    OutOfDate,
+}
+
+pub type ImageAndFence = (Arc<Image<{ Bound }>>, SignalingFence<()>);
+pub type ImageAndOptionalFence = (Arc<Image<{ Bound }>>, Option<SignalingFence<()>>);
+
+// NotReady and Timeout give back the resource passed to the acquire function (the unsignaled
+// fence if any), since no image is acquired and nothing will be signaled.
+pub enum AcquireResult<T, U = ()> {
+    // `suboptimal` is VK_SUBOPTIMAL_KHR, the image can still be presented but the swapchain
+    // should be recreated
+    Acquired { image: T, suboptimal: bool },
+    // timeout is 0 and no image is available
+    NotReady(U),
+    Timeout(U),
+}
+
+impl<U> AcquireResult<(u32, U), U> {
+    fn from_vk(
+        result: Result<(u32, bool), ash::vk::Result>,
+        u: U,
+    ) -> Result<Self, ash::vk::Result> {
+        match result {
+            Ok((index, suboptimal)) => Ok(AcquireResult::Acquired {
+                image: (index, u),
+                suboptimal,
+            }),
+            Err(ash::vk::Result::NOT_READY) => Ok(AcquireResult::NotReady(u)),
+            Err(ash::vk::Result::TIMEOUT) => Ok(AcquireResult::Timeout(u)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<T, U> AcquireResult<T, U> {
+    pub fn acquired(self) -> Option<T> {
+        match self {
+            AcquireResult::Acquired { image, .. } => Some(image),
+            _ => None,
+        }
+    }
+}
+
+// Track the fence of the last submission rendering into each swapchain image, so an image
+// acquired while it is still in flight can be waited before being rendered again.
+pub struct SwapchainImageFences<T> {
+    pub swapchain: Arc<Swapchain>,
+    fences: Vec<Option<SignalingFence<T>>>,
+}
+
+impl<T> SwapchainImageFences<T> {
+    pub fn new(swapchain: Arc<Swapchain>) -> Self {
+        let fences = swapchain.images.iter().map(|_| None).collect();
+        Self { swapchain, fences }
+    }
+
+    pub fn set_image_fence(&mut self, image: &Image, fence: SignalingFence<T>) {
+        let index = self
+            .swapchain
+            .get_image_index(image)
+            .expect("image is not acquired from this swapchain");
+        if self.fences[index as usize].is_some() {
+            panic!("previous fence of the image must be waited before setting a new one");
+        }
+        self.fences[index as usize] = Some(fence);
+    }
+
+    pub fn wait_for_image(
+        &mut self,
+        image: &Image,
+    ) -> Result<Option<(SignaledFence, T)>, ash::vk::Result> {
+        let index = self
+            .swapchain
+            .get_image_index(image)
+            .expect("image is not acquired from this swapchain");
+        match self.fences[index as usize].take() {
+            Some(fence) => Ok(Some(fence.wait()?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T> Drop for SwapchainImageFences<T> {
+    fn drop(&mut self) {
+        for fence in self.fences.drain(..).flatten() {
+            let _ = fence.wait();
+        }
     }
 }
 
@@ -0,0 +1,321 @@
+use crate::barrier::ImageMemoryBarrier;
+use crate::buffer::Buffer;
+use crate::command::command_buffer::Level::PRIMARY;
+use crate::command::command_pool::{CommandPool, CommandPoolCreateFlags};
+use crate::device::Device;
+use crate::device_memory::DeviceMemory;
+use crate::error::Error;
+use crate::fence::Fence;
+use crate::image::image_subresource_range::ImageSubresourceRange;
+use crate::image::image_view::{ImageView, ImageViewType};
+use crate::image::Image;
+use crate::physical_device::SharingMode;
+use crate::pipeline::pipeline_stage_flags::PipelineStageFlags;
+use crate::queue::{Queue, SubmitInfo};
+use crate::sampler::Sampler;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum TextureLoadError {
+    Image(image::ImageError),
+    Vulkan(Error),
+}
+
+impl From<image::ImageError> for TextureLoadError {
+    fn from(error: image::ImageError) -> Self {
+        TextureLoadError::Image(error)
+    }
+}
+
+impl From<Error> for TextureLoadError {
+    fn from(error: Error) -> Self {
+        TextureLoadError::Vulkan(error)
+    }
+}
+
+impl From<ash::vk::Result> for TextureLoadError {
+    fn from(error: ash::vk::Result) -> Self {
+        TextureLoadError::Vulkan(error.into())
+    }
+}
+
+pub enum ColorSpace {
+    Srgb,
+    Linear,
+}
+
+impl ColorSpace {
+    fn rgba8_format(&self) -> ash::vk::Format {
+        match self {
+            ColorSpace::Srgb => ash::vk::Format::R8G8B8A8_SRGB,
+            ColorSpace::Linear => ash::vk::Format::R8G8B8A8_UNORM,
+        }
+    }
+}
+
+pub struct Texture {
+    pub image: Arc<Image>,
+    pub image_view: Arc<ImageView>,
+    pub sampler: Arc<Sampler>,
+    _memory: DeviceMemory,
+}
+
+impl Texture {
+    pub fn load_from_file<P: AsRef<Path>>(
+        queue: &mut Queue,
+        path: P,
+        color_space: ColorSpace,
+    ) -> Result<Texture, TextureLoadError> {
+        Self::load(queue, image::open(path)?, color_space)
+    }
+
+    pub fn load_from_memory(
+        queue: &mut Queue,
+        bytes: &[u8],
+        color_space: ColorSpace,
+    ) -> Result<Texture, TextureLoadError> {
+        Self::load(queue, image::load_from_memory(bytes)?, color_space)
+    }
+
+    fn load(
+        queue: &mut Queue,
+        dynamic_image: image::DynamicImage,
+        color_space: ColorSpace,
+    ) -> Result<Texture, TextureLoadError> {
+        let device = queue.device.clone();
+        let rgba = dynamic_image.to_rgba8();
+        let (width, height) = rgba.dimensions();
+        let data = rgba.into_raw();
+        let format = color_space.rgba8_format();
+        let mip_levels = if supports_linear_blit(&device, format) {
+            32 - width.max(height).leading_zeros()
+        } else {
+            1
+        };
+        let memory_properties = device.physical_device.memory_properties();
+
+        let staging_buffer = Buffer::builder(device.clone())
+            .size(data.len() as _)
+            .usage(ash::vk::BufferUsageFlags::TRANSFER_SRC)
+            .sharing_mode(SharingMode::EXCLUSIVE)
+            .build()?;
+        let staging_requirements = staging_buffer.get_buffer_memory_requirements();
+        let staging_property_flags = ash::vk::MemoryPropertyFlags::HOST_VISIBLE
+            | ash::vk::MemoryPropertyFlags::HOST_COHERENT;
+        let staging_memory_type = memory_properties
+            .find_memory_type(&staging_requirements, staging_property_flags)
+            .ok_or(Error::NoMemoryType {
+                memory_type_bits: staging_requirements.memory_type_bits,
+                property_flags: staging_property_flags,
+            })?;
+        let mut staging_memory = DeviceMemory::builder(staging_memory_type, device.clone())
+            .allocation_size(staging_requirements.size)
+            .build()?;
+        staging_memory.map_memory(0, staging_requirements.size, |mapped| {
+            mapped[..data.len()].copy_from_slice(data.as_slice());
+        })?;
+        let staging_buffer = staging_buffer.bind_memory(&staging_memory, 0)?;
+
+        let image = Image::builder(device.clone())
+            .image_type(ash::vk::ImageType::TYPE_2D)
+            .format(format)
+            .extent(ash::vk::Extent3D {
+                width,
+                height,
+                depth: 1,
+            })
+            .mip_levels(mip_levels)
+            .array_layers(1)
+            .samples(ash::vk::SampleCountFlags::TYPE_1)
+            .tiling(ash::vk::ImageTiling::OPTIMAL)
+            .usage(
+                ash::vk::ImageUsageFlags::TRANSFER_SRC
+                    | ash::vk::ImageUsageFlags::TRANSFER_DST
+                    | ash::vk::ImageUsageFlags::SAMPLED,
+            )
+            .sharing_mode(SharingMode::EXCLUSIVE)
+            .build()?;
+        let image_requirements = image.get_image_memory_requirements();
+        let image_memory_type = memory_properties
+            .find_memory_type(
+                &image_requirements,
+                ash::vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            )
+            .ok_or(Error::NoMemoryType {
+                memory_type_bits: image_requirements.memory_type_bits,
+                property_flags: ash::vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            })?;
+        let memory = DeviceMemory::builder(image_memory_type, device.clone())
+            .allocation_size(image_requirements.size)
+            .build()?;
+        let image = image.bind_memory(&memory, 0)?;
+
+        let command_pool = CommandPool::builder(queue.queue_family.clone(), device.clone())
+            .add_flag(CommandPoolCreateFlags::TRANSIENT)
+            .build()?;
+        let command_buffer = command_pool
+            .allocate_command_buffers::<{ PRIMARY }>(1)?
+            .pop()
+            .unwrap();
+        let command_buffer = command_buffer.record(
+            ash::vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT,
+            |command_buffer| {
+                command_buffer.cmd_pipeline_barrier(
+                    &[PipelineStageFlags::TopOfPipe],
+                    &[PipelineStageFlags::Transfer],
+                    ash::vk::DependencyFlags::empty(),
+                    &[],
+                    &[],
+                    &[ImageMemoryBarrier::builder(image.clone())
+                        .dst_access_mask(ash::vk::AccessFlags::TRANSFER_WRITE)
+                        .old_layout(ash::vk::ImageLayout::UNDEFINED)
+                        .new_layout(ash::vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                        .subresource_range(color_range(0, mip_levels))
+                        .build()],
+                );
+                command_buffer.cmd_copy_buffer_to_image(
+                    staging_buffer.clone(),
+                    image.clone(),
+                    ash::vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[ash::vk::BufferImageCopy::builder()
+                        .image_subresource(color_layers(0))
+                        .image_extent(ash::vk::Extent3D {
+                            width,
+                            height,
+                            depth: 1,
+                        })
+                        .build()],
+                );
+                let mut src_width = width as i32;
+                let mut src_height = height as i32;
+                for level in 1..mip_levels {
+                    let dst_width = (src_width / 2).max(1);
+                    let dst_height = (src_height / 2).max(1);
+                    command_buffer.cmd_pipeline_barrier(
+                        &[PipelineStageFlags::Transfer],
+                        &[PipelineStageFlags::Transfer],
+                        ash::vk::DependencyFlags::empty(),
+                        &[],
+                        &[],
+                        &[ImageMemoryBarrier::builder(image.clone())
+                            .src_access_mask(ash::vk::AccessFlags::TRANSFER_WRITE)
+                            .dst_access_mask(ash::vk::AccessFlags::TRANSFER_READ)
+                            .old_layout(ash::vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                            .new_layout(ash::vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                            .subresource_range(color_range(level - 1, 1))
+                            .build()],
+                    );
+                    command_buffer.cmd_blit_image(
+                        image.clone(),
+                        ash::vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                        image.clone(),
+                        ash::vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                        &[ash::vk::ImageBlit::builder()
+                            .src_subresource(color_layers(level - 1))
+                            .src_offsets([
+                                ash::vk::Offset3D::default(),
+                                ash::vk::Offset3D {
+                                    x: src_width,
+                                    y: src_height,
+                                    z: 1,
+                                },
+                            ])
+                            .dst_subresource(color_layers(level))
+                            .dst_offsets([
+                                ash::vk::Offset3D::default(),
+                                ash::vk::Offset3D {
+                                    x: dst_width,
+                                    y: dst_height,
+                                    z: 1,
+                                },
+                            ])
+                            .build()],
+                        ash::vk::Filter::LINEAR,
+                    );
+                    command_buffer.cmd_pipeline_barrier(
+                        &[PipelineStageFlags::Transfer],
+                        &[PipelineStageFlags::FragmentShader],
+                        ash::vk::DependencyFlags::empty(),
+                        &[],
+                        &[],
+                        &[ImageMemoryBarrier::builder(image.clone())
+                            .src_access_mask(ash::vk::AccessFlags::TRANSFER_READ)
+                            .dst_access_mask(ash::vk::AccessFlags::SHADER_READ)
+                            .old_layout(ash::vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                            .new_layout(ash::vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                            .subresource_range(color_range(level - 1, 1))
+                            .build()],
+                    );
+                    src_width = dst_width;
+                    src_height = dst_height;
+                }
+                command_buffer.cmd_pipeline_barrier(
+                    &[PipelineStageFlags::Transfer],
+                    &[PipelineStageFlags::FragmentShader],
+                    ash::vk::DependencyFlags::empty(),
+                    &[],
+                    &[],
+                    &[ImageMemoryBarrier::builder(image.clone())
+                        .src_access_mask(ash::vk::AccessFlags::TRANSFER_WRITE)
+                        .dst_access_mask(ash::vk::AccessFlags::SHADER_READ)
+                        .old_layout(ash::vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                        .new_layout(ash::vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                        .subresource_range(color_range(mip_levels - 1, 1))
+                        .build()],
+                );
+            },
+        )?;
+        let mut submit_info = SubmitInfo::new();
+        submit_info.add_command_buffer(command_buffer);
+        let fence = queue.submit(Fence::new(device.clone())?, vec![submit_info])?;
+        fence.wait()?;
+
+        let image_view = ImageView::builder(image.clone())
+            .view_type(ImageViewType::Type2d)
+            .format(format)
+            .subresource_range(color_range(0, mip_levels))
+            .build()?;
+        let sampler = Sampler::builder(device)
+            .mag_filter(ash::vk::Filter::LINEAR)
+            .min_filter(ash::vk::Filter::LINEAR)
+            .mipmap_mode(ash::vk::SamplerMipmapMode::LINEAR)
+            .address_mode_u(ash::vk::SamplerAddressMode::REPEAT)
+            .address_mode_v(ash::vk::SamplerAddressMode::REPEAT)
+            .address_mode_w(ash::vk::SamplerAddressMode::REPEAT)
+            .max_lod(mip_levels as f32)
+            .build()?;
+        Ok(Texture {
+            image,
+            image_view,
+            sampler,
+            _memory: memory,
+        })
+    }
+}
+
+fn supports_linear_blit(device: &Device, format: ash::vk::Format) -> bool {
+    let format_properties = device.physical_device.get_format_properties(format);
+    format_properties.optimal_tiling_features.contains(
+        ash::vk::FormatFeatureFlags::BLIT_SRC
+            | ash::vk::FormatFeatureFlags::BLIT_DST
+            | ash::vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR,
+    )
+}
+
+fn color_range(base_mip_level: u32, level_count: u32) -> ImageSubresourceRange {
+    ImageSubresourceRange::builder()
+        .aspect_mask(ash::vk::ImageAspectFlags::COLOR)
+        .base_mip_level(base_mip_level)
+        .level_count(level_count)
+        .layer_count(1)
+        .build()
+}
+
+fn color_layers(mip_level: u32) -> ash::vk::ImageSubresourceLayers {
+    ash::vk::ImageSubresourceLayers::builder()
+        .aspect_mask(ash::vk::ImageAspectFlags::COLOR)
+        .mip_level(mip_level)
+        .layer_count(1)
+        .build()
+}
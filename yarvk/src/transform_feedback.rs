@@ -0,0 +1,324 @@
+use crate::buffer::Buffer;
+use crate::command::command_buffer::RenderPassScope::INSIDE;
+use crate::command::command_buffer::State::RECORDING;
+use crate::command::command_buffer::{CommandBuffer, Level, RenderPassScope};
+use crate::device::Device;
+use crate::device_features::Feature;
+use crate::device_features::PhysicalDeviceTransformFeedbackFeaturesEXT::TransformFeedback;
+use crate::extensions::DeviceExtension;
+use crate::extensions::PhysicalDeviceExtensionType::ExtTransformFeedback;
+use crate::physical_device::PhysicalDevice;
+use ash::vk::Handle;
+use derive_more::Deref;
+use smallvec::SmallVec;
+use std::sync::Arc;
+
+// queried once when the device is created, `p_next` is cleared so it can be kept by the device
+#[derive(Deref)]
+pub(crate) struct TransformFeedbackProperties(
+    ash::vk::PhysicalDeviceTransformFeedbackPropertiesEXT,
+);
+
+unsafe impl Send for TransformFeedbackProperties {}
+unsafe impl Sync for TransformFeedbackProperties {}
+
+impl TransformFeedbackProperties {
+    pub(crate) fn new(physical_device: &PhysicalDevice) -> Self {
+        let mut properties = physical_device.get_physical_device_properties2::<
+            ash::vk::PhysicalDeviceTransformFeedbackPropertiesEXT,
+        >();
+        properties.p_next = std::ptr::null_mut();
+        TransformFeedbackProperties(properties)
+    }
+}
+
+impl<const LEVEL: Level, const SCOPE: RenderPassScope> CommandBuffer<LEVEL, { RECORDING }, SCOPE> {
+    // `sizes` of None binds the whole range after each offset
+    // DONE VUID-vkCmdBindTransformFeedbackBuffersEXT-commandBuffer-recording
+    // DONE VUID-vkCmdBindTransformFeedbackBuffersEXT-transformFeedback-02355
+    // DONE VUID-vkCmdBindTransformFeedbackBuffersEXT-pBuffers-02364
+    // TODO VUID-vkCmdBindTransformFeedbackBuffersEXT-None-02365
+    pub fn cmd_bind_transform_feedback_buffers(
+        &mut self,
+        first_binding: u32,
+        buffers: &[Arc<Buffer>],
+        offsets: &[ash::vk::DeviceSize],
+        sizes: Option<&[ash::vk::DeviceSize]>,
+        _extension: &DeviceExtension<{ ExtTransformFeedback }>,
+        _feature: &Feature<{ TransformFeedback.into() }>,
+    ) {
+        // MUST VUID-vkCmdBindTransformFeedbackBuffersEXT-bindingCount-arraylength
+        if buffers.is_empty() || offsets.len() != buffers.len() {
+            panic!("VUID-vkCmdBindTransformFeedbackBuffersEXT-bindingCount-arraylength");
+        }
+        if let Some(sizes) = sizes {
+            if sizes.len() != buffers.len() {
+                panic!("VUID-vkCmdBindTransformFeedbackBuffersEXT-bindingCount-arraylength");
+            }
+        }
+        let properties = self.device.transform_feedback_properties();
+        // MUST VUID-vkCmdBindTransformFeedbackBuffersEXT-firstBinding-02356
+        if first_binding >= properties.max_transform_feedback_buffers {
+            panic!("VUID-vkCmdBindTransformFeedbackBuffersEXT-firstBinding-02356");
+        }
+        // MUST VUID-vkCmdBindTransformFeedbackBuffersEXT-firstBinding-02357
+        if first_binding + buffers.len() as u32 > properties.max_transform_feedback_buffers {
+            panic!("VUID-vkCmdBindTransformFeedbackBuffersEXT-firstBinding-02357");
+        }
+        for (index, buffer) in buffers.iter().enumerate() {
+            let offset = offsets[index];
+            // MUST VUID-vkCmdBindTransformFeedbackBuffersEXT-pOffsets-02358
+            if offset >= buffer.size {
+                panic!("VUID-vkCmdBindTransformFeedbackBuffersEXT-pOffsets-02358");
+            }
+            // MUST VUID-vkCmdBindTransformFeedbackBuffersEXT-pOffsets-02359
+            if offset % 4 != 0 {
+                panic!("VUID-vkCmdBindTransformFeedbackBuffersEXT-pOffsets-02359");
+            }
+            // MUST VUID-vkCmdBindTransformFeedbackBuffersEXT-pBuffers-02360
+            if !buffer
+                .usage
+                .contains(ash::vk::BufferUsageFlags::TRANSFORM_FEEDBACK_BUFFER_EXT)
+            {
+                panic!("VUID-vkCmdBindTransformFeedbackBuffersEXT-pBuffers-02360");
+            }
+            if let Some(sizes) = sizes {
+                let size = sizes[index];
+                if size != ash::vk::WHOLE_SIZE {
+                    // MUST VUID-vkCmdBindTransformFeedbackBuffersEXT-pSize-02361
+                    if size > buffer.size {
+                        panic!("VUID-vkCmdBindTransformFeedbackBuffersEXT-pSize-02361");
+                    }
+                    // MUST VUID-vkCmdBindTransformFeedbackBuffersEXT-pOffsets-02362
+                    if offset + size > buffer.size {
+                        panic!("VUID-vkCmdBindTransformFeedbackBuffersEXT-pOffsets-02362");
+                    }
+                    // MUST VUID-vkCmdBindTransformFeedbackBuffersEXT-pSizes-02363
+                    if size > properties.max_transform_feedback_buffer_size {
+                        panic!("VUID-vkCmdBindTransformFeedbackBuffersEXT-pSizes-02363");
+                    }
+                }
+            }
+        }
+        let ash_vk_buffers: SmallVec<[ash::vk::Buffer; 4]> =
+            buffers.iter().map(|buffer| buffer.ash_vk_buffer).collect();
+        let fp = self.device.transform_feedback_fn();
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            (fp.cmd_bind_transform_feedback_buffers_ext)(
+                self.vk_command_buffer,
+                first_binding,
+                buffers.len() as u32,
+                ash_vk_buffers.as_ptr(),
+                offsets.as_ptr(),
+                sizes.map_or(std::ptr::null(), |sizes| sizes.as_ptr()),
+            );
+        }
+        for buffer in buffers {
+            self.holding_resources
+                .write_buffers
+                .insert(buffer.ash_vk_buffer.as_raw(), buffer.clone());
+        }
+    }
+}
+
+// checks shared by begin and end, the VUIDs only differ by number
+fn check_counter_buffers(
+    device: &Device,
+    first_counter_buffer: u32,
+    counter_buffers: &[(Arc<Buffer>, ash::vk::DeviceSize)],
+    vuids: [&'static str; 4],
+) {
+    let properties = device.transform_feedback_properties();
+    if first_counter_buffer >= properties.max_transform_feedback_buffers {
+        panic!("{}", vuids[0]);
+    }
+    if first_counter_buffer + counter_buffers.len() as u32
+        > properties.max_transform_feedback_buffers
+    {
+        panic!("{}", vuids[1]);
+    }
+    for (buffer, offset) in counter_buffers {
+        if offset + 4 > buffer.size {
+            panic!("{}", vuids[2]);
+        }
+        if !buffer
+            .usage
+            .contains(ash::vk::BufferUsageFlags::TRANSFORM_FEEDBACK_COUNTER_BUFFER_EXT)
+        {
+            panic!("{}", vuids[3]);
+        }
+    }
+}
+
+impl<const LEVEL: Level> CommandBuffer<LEVEL, { RECORDING }, { INSIDE }> {
+    // `counter_buffers` hold the byte counts to resume from, pass an empty slice to start
+    // capturing from the beginning of the bound transform feedback buffers
+    // DONE VUID-vkCmdBeginTransformFeedbackEXT-commandBuffer-recording
+    // DONE VUID-vkCmdBeginTransformFeedbackEXT-renderpass
+    // DONE VUID-vkCmdBeginTransformFeedbackEXT-transformFeedback-02366
+    // TODO VUID-vkCmdBeginTransformFeedbackEXT-None-02367
+    // TODO VUID-vkCmdBeginTransformFeedbackEXT-None-02373
+    // TODO VUID-vkCmdBeginTransformFeedbackEXT-None-06233
+    pub fn cmd_begin_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: &[(Arc<Buffer>, ash::vk::DeviceSize)],
+        _extension: &DeviceExtension<{ ExtTransformFeedback }>,
+        _feature: &Feature<{ TransformFeedback.into() }>,
+    ) {
+        // MUST VUID-vkCmdBeginTransformFeedbackEXT-firstCounterBuffer-02368
+        // MUST VUID-vkCmdBeginTransformFeedbackEXT-firstCounterBuffer-02369
+        // MUST VUID-vkCmdBeginTransformFeedbackEXT-pCounterBufferOffsets-02370
+        // MUST VUID-vkCmdBeginTransformFeedbackEXT-pCounterBuffers-02372
+        check_counter_buffers(
+            &self.device,
+            first_counter_buffer,
+            counter_buffers,
+            [
+                "VUID-vkCmdBeginTransformFeedbackEXT-firstCounterBuffer-02368",
+                "VUID-vkCmdBeginTransformFeedbackEXT-firstCounterBuffer-02369",
+                "VUID-vkCmdBeginTransformFeedbackEXT-pCounterBufferOffsets-02370",
+                "VUID-vkCmdBeginTransformFeedbackEXT-pCounterBuffers-02372",
+            ],
+        );
+        let (ash_vk_buffers, offsets): (
+            SmallVec<[ash::vk::Buffer; 4]>,
+            SmallVec<[ash::vk::DeviceSize; 4]>,
+        ) = counter_buffers
+            .iter()
+            .map(|(buffer, offset)| (buffer.ash_vk_buffer, *offset))
+            .unzip();
+        let fp = self.device.transform_feedback_fn();
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            (fp.cmd_begin_transform_feedback_ext)(
+                self.vk_command_buffer,
+                first_counter_buffer,
+                counter_buffers.len() as u32,
+                ash_vk_buffers.as_ptr(),
+                offsets.as_ptr(),
+            );
+        }
+        for (buffer, _) in counter_buffers {
+            self.holding_resources
+                .read_buffers
+                .insert(buffer.ash_vk_buffer.as_raw(), buffer.clone());
+        }
+    }
+
+    // `counter_buffers` receive the byte counts written, to resume capturing or to draw with
+    // `cmd_draw_indirect_byte_count` later
+    // DONE VUID-vkCmdEndTransformFeedbackEXT-commandBuffer-recording
+    // DONE VUID-vkCmdEndTransformFeedbackEXT-renderpass
+    // DONE VUID-vkCmdEndTransformFeedbackEXT-transformFeedback-02374
+    // TODO VUID-vkCmdEndTransformFeedbackEXT-None-02375
+    pub fn cmd_end_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: &[(Arc<Buffer>, ash::vk::DeviceSize)],
+        _extension: &DeviceExtension<{ ExtTransformFeedback }>,
+        _feature: &Feature<{ TransformFeedback.into() }>,
+    ) {
+        // MUST VUID-vkCmdEndTransformFeedbackEXT-firstCounterBuffer-02376
+        // MUST VUID-vkCmdEndTransformFeedbackEXT-firstCounterBuffer-02377
+        // MUST VUID-vkCmdEndTransformFeedbackEXT-pCounterBufferOffsets-02378
+        // MUST VUID-vkCmdEndTransformFeedbackEXT-pCounterBuffers-02380
+        check_counter_buffers(
+            &self.device,
+            first_counter_buffer,
+            counter_buffers,
+            [
+                "VUID-vkCmdEndTransformFeedbackEXT-firstCounterBuffer-02376",
+                "VUID-vkCmdEndTransformFeedbackEXT-firstCounterBuffer-02377",
+                "VUID-vkCmdEndTransformFeedbackEXT-pCounterBufferOffsets-02378",
+                "VUID-vkCmdEndTransformFeedbackEXT-pCounterBuffers-02380",
+            ],
+        );
+        let (ash_vk_buffers, offsets): (
+            SmallVec<[ash::vk::Buffer; 4]>,
+            SmallVec<[ash::vk::DeviceSize; 4]>,
+        ) = counter_buffers
+            .iter()
+            .map(|(buffer, offset)| (buffer.ash_vk_buffer, *offset))
+            .unzip();
+        let fp = self.device.transform_feedback_fn();
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            (fp.cmd_end_transform_feedback_ext)(
+                self.vk_command_buffer,
+                first_counter_buffer,
+                counter_buffers.len() as u32,
+                ash_vk_buffers.as_ptr(),
+                offsets.as_ptr(),
+            );
+        }
+        for (buffer, _) in counter_buffers {
+            self.holding_resources
+                .write_buffers
+                .insert(buffer.ash_vk_buffer.as_raw(), buffer.clone());
+        }
+    }
+
+    // Draw the vertices captured into `counter_buffer` by `cmd_end_transform_feedback`
+    // DONE VUID-vkCmdDrawIndirectByteCountEXT-commandBuffer-recording
+    // DONE VUID-vkCmdDrawIndirectByteCountEXT-renderpass
+    // DONE VUID-vkCmdDrawIndirectByteCountEXT-transformFeedback-02287
+    #[allow(clippy::too_many_arguments)]
+    pub fn cmd_draw_indirect_byte_count(
+        &mut self,
+        instance_count: u32,
+        first_instance: u32,
+        counter_buffer: Arc<Buffer>,
+        counter_buffer_offset: ash::vk::DeviceSize,
+        counter_offset: u32,
+        vertex_stride: u32,
+        _extension: &DeviceExtension<{ ExtTransformFeedback }>,
+        _feature: &Feature<{ TransformFeedback.into() }>,
+    ) {
+        let properties = self.device.transform_feedback_properties();
+        // MUST VUID-vkCmdDrawIndirectByteCountEXT-transformFeedbackDraw-02288
+        if properties.transform_feedback_draw == ash::vk::FALSE {
+            panic!("VUID-vkCmdDrawIndirectByteCountEXT-transformFeedbackDraw-02288");
+        }
+        // MUST VUID-vkCmdDrawIndirectByteCountEXT-vertexStride-02289
+        if vertex_stride == 0
+            || vertex_stride > properties.max_transform_feedback_buffer_data_stride
+        {
+            panic!("VUID-vkCmdDrawIndirectByteCountEXT-vertexStride-02289");
+        }
+        // MUST VUID-vkCmdDrawIndirectByteCountEXT-counterBuffer-02290
+        if !counter_buffer
+            .usage
+            .contains(ash::vk::BufferUsageFlags::INDIRECT_BUFFER)
+        {
+            panic!("VUID-vkCmdDrawIndirectByteCountEXT-counterBuffer-02290");
+        }
+        // MUST VUID-vkCmdDrawIndirectByteCountEXT-counterBufferOffset-04568
+        if counter_buffer_offset % 4 != 0 {
+            panic!("VUID-vkCmdDrawIndirectByteCountEXT-counterBufferOffset-04568");
+        }
+        #[cfg(debug_assertions)]
+        self.check_bound_descriptor_sets(ash::vk::PipelineBindPoint::GRAPHICS);
+        let fp = self.device.transform_feedback_fn();
+        unsafe {
+            // Host Synchronization: commandBuffer, VkCommandPool
+            let _pool = self.command_pool.vk_command_pool.write();
+            (fp.cmd_draw_indirect_byte_count_ext)(
+                self.vk_command_buffer,
+                instance_count,
+                first_instance,
+                counter_buffer.ash_vk_buffer,
+                counter_buffer_offset,
+                counter_offset,
+                vertex_stride,
+            );
+        }
+        self.holding_resources
+            .read_buffers
+            .insert(counter_buffer.ash_vk_buffer.as_raw(), counter_buffer);
+    }
+}
@@ -6,8 +6,9 @@ use winit::event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEve
 use winit::event_loop::{ControlFlow, EventLoop};
 use winit::window::WindowBuilder;
 use yarvk::barrier::ImageMemoryBarrier;
-use yarvk::buffer::Buffer;
-use yarvk::command::command_buffer::Level::PRIMARY;
+use yarvk::buffer::{Buffer, IndexType};
+use yarvk::command::command_buffer::CommandBufferInheritanceInfo;
+use yarvk::command::command_buffer::Level::{PRIMARY, SECONDARY};
 use yarvk::command::command_pool::{CommandPool, CommandPoolCreateFlags};
 use yarvk::debug_utils_messenger::DebugUtilsMessengerCreateInfoEXT;
 use yarvk::descriptor_pool::{
@@ -29,6 +30,7 @@ use yarvk::image::image_view::{ImageView, ImageViewType};
 use yarvk::image::Image;
 use yarvk::image::State::Bound;
 use yarvk::instance::{ApplicationInfo, Instance};
+use yarvk::physical_device::device_selector::{DeviceSelector, SelectedDevice};
 use yarvk::physical_device::memory_properties::{MemoryType, PhysicalDeviceMemoryProperties};
 use yarvk::physical_device::SharingMode;
 use yarvk::pipeline::color_blend_state::{
@@ -46,28 +48,26 @@ use yarvk::pipeline::vertex_input_state::{
     PipelineVertexInputStateCreateInfo, VertexInputAttributeDescription,
     VertexInputBindingDescription,
 };
-use yarvk::pipeline::viewport_state::PipelineViewportStateCreateInfo;
+use yarvk::pipeline::viewport_state::{PipelineViewportStateCreateInfo, Rect2DExt, ViewportExt};
 use yarvk::pipeline::{Pipeline, PipelineLayout};
 use yarvk::queue::SubmitInfo;
 use yarvk::render_pass::attachment::{AttachmentDescription, AttachmentReference};
-use yarvk::render_pass::render_pass_begin_info::RenderPassBeginInfo;
+use yarvk::render_pass::render_pass_begin_info::{ClearValueExt, RenderPassBeginInfo};
 use yarvk::render_pass::subpass::{SubpassDependency, SubpassDescription};
 use yarvk::render_pass::RenderPass;
 use yarvk::sampler::Sampler;
 use yarvk::semaphore::Semaphore;
 use yarvk::shader_module::ShaderModule;
-use yarvk::surface::Surface;
-use yarvk::swapchain::{PresentInfo, Swapchain};
+use yarvk::swapchain::{AcquireResult, PresentInfo, Swapchain};
 use yarvk::window::enumerate_required_extensions;
 use yarvk::{read_spv};
 use yarvk::{
-    AccessFlags, AttachmentLoadOp, AttachmentStoreOp, BlendOp, BorderColor, BufferImageCopy,
-    BufferUsageFlags, ClearColorValue, ClearDepthStencilValue, ClearValue, ColorComponentFlags,
+    AccessFlags, AttachmentLoadOp, AttachmentStoreOp, BlendOp, BorderColor, BufferUsageFlags,
+    ClearValue, ColorComponentFlags,
     CommandBufferUsageFlags, CompareOp, ComponentMapping, ComponentSwizzle, CompositeAlphaFlagsKHR,
     DebugUtilsMessageSeverityFlagsEXT, DependencyFlags, DescriptorPoolSize, DescriptorType,
-    Extent2D, Extent3D, Filter, Format, FrontFace, ImageAspectFlags, ImageLayout,
-    ImageSubresourceLayers, ImageTiling, ImageType, ImageUsageFlags, IndexType,
-    MemoryPropertyFlags, MemoryRequirements, PipelineBindPoint, PresentModeKHR, QueueFlags, Rect2D,
+    Extent2D, Filter, Format, FrontFace, ImageAspectFlags, ImageLayout, ImageTiling, ImageType,
+    ImageUsageFlags, MemoryPropertyFlags, MemoryRequirements, PipelineBindPoint, PresentModeKHR, QueueFlags, Rect2D,
     SampleCountFlags, SamplerAddressMode, SamplerMipmapMode, StencilOp, StencilOpState,
     SubpassContents, SurfaceTransformFlagsKHR, VertexInputRate, Viewport, SUBPASS_EXTERNAL,
 };
@@ -96,6 +96,15 @@ pub struct Vector3 {
     pub _pad: f32,
 }
 
+// Resources shared by the threads recording secondary command buffers
+struct DrawResources {
+    pipeline_layout: Arc<PipelineLayout>,
+    pipeline: Pipeline,
+    descriptor_sets: Vec<Arc<DescriptorSet>>,
+    vertex_input_buffer: Arc<Buffer>,
+    index_buffer: Arc<Buffer>,
+}
+
 pub fn find_memory_type_index(
     memory_req: &MemoryRequirements,
     memory_prop: &PhysicalDeviceMemoryProperties,
@@ -153,8 +162,10 @@ fn main() {
         .build();
     let mut instance_builder = Instance::builder(entry.clone())
         .application_info(application_info)
-        .add_layer(layer)
         .debug_utils_messenger_exts(vec![debug_utils_messenger_callback]);
+    if entry.has_layer(layer).unwrap() {
+        instance_builder = instance_builder.add_layer(layer);
+    }
     for ext in surface_extensions {
         instance_builder = instance_builder.add_extension(&ext);
     }
@@ -163,37 +174,20 @@ fn main() {
         .get_extension::<{ PhysicalInstanceExtensionType::KhrSurface }>()
         .unwrap();
     let pdevices = instance.enumerate_physical_devices().unwrap();
-    let (pdevice, queue_family, surface) = pdevices
-        .iter()
-        .filter_map(|pdevice| {
-            pdevice
-                .get_physical_device_queue_family_properties()
-                .into_iter()
-                .filter_map(|queue_family_properties| {
-                    if let Some(surface) = Surface::get_physical_device_surface_support(
-                        khr_surface_ext.clone(),
-                        &window,
-                        &queue_family_properties,
-                    )
-                    .unwrap()
-                    {
-                        if queue_family_properties
-                            .property
-                            .queue_flags
-                            .contains(QueueFlags::GRAPHICS)
-                        {
-                            return Some((pdevice, queue_family_properties, surface));
-                        }
-                    }
-                    None
-                })
-                .next()
-        })
-        .next()
+    let SelectedDevice {
+        physical_device: pdevice,
+        queue_family,
+        surface,
+    } = DeviceSelector::builder()
+        .queue_flags(QueueFlags::GRAPHICS)
+        .surface_support(khr_surface_ext, &window)
+        .build()
+        .select(&pdevices)
+        .unwrap()
         .expect("Couldn't find suitable device.");
+    let surface = surface.unwrap();
     // let portable_property = pdevice.get_physical_device_properties2::<PhysicalDevicePortabilitySubsetPropertiesKHR>();
     // println!("min_vertex_input_binding_stride_alignment: {}", portable_property.min_vertex_input_binding_stride_alignment);
-    // let prop2_ext = instance.get_extension::<{ PhysicalInstanceExtensionType::KhrGetPhysicalDeviceProperties2 }>().unwrap();
     let surface_ext = instance
         .get_extension::<{ PhysicalInstanceExtensionType::KhrSurface }>()
         .unwrap();
@@ -211,7 +205,7 @@ fn main() {
         .get_extension::<{ PhysicalDeviceExtensionType::KhrSwapchain }>()
         .unwrap();
     let mut present_queue = queues.get_mut(&queue_family).unwrap().pop().unwrap();
-    let surface_format = surface.get_physical_device_surface_formats()[0];
+    let surface_format = surface.preferred_format();
     let surface_capabilities = surface.get_physical_device_surface_capabilities();
     let mut desired_image_count = surface_capabilities.min_image_count + 1;
     if surface_capabilities.max_image_count > 0
@@ -253,7 +247,7 @@ fn main() {
         .image_array_layers(1)
         .build()
         .unwrap();
-    let pool = CommandPool::builder(queue_family, device.clone())
+    let pool = CommandPool::builder(queue_family.clone(), device.clone())
         // do not need, yarvk enable reset feature by default
         .add_flag(CommandPoolCreateFlags::ResetCommandBuffer)
         .build()
@@ -328,21 +322,15 @@ fn main() {
                 DependencyFlags::empty(),
                 &[],
                 &[],
-                &[ImageMemoryBarrier::builder(depth_image.clone())
-                    .dst_access_mask(
-                        AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ
-                            | AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
-                    )
-                    .new_layout(ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL)
-                    .old_layout(ImageLayout::UNDEFINED)
-                    .subresource_range(
-                        ImageSubresourceRange::builder()
-                            .aspect_mask(ImageAspectFlags::DEPTH)
-                            .layer_count(1)
-                            .level_count(1)
-                            .build(),
-                    )
-                    .build()],
+                &[ImageMemoryBarrier::transition(
+                    depth_image.clone(),
+                    ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                )
+                .dst_access_mask(
+                    AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ
+                        | AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                )
+                .build()],
             );
         })
         .unwrap();
@@ -358,7 +346,7 @@ fn main() {
         .take_invalid_buffers()
         .pop()
         .unwrap()
-        .reset()
+        .reset(true)
         .unwrap();
 
     let depth_image_view = ImageView::builder(depth_image.clone())
@@ -569,31 +557,6 @@ fn main() {
     let image_extent = Extent2D { width, height };
     let image_data = image.into_raw();
 
-    let image_buffer = Buffer::builder(device.clone())
-        .size(image_data.len() as _)
-        .usage(BufferUsageFlags::TRANSFER_SRC)
-        .sharing_mode(SharingMode::EXCLUSIVE)
-        .build()
-        .unwrap();
-    let image_buffer_memory_req = image_buffer.get_buffer_memory_requirements();
-    let image_buffer_memory_index = find_memory_type_index(
-        &image_buffer_memory_req,
-        &device_memory_properties,
-        MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT,
-    )
-    .expect("Unable to find suitable memorytype for the vertex buffer.");
-
-    let mut image_buffer_memory = DeviceMemory::builder(image_buffer_memory_index, device.clone())
-        .allocation_size(image_buffer_memory_req.size)
-        .build()
-        .unwrap();
-    image_buffer_memory
-        .map_memory(0, image_buffer_memory_req.size, |mut_slice| {
-            mut_slice[0..image_data.len()].copy_from_slice(image_data.as_slice());
-        })
-        .unwrap();
-    let image_buffer = image_buffer.bind_memory(&image_buffer_memory, 0).unwrap();
-
     let texture_image = Image::builder(device.clone())
         .image_type(ImageType::TYPE_2D)
         .format(Format::R8G8B8A8_UNORM)
@@ -624,69 +587,14 @@ fn main() {
 
     let command_buffer = setup_command_buffer
         .record(CommandBufferUsageFlags::ONE_TIME_SUBMIT, |command_buffer| {
-            let texture_barrier = ImageMemoryBarrier::builder(texture_image.clone())
-                .dst_access_mask(AccessFlags::TRANSFER_WRITE)
-                .new_layout(ImageLayout::TRANSFER_DST_OPTIMAL)
-                .subresource_range(
-                    ImageSubresourceRange::builder()
-                        .aspect_mask(ImageAspectFlags::COLOR)
-                        .level_count(1)
-                        .layer_count(1)
-                        .build(),
-                )
-                .build();
-            let mut image_barriers = Vec::with_capacity(1);
-            image_barriers.push(texture_barrier);
-            command_buffer.cmd_pipeline_barrier(
-                &[PipelineStageFlags::BottomOfPipe],
-                &[PipelineStageFlags::Transfer],
-                DependencyFlags::empty(),
-                &[],
-                &[],
-                &image_barriers,
-            );
-            let buffer_copy_regions = BufferImageCopy::builder()
-                .image_subresource(
-                    ImageSubresourceLayers::builder()
-                        .aspect_mask(ImageAspectFlags::COLOR)
-                        .layer_count(1)
-                        .build(),
-                )
-                .image_extent(Extent3D {
-                    width,
-                    height,
-                    depth: 1,
-                });
-
-            command_buffer.cmd_copy_buffer_to_image(
-                image_buffer.clone(),
-                texture_image.clone(),
-                ImageLayout::TRANSFER_DST_OPTIMAL,
-                &[buffer_copy_regions.build()],
-            );
-            let texture_barrier_end = ImageMemoryBarrier::builder(texture_image.clone())
-                .src_access_mask(AccessFlags::TRANSFER_WRITE)
-                .dst_access_mask(AccessFlags::SHADER_READ)
-                .old_layout(ImageLayout::TRANSFER_DST_OPTIMAL)
-                .new_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
-                .subresource_range(
-                    ImageSubresourceRange::builder()
-                        .aspect_mask(ImageAspectFlags::COLOR)
-                        .level_count(1)
-                        .layer_count(1)
-                        .build(),
+            command_buffer
+                .cmd_upload_image(
+                    texture_image.clone(),
+                    &image_data,
+                    ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                    PipelineStageFlags::FragmentShader,
                 )
-                .build();
-            let mut image_barriers = Vec::with_capacity(1);
-            image_barriers.push(texture_barrier_end);
-            command_buffer.cmd_pipeline_barrier(
-                &[PipelineStageFlags::Transfer],
-                &[PipelineStageFlags::FragmentShader],
-                DependencyFlags::empty(),
-                &[],
-                &[],
-                &image_barriers,
-            );
+                .unwrap();
         })
         .unwrap();
     let mut submit_info = SubmitInfo::new();
@@ -703,7 +611,7 @@ fn main() {
         .address_mode_u(SamplerAddressMode::MIRRORED_REPEAT)
         .address_mode_v(SamplerAddressMode::MIRRORED_REPEAT)
         .address_mode_w(SamplerAddressMode::MIRRORED_REPEAT)
-        // .max_anisotropy(1.0)
+        // .max_anisotropy(1.0, device.get_feature::<{ PhysicalDeviceFeatures::SamplerAnisotropy.into() }>().unwrap())
         .border_color(BorderColor::FLOAT_OPAQUE_WHITE)
         .compare_op(CompareOp::NEVER)
         .build()
@@ -853,18 +761,11 @@ fn main() {
         .vertex_input_state(vertex_input_state_info)
         .viewport_state(
             PipelineViewportStateCreateInfo::builder()
-                .viewport(Viewport {
-                    x: 0.0,
-                    y: 0.0,
-                    width: surface_resolution.width as f32,
-                    height: surface_resolution.height as f32,
-                    min_depth: 0.0,
-                    max_depth: 1.0,
-                })
-                .scissor(Rect2D {
-                    extent: surface_resolution,
-                    ..Default::default()
-                })
+                .viewport(Viewport::reverse_z(
+                    surface_resolution.width as f32,
+                    surface_resolution.height as f32,
+                ))
+                .scissor(Rect2D::from_extent(surface_resolution))
                 .build(),
         )
         .input_assembly_state(
@@ -888,7 +789,7 @@ fn main() {
             PipelineDepthStencilStateCreateInfo::builder()
                 .depth_test_enable()
                 .depth_write_enable()
-                .depth_compare_op(CompareOp::LESS_OR_EQUAL)
+                .depth_compare_op(CompareOp::GREATER_OR_EQUAL)
                 .front(noop_stencil_state.clone())
                 .back(noop_stencil_state.clone())
                 .depth_bounds(0.0, 1.0)
@@ -916,6 +817,22 @@ fn main() {
     let present_complete_semaphore = Semaphore::new(device.clone()).unwrap();
     let rendering_complete_semaphore = Semaphore::new(device.clone()).unwrap();
     let mut submit_info_holder = Some(SubmitInfo::new());
+    let draw_resources = Arc::new(DrawResources {
+        pipeline_layout,
+        pipeline: graphic_pipeline,
+        descriptor_sets,
+        vertex_input_buffer,
+        index_buffer,
+    });
+    // one pool per recording thread, each thread draws one triangle of the quad
+    let secondary_pools: Vec<Arc<CommandPool>> = (0..index_buffer_data.len() / 3)
+        .map(|_| {
+            CommandPool::builder(queue_family.clone(), device.clone())
+                .add_flag(CommandPoolCreateFlags::TRANSIENT)
+                .build()
+                .unwrap()
+        })
+        .collect();
     event_loop.run(move |event, _, control_flow| {
         *control_flow = ControlFlow::Poll;
         match event {
@@ -934,66 +851,88 @@ fn main() {
                 ..
             } => {
                 *control_flow = ControlFlow::Exit;
-                present_queue.wait_idle().unwrap();
+                device.wait_idle().unwrap();
             }
             Event::MainEventsCleared => {
-                let image = swapchain
+                let image = match swapchain
                     .acquire_next_image_semaphore_only(
                         u64::MAX,
                         present_complete_semaphore.as_ref(),
                     )
-                    .unwrap();
+                    .unwrap()
+                {
+                    // a suboptimal image can still be presented
+                    AcquireResult::Acquired { image, .. } => image,
+                    // no image is available yet, skip this frame
+                    AcquireResult::NotReady(_) | AcquireResult::Timeout(_) => return,
+                };
                 let framebuffer = framebuffers.get(&image).unwrap();
                 let render_pass_begin_info =
                     RenderPassBeginInfo::builder(renderpass.clone(), framebuffer.clone())
                         .render_area(surface_resolution.into())
-                        .add_clear_value(ClearValue {
-                            color: ClearColorValue {
-                                float32: [0.0, 0.0, 0.0, 0.0],
-                            },
-                        })
-                        .add_clear_value(ClearValue {
-                            depth_stencil: ClearDepthStencilValue {
-                                depth: 1.0,
-                                stencil: 0,
-                            },
-                        })
+                        .add_clear_value(ClearValue::color_f32([0.0, 0.0, 0.0, 0.0]))
+                        .add_clear_value(ClearValue::depth_stencil(0.0, 0))
                         .build();
+                let inheritance_info = CommandBufferInheritanceInfo::builder()
+                    .render_pass(renderpass.clone(), subpass_id0)
+                    .framebuffer(framebuffer.clone())
+                    .build();
+                // spawn all threads before joining any of them
+                let mut workers = Vec::with_capacity(secondary_pools.len());
+                for (index, pool) in secondary_pools.iter().enumerate() {
+                    let command_buffer = pool
+                        .clone()
+                        .allocate_command_buffers::<{ SECONDARY }>(1)
+                        .unwrap()
+                        .pop()
+                        .unwrap();
+                    let inheritance_info = inheritance_info.clone();
+                    let draw_resources = draw_resources.clone();
+                    workers.push(std::thread::spawn(move || {
+                        command_buffer
+                            .record_render_pass_continue(
+                                CommandBufferUsageFlags::ONE_TIME_SUBMIT,
+                                inheritance_info,
+                                |command_buffer| {
+                                    command_buffer.cmd_bind_descriptor_sets(
+                                        PipelineBindPoint::GRAPHICS,
+                                        &draw_resources.pipeline_layout,
+                                        0,
+                                        &draw_resources.descriptor_sets[..],
+                                        &[],
+                                    );
+                                    command_buffer.cmd_bind_pipeline(
+                                        PipelineBindPoint::GRAPHICS,
+                                        &draw_resources.pipeline,
+                                    );
+                                    command_buffer.cmd_bind_vertex_buffers(
+                                        0,
+                                        &[draw_resources.vertex_input_buffer.clone()],
+                                        &[0],
+                                    );
+                                    command_buffer.cmd_bind_index_buffer(
+                                        draw_resources.index_buffer.clone(),
+                                        0,
+                                        IndexType::UINT32,
+                                    );
+                                    command_buffer.cmd_draw_indexed(3, 1, index as u32 * 3, 0, 1);
+                                },
+                            )
+                            .unwrap()
+                    }));
+                }
+                let secondary_command_buffers = workers
+                    .into_iter()
+                    .map(|worker| worker.join().unwrap())
+                    .collect();
                 let command_buffer = draw_command_buffer.take().unwrap();
                 let command_buffer = command_buffer
                     .record(CommandBufferUsageFlags::ONE_TIME_SUBMIT, |command_buffer| {
                         command_buffer.cmd_begin_render_pass(
                             &render_pass_begin_info,
-                            SubpassContents::INLINE,
+                            SubpassContents::SECONDARY_COMMAND_BUFFERS,
                             |command_buffer| {
-                                command_buffer.cmd_bind_descriptor_sets(
-                                    PipelineBindPoint::GRAPHICS,
-                                    &pipeline_layout,
-                                    0,
-                                    &descriptor_sets[..],
-                                    &[],
-                                );
-                                command_buffer.cmd_bind_pipeline(
-                                    PipelineBindPoint::GRAPHICS,
-                                    &graphic_pipeline,
-                                );
-                                command_buffer.cmd_bind_vertex_buffers(
-                                    0,
-                                    &[vertex_input_buffer.clone()],
-                                    &[0],
-                                );
-                                command_buffer.cmd_bind_index_buffer(
-                                    index_buffer.clone(),
-                                    0,
-                                    IndexType::UINT32,
-                                );
-                                command_buffer.cmd_draw_indexed(
-                                    index_buffer_data.len() as u32,
-                                    1,
-                                    0,
-                                    0,
-                                    1,
-                                );
+                                command_buffer.cmd_execute_commands(secondary_command_buffers);
                             },
                         );
                     })
@@ -1024,7 +963,7 @@ fn main() {
                     .take_invalid_buffers()
                     .pop()
                     .unwrap()
-                    .reset()
+                    .reset(false)
                     .unwrap();
                 submit_info_holder = Some(submit_info);
                 draw_command_buffer = Some(command_buffer);